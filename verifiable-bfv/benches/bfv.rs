@@ -0,0 +1,52 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::SeedableRng;
+use verifiable_bfv::bfv::{Plaintext, SecretKey, SizedCiphertext};
+
+const T: i64 = 16;
+const STD_DEV: f64 = 3.2;
+
+// `65537` supports the negacyclic NTT for all of these `N`, while `2^16` falls back to
+// schoolbook multiplication.
+const PARAMS: [(usize, i64); 4] = [(256, 65537), (1024, 65537), (4096, 65537), (256, 1 << 16)];
+
+fn bench_bfv_ops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bfv");
+    group.sample_size(10);
+    for (degree, q) in PARAMS {
+        let id = format!("N={degree}/q={q}");
+        let mut rng = rand::rngs::StdRng::seed_from_u64(55);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, STD_DEV, &mut rng);
+        let rlk_base = (q as f64).log2() as i64;
+        let rlk_1 = secret_key
+            .relin_key_gen_1(q, STD_DEV, &mut rng, rlk_base)
+            .unwrap();
+        let pt_1 = Plaintext::rand(degree, T, &mut rng);
+        let pt_2 = Plaintext::rand(degree, T, &mut rng);
+        let ct_1 = pt_1.encrypt(&public_key, STD_DEV, &mut rng);
+        let ct_2 = pt_2.encrypt(&public_key, STD_DEV, &mut rng);
+
+        group.bench_function(BenchmarkId::new("encrypt", &id), |b| {
+            b.iter(|| pt_1.encrypt(black_box(&public_key), STD_DEV, &mut rng))
+        });
+        group.bench_function(BenchmarkId::new("add", &id), |b| {
+            b.iter(|| black_box(&ct_1) + black_box(&ct_2))
+        });
+        group.bench_function(BenchmarkId::new("mul_plain", &id), |b| {
+            b.iter(|| black_box(&ct_1).mul_plain(black_box(&pt_2)))
+        });
+        // Without a relinearization key, `SizedCiphertext` multiplies with `basic_mul` only
+        let sized_1 = SizedCiphertext::new(ct_1.clone());
+        let sized_2 = SizedCiphertext::new(ct_2.clone());
+        group.bench_function(BenchmarkId::new("basic_mul", &id), |b| {
+            b.iter(|| (black_box(sized_1.clone()) * sized_2.clone()).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("mul_relin_1", &id), |b| {
+            b.iter(|| ct_1.clone() * (ct_2.clone(), black_box(&rlk_1)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_bfv_ops);
+criterion_main!(benches);
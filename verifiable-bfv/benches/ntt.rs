@@ -0,0 +1,51 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use plonky2::field::{goldilocks_field::GoldilocksField, types::Field};
+use rand::SeedableRng;
+use verifiable_bfv::{
+    bfv::{Plaintext, SecretKey},
+    ntt_params::params,
+    vbfv::{ntt_forward, prove_add_ciphertexts},
+};
+
+const D: usize = 2;
+const Q: u64 = 3329;
+type F = GoldilocksField;
+
+/// `ntt_forward` reads the root tables of `params`, so it runs at the `params::N` of the params
+/// file the crate is built with (see `ntt_params`), and other `N` are benchmarked by switching that
+/// file. The plain negacyclic NTT of `bfv` is benchmarked at several `N` by `mul_plain` in
+/// `benches/bfv.rs`.
+fn bench_ntt_forward(c: &mut Criterion) {
+    let input = params::TESTG.map(F::from_canonical_u64);
+    let mut group = c.benchmark_group(format!("ntt_forward/N={}", params::N));
+    group.bench_function("sequential", |b| {
+        b.iter(|| ntt_forward::<F, D, Q>(black_box(&input)))
+    });
+    #[cfg(feature = "rayon")]
+    group.bench_function("rayon", |b| {
+        b.iter(|| verifiable_bfv::vbfv::ntt_forward_par::<F, D, Q>(black_box(&input)))
+    });
+    group.finish();
+}
+
+/// The circuit is generated for `params::N` only, as the in-circuit NTT uses its root tables.
+fn bench_prove_add_ciphertexts(c: &mut Criterion) {
+    const N: usize = params::N;
+    let t = 16;
+    let std_dev = 3.2;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(55);
+    let secret_key = SecretKey::generate(N, &mut rng);
+    let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+    let ct_1 = Plaintext::rand(N, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+    let ct_2 = Plaintext::rand(N, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+
+    let mut group = c.benchmark_group(format!("prove_add_ciphertexts/N={N}/Q={Q}"));
+    group.sample_size(10);
+    group.bench_function("build_prove_verify", |b| {
+        b.iter(|| prove_add_ciphertexts::<N, Q>(ct_1.clone(), ct_2.clone()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_ntt_forward, bench_prove_add_ciphertexts);
+criterion_main!(benches);
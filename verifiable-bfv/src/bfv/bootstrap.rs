@@ -0,0 +1,205 @@
+use super::ciphertext::Ciphertext;
+use super::keys::{KeySwitchKey, SecretKey};
+use super::lwe::LweCiphertext;
+use super::poly::{rounded_div, Poly};
+use rand::{CryptoRng, RngCore};
+
+/// A GSW-style ciphertext `RGSW(mu)` of an integer `mu` under a secret key `s`
+///
+/// It is the pair of gadget encryptions of `mu` and `mu * s`, built as the key switching keys
+/// from `mu` and from `mu * s`. The external product with an RLWE ciphertext `(c_0, c_1)`
+/// switches `c_0` with the first one and `c_1` with the second, giving an encryption of
+/// `mu * (c_0 + c_1 * s)` with an additive noise.
+///
+/// * `c_mu` = the key switching key from `mu`
+/// * `c_mu_s` = the key switching key from `mu * s`
+#[derive(Clone, Debug)]
+pub struct GswCiphertext {
+    pub(crate) c_mu: KeySwitchKey,
+    pub(crate) c_mu_s: KeySwitchKey,
+}
+
+impl GswCiphertext {
+    /// Encrypt `mu` under the secret key of the negacyclic ring `sk`
+    ///
+    /// * `base`: the gadget decomposition base. The noise of an external product grows with it
+    ///   and with the top digit `q / base^{l-1}`, so small bases like `8` keep it low.
+    /// * `q`: the ciphertext modulus
+    pub fn encrypt<T: RngCore + CryptoRng>(
+        mu: i64,
+        sk: &SecretKey,
+        base: i64,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> GswCiphertext {
+        let degree = sk.poly.degree();
+        let mu_poly = Poly::new(vec![mu; 1]) % (q, degree);
+        let mu_s = sk.poly.mul_int_mod(mu, q);
+        GswCiphertext {
+            c_mu: KeySwitchKey::from_poly(&mu_poly, sk, base, q, std_dev, rng),
+            c_mu_s: KeySwitchKey::from_poly(&mu_s, sk, base, q, std_dev, rng),
+        }
+    }
+
+    /// Compute the external product `RGSW(mu) ⊠ ct`, which decrypts to `mu` times the message
+    /// of `ct`. The ciphertext must have the modulus of the GSW ciphertext.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 8;
+    /// # let q = 1 << 24;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{GswCiphertext, Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let gsw = GswCiphertext::encrypt(3, &secret_key, 1 << 4, q, std_dev, &mut rng);
+    ///
+    /// let pt = Plaintext::new(vec![1, 2, 0, 0, 0, 0, 0, 1], t);
+    /// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+    /// let product = gsw.external_product(&ct).decrypt(&secret_key);
+    /// assert_eq!(product, Plaintext::new(vec![3, 6, 0, 0, 0, 0, 0, 3], t));
+    /// ```
+    pub fn external_product(&self, ct: &Ciphertext) -> Ciphertext {
+        let mut ct = ct.clone();
+        ct.canonicalize();
+        let (c_00, c_01) = ct.key_switch_poly(ct.c_0.clone(), &self.c_mu);
+        let (c_10, c_11) = ct.key_switch_poly(ct.c_1.clone(), &self.c_mu_s);
+        let degree = ct.c_0.degree();
+        Ciphertext {
+            c_0: (c_00 + c_10) % (ct.q, degree),
+            c_1: (c_01 + c_11) % (ct.q, degree),
+            q: ct.q,
+            t: ct.t,
+            level: ct.level,
+        }
+    }
+
+    /// Return the gadget decomposition base
+    pub fn base(&self) -> i64 {
+        self.c_mu.base()
+    }
+}
+
+/// A bootstrapping key, the GSW ciphertexts `RGSW(s'_j)` of the coefficients of an LWE secret
+/// key `s'` under an RLWE secret key `s`
+///
+/// * `rgsw` = `[RGSW(s'_j) : j \in (0..n)]`
+/// * `q` = the ciphertext modulus of the RLWE side
+#[derive(Clone, Debug)]
+pub struct BootstrappingKey {
+    pub(crate) rgsw: Vec<GswCiphertext>,
+    pub(crate) q: i64,
+}
+
+impl BootstrappingKey {
+    /// Generate the bootstrapping key of the LWE secret key `lwe_sk`, which must be binary, under
+    /// the RLWE secret key `rlwe_sk` of the negacyclic ring
+    ///
+    /// * `base`: the gadget decomposition base, see `GswCiphertext::encrypt`
+    /// * `q`: the ciphertext modulus of the refreshed ciphertexts
+    pub fn generate<T: RngCore + CryptoRng>(
+        lwe_sk: &SecretKey,
+        rlwe_sk: &SecretKey,
+        base: i64,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> BootstrappingKey {
+        let rgsw = lwe_sk
+            .poly
+            .val()
+            .iter()
+            .map(|s_j| GswCiphertext::encrypt(*s_j, rlwe_sk, base, q, std_dev, rng))
+            .collect();
+        BootstrappingKey { rgsw, q }
+    }
+}
+
+/// Blindly rotate a test polynomial by the phase of an LWE ciphertext, returning a refreshed RLWE
+/// ciphertext whose constant coefficient encrypts `f(m)` for the message `m` of `lwe`
+///
+/// The phase `b + <a, s'>` is first switched to the modulus `2N` of the exponents of `X`, and
+/// shifted by half a step of `2N / t` so that the noise of the LWE ciphertext rounds away. The
+/// accumulator starts as the trivial encryption of `delta * TV * X^{-b}`, where the coefficient
+/// `k < N` of the test polynomial `TV` is `f(floor(k * t / 2N))`, and is multiplied by
+/// `X^{-a_j * s'_j}` with a CMux on `RGSW(s'_j)` for every `j`. Only the `N` first exponents
+/// are free, the others negate `TV` as `X^N = -1`, so the message must be in `[0, t/2)`.
+///
+/// This is the non-verifiable core of a programmable bootstrapping; its external products only
+/// use the negacyclic multiplications and NTT that the `NTTChip` constrains.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 32;
+/// # let q = 1 << 24;
+/// # let big_q = 2013265921;
+/// # let t = 8;
+/// #
+/// use verifiable_bfv::bfv::{blind_rotate, BootstrappingKey, Plaintext, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+/// let base = 1 << 3;
+/// let bsk = BootstrappingKey::generate(&secret_key, &secret_key, base, big_q, std_dev, &mut rng);
+///
+/// let mut msg = vec![0; degree];
+/// msg[0] = 3;
+/// let ct = Plaintext::new(msg, t).encrypt(&public_key, std_dev, &mut rng);
+///
+/// let refreshed = blind_rotate(&ct.extract_lwe(0), &bsk, |m| 2 * m);
+/// assert_eq!(refreshed.decrypt(&secret_key).poly().val()[0], 6);
+/// ```
+pub fn blind_rotate(
+    lwe: &LweCiphertext,
+    bsk: &BootstrappingKey,
+    f: impl Fn(i64) -> i64,
+) -> Ciphertext {
+    assert_eq!(lwe.a.len(), bsk.rgsw.len());
+    let degree = bsk.rgsw[0].c_mu.val[0].0.degree();
+    let (q, t) = (bsk.q, lwe.t);
+    let two_n = 2 * degree as i64;
+    let switch = |x: i64| {
+        rounded_div(x as i128 * two_n as i128, lwe.q as i128).rem_euclid(two_n as i128) as i64
+    };
+    let half_step = degree as i64 / t;
+
+    let delta = q / t;
+    let test_poly = Poly::new(
+        (0..degree as i64)
+            .map(|k| (f(k * t / two_n).rem_euclid(t)) * delta)
+            .collect(),
+    );
+    let mut acc = Ciphertext {
+        c_0: test_poly.mul_monomial(-(switch(lwe.b) + half_step)) % (q, degree),
+        c_1: Poly::new(vec![0; degree]),
+        q,
+        t,
+        level: 0,
+    };
+
+    for (a_j, rgsw_j) in lwe.a.iter().zip(bsk.rgsw.iter()) {
+        // CMux: acc + s'_j * (acc * X^{-a_j} - acc)
+        let a_j = switch(*a_j);
+        let rotated = Ciphertext {
+            c_0: (acc.c_0.mul_monomial(-a_j) - acc.c_0.clone()) % (q, degree),
+            c_1: (acc.c_1.mul_monomial(-a_j) - acc.c_1.clone()) % (q, degree),
+            q,
+            t,
+            level: 0,
+        };
+        let product = rgsw_j.external_product(&rotated);
+        acc = Ciphertext {
+            c_0: (acc.c_0 + product.c_0) % (q, degree),
+            c_1: (acc.c_1 + product.c_1) % (q, degree),
+            q,
+            t,
+            level: 0,
+        };
+    }
+    acc
+}
@@ -1,7 +1,21 @@
-use super::keys::{RelinearizationKey1, RelinearizationKey2, SecretKey};
-use super::plaintext::Plaintext;
-use super::poly::Poly;
-use std::ops::{Add, Mul, Neg, Sub};
+use super::error::BfvError;
+use super::keys::{
+    galois_element, GaloisKey, GaloisKeySet, KeySwitchKey, PublicKey, RelinearizationKey1,
+    RelinearizationKey2, SecretKey,
+};
+use super::lwe::LweCiphertext;
+use super::modulus_chain::ModulusChain;
+use super::ntt_ciphertext::NttCiphertext;
+use super::plaintext::{Plaintext, Seed};
+use super::poly::{centered_coeff, rounded_div, Poly};
+use super::random_source;
+use super::rns::{from_residues, to_residues, RnsCiphertext};
+use rand::{CryptoRng, RngCore};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 
 /// A BFV12 Ciphertext
 ///
@@ -9,97 +23,1131 @@ use std::ops::{Add, Mul, Neg, Sub};
 /// * `c_1` = `[p_1 * u + e_2]_q`
 /// * `q` = the ciphertext modulus
 /// * `t` = the plaintext modulus
-#[derive(Clone, Debug)]
+/// * `level` = the position of `q` in the modulus chain, counting the switches from the modulus
+///   of encryption
+///
+/// `+`, `-` and `*` on ciphertexts at different levels first switch the one with the larger
+/// modulus down to the other (see `align`).
+#[derive(Clone)]
 pub struct Ciphertext {
     pub(crate) c_0: Poly,
     pub(crate) c_1: Poly,
     pub(crate) q: i64,
     pub(crate) t: i64,
+    pub(crate) level: usize,
 }
 
-impl Ciphertext {
-    /// Decrypt a ciphertext to recover a plaintext, given a secret key
+// The operands of a binary operation brought to one level by `Ciphertext::align`.
+type Aligned<'a, 'b> = (Cow<'a, Ciphertext>, Cow<'b, Ciphertext>);
+
+impl Ciphertext {
+    /// Decrypt a ciphertext to recover a plaintext, given a secret key
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+    /// let decrypted = ct.decrypt(&secret_key);
+    ///
+    /// assert_eq!(decrypted, pt);
+    /// ```
+    pub fn decrypt(&self, secret_key: &SecretKey) -> Plaintext {
+        self.try_decrypt(secret_key)
+            .unwrap_or_else(|err| panic!("cannot decrypt: {err}"))
+    }
+
+    /// Decrypt a ciphertext, like `decrypt`, but return `DegreeMismatch` if the degrees of the
+    /// ciphertext and the secret key differ, where `decrypt` panics
+    pub fn try_decrypt(&self, secret_key: &SecretKey) -> Result<Plaintext, BfvError> {
+        self.check_secret_key(secret_key)?;
+        let (s, ring) = (&secret_key.poly, secret_key.ring);
+
+        // Both the NTT and the schoolbook path of `mul_ring` are chosen from the public
+        // `(ring, q)` and run the same operations for every `s` (see `SecretKey` for the threat
+        // model).
+        let raw = (self.c_0.clone() + self.c_1.mul_ring(s, self.q, ring)) % (self.q, ring);
+        let poly = raw.scale_round(self.t, self.q) % (self.t, ring);
+
+        Ok(Plaintext::new_from_poly(poly, self.t))
+    }
+
+    fn check_secret_key(&self, secret_key: &SecretKey) -> Result<(), BfvError> {
+        let (expected, actual) = (secret_key.poly.degree(), self.c_0.degree());
+        if expected != actual {
+            return Err(BfvError::DegreeMismatch { expected, actual });
+        }
+        Ok(())
+    }
+
+    /// Check that `other` has the moduli `q` and `t` and the degree of `self`, so that they can be
+    /// combined, returning `ModulusMismatch` or `DegreeMismatch` with the values of `self` as
+    /// expected
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{BfvError, Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(4, &mut rng);
+    /// let ct_1 = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(
+    ///     &secret_key.public_key_gen(1 << 16, std_dev, &mut rng),
+    ///     std_dev,
+    ///     &mut rng,
+    /// );
+    /// let ct_2 = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(
+    ///     &secret_key.public_key_gen(1 << 20, std_dev, &mut rng),
+    ///     std_dev,
+    ///     &mut rng,
+    /// );
+    /// assert_eq!(
+    ///     ct_1.check_compatible(&ct_2),
+    ///     Err(BfvError::ModulusMismatch { expected: 1 << 16, actual: 1 << 20 })
+    /// );
+    /// ```
+    pub fn check_compatible(&self, other: &Ciphertext) -> Result<(), BfvError> {
+        for (expected, actual) in [(self.q, other.q), (self.t, other.t)] {
+            if actual != expected {
+                return Err(BfvError::ModulusMismatch { expected, actual });
+            }
+        }
+        let expected = self.c_0.degree();
+        for actual in [self.c_1.degree(), other.c_0.degree(), other.c_1.degree()] {
+            if actual != expected {
+                return Err(BfvError::DegreeMismatch { expected, actual });
+            }
+        }
+        Ok(())
+    }
+
+    /// Switch the operand with the larger modulus down to the modulus of the other one, which
+    /// then also takes its level. Returns `ModulusMismatch` if the plaintext moduli differ, and
+    /// `ModulusChainMismatch` if the operand with the larger modulus is not at the lower level,
+    /// or the operands share a level but not a modulus.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{ModulusChain, Plaintext, SecretKey};
+    /// let chain = ModulusChain::new(vec![1 << 30, 1 << 20]).unwrap();
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(chain.moduli()[0], std_dev, &mut rng);
+    ///
+    /// let ct_1 = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    /// let ct_2 = Plaintext::new(vec![1, 1, 1, 1], t).encrypt(&public_key, std_dev, &mut rng);
+    /// let ct_2 = ct_2.mod_switch_next(&chain).unwrap();
+    /// let (lhs, rhs) = ct_1.align(&ct_2).unwrap();
+    /// assert_eq!((lhs.q(), lhs.level()), (1 << 20, 1));
+    /// assert_eq!((rhs.q(), rhs.level()), (1 << 20, 1));
+    /// ```
+    pub fn align<'a, 'b>(
+        &'a self,
+        other: &'b Ciphertext,
+    ) -> Result<(Cow<'a, Ciphertext>, Cow<'b, Ciphertext>), BfvError> {
+        if self.t != other.t {
+            return Err(BfvError::ModulusMismatch {
+                expected: self.t,
+                actual: other.t,
+            });
+        }
+        let switch_down = |high: &Ciphertext, low: &Ciphertext| {
+            let mut switched = high.mod_switch(low.q);
+            switched.level = low.level;
+            Cow::Owned(switched)
+        };
+        match (self.q.cmp(&other.q), self.level.cmp(&other.level)) {
+            (Ordering::Equal, Ordering::Equal) => Ok((Cow::Borrowed(self), Cow::Borrowed(other))),
+            (Ordering::Greater, Ordering::Less) => {
+                Ok((switch_down(self, other), Cow::Borrowed(other)))
+            }
+            (Ordering::Less, Ordering::Greater) => {
+                Ok((Cow::Borrowed(self), switch_down(other, self)))
+            }
+            _ => Err(BfvError::ModulusChainMismatch {
+                q: self.q,
+                level: self.level,
+                other_q: other.q,
+                other_level: other.level,
+            }),
+        }
+    }
+
+    // Align `self` and `other`, then check that they share their degree. This is the check of
+    // `try_add`, `try_sub` and `try_mul`, whose operators panic with its error.
+    fn align_compatible<'a, 'b>(
+        &'a self,
+        other: &'b Ciphertext,
+    ) -> Result<Aligned<'a, 'b>, BfvError> {
+        let (this, other) = self.align(other)?;
+        this.check_compatible(&other)?;
+        Ok((this, other))
+    }
+
+    /// Add two ciphertexts, like `+`, but return the error of `align` or `check_compatible` if
+    /// they cannot be brought to one level or do not share their degree, where `+` panics
+    pub fn try_add(&self, other: &Ciphertext) -> Result<Ciphertext, BfvError> {
+        let (this, other) = self.align_compatible(other)?;
+        Ok(&*this + &*other)
+    }
+
+    /// Subtract a ciphertext, like `-`, but return the error of `align` or `check_compatible` if
+    /// they cannot be brought to one level or do not share their degree, where `-` panics
+    pub fn try_sub(&self, other: &Ciphertext) -> Result<Ciphertext, BfvError> {
+        let (this, other) = self.align_compatible(other)?;
+        Ok(&*this - &*other)
+    }
+
+    /// Decrypt a ciphertext and return the message directly, as the centered representatives of
+    /// the plaintext coefficients in `(-t/2, t/2]` (see `Plaintext::to_vec`)
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let msg = vec![0, -3, 2, 4];
+    /// let pt = Plaintext::new(msg.clone(), t);
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+    /// assert_eq!(ct.decrypt_to_vec(&secret_key), msg);
+    /// ```
+    pub fn decrypt_to_vec(&self, secret_key: &SecretKey) -> Vec<i64> {
+        self.decrypt(secret_key).to_vec()
+    }
+
+    /// Measure the remaining noise budget of a ciphertext in bits, given a secret key
+    ///
+    /// The noise is the centered difference between `[c_0 + c_1 * s]_q` and `delta * m`.
+    /// Decryption is correct while its infinity norm stays below `delta / 2`, so the budget is
+    /// `log_2(delta / 2) - log_2(|noise|)`, and a non-positive budget means decryption may fail.
+    pub fn noise_budget(&self, secret_key: &SecretKey) -> i64 {
+        let (s, ring) = (&secret_key.poly, secret_key.ring);
+
+        let delta = self.q / self.t;
+        let m = self.decrypt(secret_key).poly();
+        let raw = (self.c_0.clone() + self.c_1.mul_ring(s, self.q, ring)) % (self.q, ring);
+        let noise = (raw - m * delta) % (self.q, ring);
+
+        // Take the centered representative of each noise coefficient in (-q/2, q/2].
+        let noise_norm = noise.norm_canonical(self.q);
+
+        ((delta as f64 / 2.0) / noise_norm.max(1) as f64)
+            .log2()
+            .floor() as i64
+    }
+
+    /// Multiply a ciphertext by a plaintext polynomial
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let pt_1 = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// let pt_2 = Plaintext::new(vec![0, 1, 0, 0], t);
+    /// let ct = pt_1.encrypt(&public_key, std_dev, &mut rng);
+    ///
+    /// let mul_pt = ct.mul_plain(&pt_2).decrypt(&secret_key);
+    /// assert_eq!(mul_pt.poly(), (pt_1.poly() * pt_2.poly()) % (t, degree));
+    /// ```
+    pub fn mul_plain(&self, pt: &Plaintext) -> Ciphertext {
+        let m = pt.poly() % (self.t, self.c_0.degree());
+        Ciphertext {
+            c_0: self.c_0.mul_ntt(&m, self.q),
+            c_1: self.c_1.mul_ntt(&m, self.q),
+            q: self.q,
+            t: self.t,
+            level: self.level,
+        }
+    }
+
+    /// Extract the LWE ciphertext encrypting the `index`-th plaintext coefficient, under the
+    /// coefficients of the same secret key
+    ///
+    /// The `index`-th coefficient of `c_1 * s` in `Z_q[X]/(X^N + 1)` is
+    /// `sum_{j <= index} c_1[index - j] * s[j] - sum_{j > index} c_1[N + index - j] * s[j]`, so the
+    /// mask is `c_1` reversed around `index`, with the coefficients that wrap around `X^N = -1`
+    /// negated, and the body is `c_0[index]`. Only ciphertexts of the negacyclic ring can be
+    /// extracted.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = Plaintext::new(vec![5, 1, 7, 2], t).encrypt(&public_key, std_dev, &mut rng);
+    /// assert_eq!(ct.extract_lwe(2).decrypt(&secret_key), 7);
+    /// ```
+    pub fn extract_lwe(&self, index: usize) -> LweCiphertext {
+        let degree = self.c_0.degree();
+        assert!(index < degree);
+        let c_1 = self.c_1.val();
+        let a = (0..degree)
+            .map(|j| {
+                if j <= index {
+                    c_1[index - j]
+                } else {
+                    (-c_1[degree + index - j]).rem_euclid(self.q)
+                }
+            })
+            .collect();
+        LweCiphertext {
+            a,
+            b: self.c_0.val()[index],
+            q: self.q,
+            t: self.t,
+        }
+    }
+
+    /// Transform the ciphertext into NTT form (see `NttCiphertext`), or return `None` unless `q`
+    /// is a prime with `2N | q - 1`
+    pub fn to_ntt(&self) -> Option<NttCiphertext> {
+        Some(NttCiphertext {
+            c_0: self.c_0.to_ntt(self.q)?,
+            c_1: self.c_1.to_ntt(self.q)?,
+            t: self.t,
+        })
+    }
+
+    /// Transform a ciphertext in NTT form back to coefficient form
+    pub fn from_ntt(ct: &NttCiphertext) -> Ciphertext {
+        Ciphertext {
+            c_0: Poly::from_ntt(&ct.c_0),
+            c_1: Poly::from_ntt(&ct.c_1),
+            q: ct.modulus(),
+            t: ct.t,
+            level: 0,
+        }
+    }
+
+    /// Multiply a ciphertext by a public integer `k`, which decrypts to `[k * m]_t`
+    ///
+    /// Only `k mod t` affects the message, so `k` is first replaced by its centered representative
+    /// in `(-t/2, t/2]`, which keeps the noise growth at most `t/2` for any `k`. Each coefficient
+    /// product is then computed in `i128` and reduced into `[0, q)`.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    /// assert_eq!(ct.mul_int(3).decrypt(&secret_key), Plaintext::new(vec![0, 3, 6, 1], t));
+    /// ```
+    pub fn mul_int(&self, k: i64) -> Ciphertext {
+        let k = centered_coeff(k.rem_euclid(self.t), self.t);
+        Ciphertext {
+            c_0: self.c_0.mul_int_mod(k, self.q),
+            c_1: self.c_1.mul_int_mod(k, self.q),
+            q: self.q,
+            t: self.t,
+            level: self.level,
+        }
+    }
+
+    /// Sum ciphertexts under the same moduli, accumulating the coefficients in `i128` and reducing
+    /// them into `[0, q)` once at the end, rather than after every addition
+    ///
+    /// Every coefficient fits in an `i64`, reduced or not, so the accumulators cannot overflow
+    /// before `2^64` operands, more than any slice can hold. Panics if `cts` is empty.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Ciphertext, Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let cts = (0..4)
+    ///     .map(|i| Plaintext::new(vec![i, 1, 0, 0], t).encrypt(&public_key, std_dev, &mut rng))
+    ///     .collect::<Vec<_>>();
+    /// let sum = Ciphertext::add_many(&cts);
+    /// assert_eq!(sum.decrypt(&secret_key), Plaintext::new(vec![6, 4, 0, 0], t));
+    /// ```
+    pub fn add_many(cts: &[Ciphertext]) -> Ciphertext {
+        let first = cts.first().expect("no ciphertexts to add");
+        let (q, t) = (first.q, first.t);
+        let degree = first.c_0.degree();
+        let mut acc = [vec![0i128; degree], vec![0i128; degree]];
+        for ct in cts {
+            assert_eq!((ct.q, ct.t), (q, t));
+            for (acc, c) in acc.iter_mut().zip([&ct.c_0, &ct.c_1]) {
+                assert_eq!(c.degree(), degree);
+                for (acc, coeff) in acc.iter_mut().zip(c.val()) {
+                    *acc += *coeff as i128;
+                }
+            }
+        }
+        let [c_0, c_1] = acc.map(|acc| {
+            Poly::new(
+                acc.into_iter()
+                    .map(|coeff| coeff.rem_euclid(q as i128) as i64)
+                    .collect(),
+            )
+        });
+        Ciphertext {
+            c_0,
+            c_1,
+            q,
+            t,
+            level: first.level,
+        }
+    }
+
+    /// Re-randomize a ciphertext by adding a fresh public key encryption of zero, which changes
+    /// `c_0` and `c_1` without changing the message, e.g. to make the ciphertext unlinkable to
+    /// the one it came from
+    ///
+    /// The noise of the encryption of zero adds to the existing one, at most `(2N + 1) * 6 *
+    /// std_dev` for the degree `N`. This costs about one bit of budget on a fresh ciphertext and
+    /// almost nothing on one whose noise is already much larger, but it does not refresh the noise
+    /// budget.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    /// let rerandomized = ct.rerandomize(&public_key, std_dev, &mut rng);
+    /// assert_ne!(rerandomized, ct);
+    /// assert_eq!(rerandomized.decrypt(&secret_key), ct.decrypt(&secret_key));
+    /// ```
+    pub fn rerandomize<T: RngCore + CryptoRng>(
+        &self,
+        pk: &PublicKey,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> Ciphertext {
+        let degree = self.c_0.degree();
+        assert_eq!(self.q, pk.q);
+        let zero = Plaintext::new(vec![0; degree], self.t).encrypt(pk, std_dev, rng);
+        Ciphertext {
+            c_0: (&self.c_0 + &zero.c_0) % (self.q, degree),
+            c_1: (&self.c_1 + &zero.c_1) % (self.q, degree),
+            q: self.q,
+            t: self.t,
+            level: self.level,
+        }
+    }
+
+    /// Switch the plaintext modulus of a ciphertext from `t` to `new_t`, without the secret key
+    ///
+    /// With `g = gcd(t, new_t)`, both components are multiplied by the integer `t / g` modulo `q`.
+    /// This turns `delta * m` with `delta = floor(q/t)` into `q/g * [m]_g` up to a rounding error
+    /// of at most `t/g * m`, which is `delta' * new_t/g * [m]_g` with `delta' = q/new_t`:
+    ///
+    /// * if `new_t` divides `t`, the ciphertext decrypts to `[m]_{new_t}` exactly;
+    /// * otherwise only `[m]_g` is well defined (`m` and `m + t` must switch to the same
+    ///   plaintext), and the ciphertext decrypts to `[new_t/g * [m]_g]_{new_t}`.
+    ///
+    /// Scaling by a rational `t / new_t` and rounding instead would leave a `t/new_t * q * k` term
+    /// behind, which is not a multiple of `q` and destroys the message. The noise grows by a
+    /// factor of `t/g`.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = Plaintext::new(vec![0, 5, 10, 15], 16).encrypt(&public_key, std_dev, &mut rng);
+    /// let switched = ct.rescale_plaintext_modulus(8);
+    /// assert_eq!(switched.decrypt(&secret_key), Plaintext::new(vec![0, 5, 2, 7], 8));
+    /// ```
+    pub fn rescale_plaintext_modulus(&self, new_t: i64) -> Ciphertext {
+        assert!(new_t > 1);
+        let degree = self.c_0.degree();
+        let scale = self.t / gcd(self.t, new_t);
+        Ciphertext {
+            c_0: self.c_0.mul_int_mod(scale, self.q) % (self.q, degree),
+            c_1: self.c_1.mul_int_mod(scale, self.q) % (self.q, degree),
+            q: self.q,
+            t: new_t,
+            level: self.level,
+        }
+    }
+
+    /// Switch the ciphertext modulus from `q` to `new_q`, without the secret key
+    ///
+    /// Both components are scaled by `new_q / q` and rounded, which scales `delta * m + e` into
+    /// `delta' * m + e'` with `delta' = floor(new_q / t)`, and the multiples of `q` into multiples
+    /// of `new_q`. The noise shrinks by the same factor but the rounding adds up to
+    /// `(1 + N * ||s||) / 2` (plus `t` for the rounding of `delta`), so switching to a smaller
+    /// modulus after a multiplication keeps the noise budget of a ciphertext.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 1 << 30;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// let switched = pt.encrypt(&public_key, std_dev, &mut rng).mod_switch(65537);
+    /// assert_eq!(switched.q(), 65537);
+    /// assert_eq!(switched.decrypt(&secret_key), pt);
+    /// ```
+    pub fn mod_switch(&self, new_q: i64) -> Ciphertext {
+        assert!(
+            new_q > self.t,
+            "{new_q} is not above the plaintext modulus {}",
+            self.t
+        );
+        let (q, new_q) = (self.q as i128, new_q as i128);
+        let switch = |c_i: &Poly| {
+            let val = c_i
+                .val()
+                .iter()
+                .map(|coeff| rounded_div(*coeff as i128 * new_q, q).rem_euclid(new_q) as i64)
+                .collect();
+            Poly::new(val)
+        };
+        Ciphertext {
+            c_0: switch(&self.c_0),
+            c_1: switch(&self.c_1),
+            q: new_q as i64,
+            t: self.t,
+            level: self.level + 1,
+        }
+    }
+
+    /// Switch the ciphertext modulus one level down `chain` (see `mod_switch`), or return
+    /// `BottomOfModulusChain` if it is already at the last level, `ModulusNotInChain` if its
+    /// modulus is not in `chain`, and `InvalidModulus` if the next modulus is not above `t`
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{BfvError, ModulusChain, Plaintext, SecretKey};
+    /// let chain = ModulusChain::new(vec![1 << 30, 1 << 20]).unwrap();
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(chain.moduli()[0], std_dev, &mut rng);
+    ///
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+    /// let switched = ct.mod_switch_next(&chain).unwrap();
+    /// assert_eq!(chain.level(switched.q()), Some(1));
+    /// assert_eq!(switched.decrypt(&secret_key), pt);
+    /// assert_eq!(
+    ///     switched.mod_switch_next(&chain).unwrap_err(),
+    ///     BfvError::BottomOfModulusChain { q: 1 << 20 }
+    /// );
+    /// ```
+    pub fn mod_switch_next(&self, chain: &ModulusChain) -> Result<Ciphertext, BfvError> {
+        let new_q = chain.next(self.q)?;
+        if new_q <= self.t {
+            return Err(BfvError::InvalidModulus {
+                q: new_q,
+                t: self.t,
+            });
+        }
+        Ok(self.mod_switch(new_q))
+    }
+
+    /// Return the ciphertext modulus
+    pub fn q(&self) -> i64 {
+        self.q
+    }
+
+    /// Return the plaintext modulus
+    pub fn t(&self) -> i64 {
+        self.t
+    }
+
+    /// Return the level of the ciphertext in the modulus chain, 0 for a fresh one
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Return the degree `N` of the ciphertext polynomials
+    pub fn degree(&self) -> usize {
+        self.c_0.degree()
+    }
+
+    /// Reduce every coefficient of `c_0` and `c_1` into the canonical range `[0, q)`
+    ///
+    /// `+`, `-` and negation leave the coefficients unreduced, possibly negative, whereas
+    /// serialization and `AssignedCiphertext::assign` expect them in `[0, q)`.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    ///
+    /// let mut neg = -&ct;
+    /// neg.canonicalize();
+    /// assert!(neg.c_0().iter().all(|coeff| (0..q).contains(coeff)));
+    /// assert_eq!(neg.decrypt(&secret_key), (-ct).decrypt(&secret_key));
+    /// ```
+    pub fn canonicalize(&mut self) {
+        let degree = self.c_0.degree();
+        self.c_0 = self.c_0.reduce(self.q, degree);
+        self.c_1 = self.c_1.reduce(self.q, degree);
+    }
+
+    /// Encode a public plaintext as the trivial ciphertext `(delta * m, 0)` modulo `q`, which
+    /// decrypts to `m` under any secret key and carries no noise
+    ///
+    /// It injects public constants into a homomorphic computation, e.g. adding it to a real
+    /// ciphertext behaves as adding the plaintext. It is transparent (see `is_transparent`), so it
+    /// hides nothing on its own.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Ciphertext, Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let one = Ciphertext::trivial(&Plaintext::new(vec![1, 0, 0, 0], t), q);
+    /// assert!(one.is_transparent());
+    /// assert_eq!(one.decrypt(&secret_key), Plaintext::new(vec![1, 0, 0, 0], t));
+    ///
+    /// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    /// assert_eq!((&ct + &one).decrypt(&secret_key), Plaintext::new(vec![1, 1, 2, 3], t));
+    /// ```
+    pub fn trivial(pt: &Plaintext, q: i64) -> Ciphertext {
+        let degree = pt.poly.degree();
+        let delta = q / pt.t;
+        let m = pt.poly() % (pt.t, degree);
+        Ciphertext {
+            c_0: (m * delta) % (q, degree),
+            c_1: Poly::new(vec![0; degree]),
+            q,
+            t: pt.t,
+            level: 0,
+        }
+    }
+
+    /// Return whether `c_1` reduces to the zero polynomial modulo `q`
+    ///
+    /// The plaintext of such a "transparent" ciphertext is recoverable without the secret key,
+    /// e.g. after subtracting a ciphertext from itself. Enabling the `transparency-warnings`
+    /// feature makes the arithmetic operators report on stderr when they produce one.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    /// assert!(!ct.is_transparent());
+    /// assert!((&ct - &ct).is_transparent());
+    /// ```
+    pub fn is_transparent(&self) -> bool {
+        self.c_1.norm_canonical(self.q) == 0
+    }
+
+    #[cfg(feature = "transparency-warnings")]
+    fn warn_if_transparent(&self, op: &str) {
+        if self.is_transparent() {
+            eprintln!("warning: `{op}` produced a transparent ciphertext");
+        }
+    }
+
+    /// Convert a ciphertext into RNS form over `primes`, whose product must be `q`
     ///
     /// ```rust
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// # let std_dev = 3.2;
     /// # let degree = 4;
-    /// # let q = 65536;
     /// # let t = 4;
     /// #
-    /// use bfv12::{Plaintext, SecretKey};
-    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// use verifiable_bfv::bfv::{Ciphertext, Plaintext, SecretKey};
+    /// let primes = [17, 97];
+    /// let q = 17 * 97;
     ///
     /// let secret_key = SecretKey::generate(degree, &mut rng);
     /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
     ///
-    /// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
-    /// let decrypted = ct.decrypt(&secret_key);
+    /// let pt_1 = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// let pt_2 = Plaintext::new(vec![3, 2, 1, 0], t);
+    /// let ct_1 = pt_1.encrypt(&public_key, std_dev, &mut rng).to_rns(&primes);
+    /// let ct_2 = pt_2.encrypt(&public_key, std_dev, &mut rng).to_rns(&primes);
     ///
-    /// assert_eq!(decrypted, pt);
+    /// let added = Ciphertext::from_rns(&(ct_1 + ct_2)).decrypt(&secret_key);
+    /// assert_eq!(added.poly(), (pt_1.poly() + pt_2.poly()) % (t, degree));
     /// ```
-    pub fn decrypt(&self, secret_key: &SecretKey) -> Plaintext {
-        let s = &secret_key.poly;
-        let degree = s.degree();
+    pub fn to_rns(&self, primes: &[i64]) -> RnsCiphertext {
+        assert_eq!(
+            primes.iter().map(|p| *p as i128).product::<i128>(),
+            self.q as i128
+        );
+        RnsCiphertext {
+            c_0: to_residues(&self.c_0, primes),
+            c_1: to_residues(&self.c_1, primes),
+            primes: primes.to_vec(),
+            t: self.t,
+        }
+    }
+
+    /// Recombine a ciphertext in RNS form into a single-modulus ciphertext
+    ///
+    /// The coefficients are reconstructed in `i128` with the Chinese remainder theorem. This never
+    /// overflows: an `RnsCiphertext` only comes from `to_rns`, so the product of its primes is the
+    /// `i64` modulus of the ciphertext it was converted from.
+    pub fn from_rns(rns: &RnsCiphertext) -> Ciphertext {
+        let q = rns.primes.iter().map(|p| *p as i128).product::<i128>();
+        Ciphertext {
+            c_0: from_residues(&rns.c_0, &rns.primes),
+            c_1: from_residues(&rns.c_1, &rns.primes),
+            q: i64::try_from(q).expect("the primes multiply to the modulus of a ciphertext"),
+            t: rns.t,
+            level: 0,
+        }
+    }
+
+    /// Return the coefficients of `c_0`, which is all `from_seeded` needs besides the seed
+    pub fn c_0(&self) -> &[i64] {
+        self.c_0.val()
+    }
+
+    /// Regenerate a ciphertext produced by `Plaintext::encrypt_seeded` from its `c_0` and the
+    /// seed `c_1` was expanded from
+    ///
+    /// * `c_0`: the coefficients of `c_0`, whose length is the degree of the ciphertext
+    /// * `seed`: the seed returned by `encrypt_seeded`
+    /// * `q`: the ciphertext modulus
+    /// * `t`: the plaintext modulus
+    pub fn from_seeded(c_0: &[i64], seed: Seed, q: i64, t: i64) -> Ciphertext {
+        let degree = c_0.len();
+        Ciphertext {
+            c_0: Poly::new(c_0.to_vec()),
+            c_1: random_source::expand_seed(seed, q, degree),
+            q,
+            t,
+            level: 0,
+        }
+    }
+
+    /// Encode a ciphertext in a fixed little-endian layout: the degree as a `u64`, then `q`, `t`
+    /// and the coefficients of `c_0` and `c_1` as `i64`s, reduced into `[0, q)`
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Ciphertext, Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    ///
+    /// let bytes = ct.to_bytes();
+    /// assert_eq!(bytes.len(), 8 * (3 + 2 * degree));
+    /// assert_eq!(Ciphertext::from_bytes(&bytes).unwrap(), ct);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let degree = self.c_0.degree();
+        let mut bytes = Vec::with_capacity(8 * (3 + 2 * degree));
+        bytes.extend_from_slice(&(degree as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.q.to_le_bytes());
+        bytes.extend_from_slice(&self.t.to_le_bytes());
+        for c in [&self.c_0, &self.c_1] {
+            for coeff in (c.clone() % (self.q, degree)).val() {
+                bytes.extend_from_slice(&coeff.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Decode a ciphertext written by `to_bytes`
+    ///
+    /// Fails if the degree is `0`, if the buffer does not have the length implied by its degree,
+    /// if the moduli do not satisfy `1 < t < q`, or if a coefficient lies outside of `[0, q)`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Ciphertext, BfvError> {
+        const HEADER_LEN: usize = 24;
+        let read_word = |i: usize| -> [u8; 8] { bytes[8 * i..8 * (i + 1)].try_into().unwrap() };
+        if bytes.len() < HEADER_LEN {
+            return Err(BfvError::BufferLength {
+                expected: HEADER_LEN,
+                actual: bytes.len(),
+            });
+        }
+
+        let degree = u64::from_le_bytes(read_word(0));
+        if degree == 0 {
+            return Err(BfvError::InvalidDegree { degree });
+        }
+        let expected = usize::try_from(degree)
+            .ok()
+            .and_then(|degree| degree.checked_mul(16))
+            .and_then(|len| len.checked_add(HEADER_LEN))
+            .unwrap_or(usize::MAX);
+        if bytes.len() != expected {
+            return Err(BfvError::BufferLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+        let degree = degree as usize;
 
-        let delta_inv = self.t as f64 / self.q as f64;
-        let raw = (self.c_0.clone() + self.c_1.clone() * s.clone()) % (self.q, degree);
-        let poly = (raw * delta_inv) % (self.t, degree);
+        let q = i64::from_le_bytes(read_word(1));
+        let t = i64::from_le_bytes(read_word(2));
+        if !(1 < t && t < q) {
+            return Err(BfvError::InvalidModulus { q, t });
+        }
 
-        Plaintext::new_from_poly(poly, self.t)
+        let read_poly = |offset: usize| -> Result<Poly, BfvError> {
+            let val = (offset..offset + degree)
+                .map(|i| {
+                    let value = i64::from_le_bytes(read_word(i));
+                    if (0..q).contains(&value) {
+                        Ok(value)
+                    } else {
+                        Err(BfvError::CoefficientOutOfRange { value, q })
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Poly::new(val))
+        };
+        Ok(Ciphertext {
+            c_0: read_poly(3)?,
+            c_1: read_poly(3 + degree)?,
+            q,
+            t,
+            level: 0,
+        })
     }
 
+    /// Multiply two ciphertexts without relinearizing, returning the degree-2 ciphertext
+    /// `(c_0, c_1, c_2)`, which decrypts with `(1, s, s^2)`
+    ///
+    /// The products are reduced modulo `X^N + 1`, so both ciphertexts must be encrypted under a
+    /// key in the negacyclic ring: `SecretKey::relin_key_gen_1` rejects the keys of prime
+    /// cyclotomic rings with `BfvError::UnsupportedRing`.
     pub(crate) fn basic_mul(&self, other: Ciphertext) -> (Poly, Poly, Poly) {
+        if let Err(err) = self.check_compatible(&other) {
+            panic!("cannot multiply: {err}");
+        }
         let degree = self.c_0.degree();
-        assert_eq!(degree, self.c_1.degree());
-        assert_eq!(degree, other.c_0.degree());
-        assert_eq!(degree, other.c_1.degree());
 
-        let out_0_raw = self.c_0.clone() * other.c_0.clone();
-        let out_1_raw = self.c_0.clone() * other.c_1.clone() + self.c_1.clone() * other.c_0.clone();
-        let out_2_raw = self.c_1.clone() * other.c_1.clone();
+        // The raw products are exact in `i128`: they have more bits than an `f64` mantissa, and
+        // than an `i64` once `q` approaches `2^31`.
+        let out_0_raw = self.c_0.mul_wide(&other.c_0);
+        let out_1_raw = self.c_0.mul_wide(&other.c_1) + self.c_1.mul_wide(&other.c_0);
+        let out_2_raw = self.c_1.mul_wide(&other.c_1);
+
+        // Scale by `t/q` exactly before reducing modulo `q`.
+        let out_0 = out_0_raw.scale_round(self.t, self.q).reduce(self.q, degree);
+        let out_1 = out_1_raw.scale_round(self.t, self.q).reduce(self.q, degree);
+        let out_2 = out_2_raw.scale_round(self.t, self.q).reduce(self.q, degree);
 
-        let delta_inv = self.t as f64 / self.q as f64;
-        let out_0 = (out_0_raw * delta_inv) % (self.q, degree);
-        let out_1 = (out_1_raw * delta_inv) % (self.q, degree);
-        let out_2 = (out_2_raw * delta_inv) % (self.q, degree);
+        #[cfg(feature = "transparency-warnings")]
+        if out_1
+            .val()
+            .iter()
+            .chain(out_2.val())
+            .all(|coeff| *coeff == 0)
+        {
+            eprintln!("warning: `basic_mul` produced a transparent ciphertext");
+        }
 
         (out_0, out_1, out_2)
     }
 
-    fn relinearization_1(
+    /// Rotate a ciphertext with a Galois key, applying the automorphism `X -> X^k` to the
+    /// encrypted message
+    ///
+    /// Only the automorphisms of `Z[X]/(X^N + 1)` are supported, `SecretKey::galois_key_gen`
+    /// returns `BfvError::UnsupportedRing` for the other rings.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// # let base = 16;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// // Step 1 applies `X -> X^3`
+    /// let galois_key = secret_key.galois_key_gen(q, std_dev, &mut rng, base, 1).unwrap();
+    ///
+    /// let ct = Plaintext::new(vec![0, 1, 0, 0], t).encrypt(&public_key, std_dev, &mut rng);
+    /// let rotated = ct.rotate(&galois_key).decrypt(&secret_key);
+    /// assert_eq!(rotated, Plaintext::new(vec![0, 0, 0, 1], t));
+    /// ```
+    pub fn rotate(&self, gk: &GaloisKey) -> Ciphertext {
+        // `(c_0(X^k), c_1(X^k))` decrypts under `s(X^k)`, switch it back to `s`.
+        self.automorphism(gk.k).key_switch(&gk.ksk)
+    }
+
+    /// Apply the automorphism `X -> X^k` of the negacyclic ring to both polynomials, for an odd
+    /// `k`, without switching keys: the result decrypts to `m(X^k)` under the transformed secret
+    /// key `s(X^k)` instead of `s`. `rotate` switches it back to `s` with a Galois key.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = Plaintext::new(vec![1, 2, 0, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    /// assert_eq!(ct.automorphism(1), ct);
+    /// ```
+    pub fn automorphism(&self, k: usize) -> Ciphertext {
+        let degree = self.c_0.degree();
+        Ciphertext {
+            c_0: self.c_0.automorphism(k) % (self.q, degree),
+            c_1: self.c_1.automorphism(k) % (self.q, degree),
+            q: self.q,
+            t: self.t,
+            level: self.level,
+        }
+    }
+
+    /// Switch a ciphertext to another secret key with a key switching key, such that it decrypts
+    /// to the same plaintext under the target key of `ksk` (see `KeySwitchKey::generate`)
+    pub fn key_switch(&self, ksk: &KeySwitchKey) -> Ciphertext {
+        let degree = self.c_0.degree();
+        let (ks_0, ks_1) = self.key_switch_poly(self.c_1.clone(), ksk);
+
+        Ciphertext {
+            c_0: (self.c_0.clone() + ks_0) % (self.q, degree),
+            c_1: ks_1 % (self.q, degree),
+            q: self.q,
+            t: self.t,
+            level: self.level,
+        }
+    }
+
+    /// Sum the `N` slots of a batched ciphertext into every slot, slot 0 included, by
+    /// rotate-and-add folding over the Galois group
+    ///
+    /// With `t = 1 mod 2N`, the slots are the evaluations of the message at the primitive `2N`-th
+    /// roots of unity modulo `t`, which the automorphisms `X -> X^k` permute. Adding the rotations
+    /// by `1, 2, 4, ..., N/4` steps sums the `N/2` slots of each orbit of `3`, and adding the
+    /// conjugation sums the two orbits, so `log2(N)` keys are needed in total (see
+    /// `KeyGenerator::slot_sum_keys`). Returns `MissingGaloisKey` before rotating anything if one
+    /// is missing. Each step doubles the noise and adds a key switching error.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let keygen_rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+    /// #
+    /// use verifiable_bfv::bfv::{BfvParameters, KeyGenerator, Plaintext};
+    /// let params = BfvParameters::new(4, 1 << 20, 17, 3.2);
+    /// let mut keygen = KeyGenerator::new(params, keygen_rng);
+    /// let public_key = keygen.public_key();
+    /// let galois_keys = keygen.slot_sum_keys().unwrap();
+    ///
+    /// // A constant message has the same value in every slot, which sum to `4 * 5 = 3 mod 17`
+    /// let ct = Plaintext::new(vec![5, 0, 0, 0], 17).encrypt(&public_key, 3.2, &mut rng);
+    /// let sum = ct.slot_sum(&galois_keys).unwrap();
+    /// assert_eq!(sum.decrypt(keygen.secret_key()), Plaintext::new(vec![3, 0, 0, 0], 17));
+    /// ```
+    pub fn slot_sum(&self, gks: &GaloisKeySet) -> Result<Ciphertext, BfvError> {
+        let degree = self.c_0.degree();
+        assert!(degree.is_power_of_two() && degree >= 2);
+
+        let mut keys = (0..(degree / 2).trailing_zeros())
+            .map(|j| {
+                let step = 1 << j;
+                gks.get(step).ok_or(BfvError::MissingGaloisKey {
+                    k: galois_element(step, degree),
+                })
+            })
+            .collect::<Result<Vec<_>, BfvError>>()?;
+        let conjugation = gks
+            .conjugation()
+            .filter(|gk| gk.k == 2 * degree - 1)
+            .ok_or(BfvError::MissingGaloisKey { k: 2 * degree - 1 })?;
+        keys.push(conjugation);
+
+        let mut acc = self.clone();
+        for gk in keys {
+            acc = &acc + &acc.rotate(gk);
+            acc.canonicalize();
+        }
+        Ok(acc)
+    }
+
+    /// Multiply two ciphertexts like `*` with a Version 1 relinearization key, checking the noise
+    /// of the product when a secret key is given
+    ///
+    /// With `secret_key`, e.g. in tests or debug builds, a product whose `noise_budget` is not
+    /// positive is returned as `NoiseBudgetExhausted` instead of a ciphertext that may decrypt to
+    /// the wrong plaintext. Without it, no check is possible and the product is always returned.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// # let base = 16;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let rlk = secret_key.relin_key_gen_1(q, std_dev, &mut rng, base).unwrap();
+    ///
+    /// let ct = Plaintext::new(vec![0, 1, 0, 0], t).encrypt(&public_key, std_dev, &mut rng);
+    /// let product = ct.clone().try_mul(ct, &rlk, Some(&secret_key)).unwrap();
+    /// assert_eq!(product.decrypt(&secret_key), Plaintext::new(vec![0, 0, 1, 0], t));
+    /// ```
+    pub fn try_mul(
+        self,
+        other: Ciphertext,
+        rlk: &RelinearizationKey1,
+        secret_key: Option<&SecretKey>,
+    ) -> Result<Ciphertext, BfvError> {
+        let (this, other) = self.align_compatible(&other)?;
+        let product = this.into_owned() * (other.into_owned(), rlk);
+        match secret_key.map(|sk| product.noise_budget(sk)) {
+            Some(budget) if budget <= 0 => Err(BfvError::NoiseBudgetExhausted { budget }),
+            _ => Ok(product),
+        }
+    }
+
+    pub(super) fn relinearization_1(
         &self,
         c_0: Poly,
         c_1: Poly,
         c_2: Poly,
         rlk: &RelinearizationKey1,
     ) -> Ciphertext {
-        let degree = c_0.degree();
-
-        // Decompose c_2 in base T (rlk_base), such that:
-        // $ c_2 = \sum_{i=0}^l c_2^(i) T^i $ with $ c_2^(i) \in R_T $
-        let c_2_dec: Vec<Poly> = c_2.decompose(rlk.l, rlk.base);
-
-        // Calculate the contributions of the decomposed c_2 for c_0 and c_1.
-        let mut c_2_0 = Poly::new(vec![0; degree]);
-        let mut c_2_1 = Poly::new(vec![0; degree]);
-        for i in 0..(rlk.l as usize) {
-            // Calculate the sum of the first entry of the relinearization key and decomposed c_2:
-            // $ \sum_{i=0}^l rlk[i][0] * c_2^(i) $
-            c_2_0 = c_2_0 + rlk.val[i].0.clone() * c_2_dec[i].clone();
-
-            // Calculate the sum of the second entry of the relinearization key and decomposed c_2:
-            // $ \sum_{i=0}^l rlk[i][1] * c_2^(i) $
-            c_2_1 = c_2_1 + rlk.val[i].1.clone() * c_2_dec[i].clone();
-        }
+        let (c_2_0, c_2_1) = self.key_switch_poly(c_2, &rlk.ksk);
 
         Ciphertext {
             c_0: c_0 + c_2_0,
             c_1: c_1 + c_2_1,
             q: self.q,
             t: self.t,
+            level: self.level,
+        }
+    }
+
+    // Return the contributions to `c_0` and `c_1` of a polynomial `c` multiplying the source key
+    // of `ksk`.
+    pub(super) fn key_switch_poly(&self, c: Poly, ksk: &KeySwitchKey) -> (Poly, Poly) {
+        let degree = c.degree();
+        let (val, l, base) = (&ksk.val, ksk.l, ksk.base);
+
+        // Decompose c in base T (base), such that:
+        // $ c = \sum_{i=0}^l c^(i) T^i $ with $ c^(i) \in R_T $
+        let c_dec: Vec<Poly> = c.decompose(l, base);
+
+        // Calculate the contributions of the decomposed c for c_0 and c_1.
+        let mut c_0 = Poly::new(vec![0; degree]);
+        let mut c_1 = Poly::new(vec![0; degree]);
+        for i in 0..l {
+            // Calculate the sum of the first entry of the key and decomposed c:
+            // $ \sum_{i=0}^l key[i][0] * c^(i) $
+            c_0 = c_0 + val[i].0.mul_ntt(&c_dec[i], self.q);
+
+            // Calculate the sum of the second entry of the key and decomposed c:
+            // $ \sum_{i=0}^l key[i][1] * c^(i) $
+            c_1 = c_1 + val[i].1.mul_ntt(&c_dec[i], self.q);
         }
+        (c_0, c_1)
     }
 
     fn relinearization_2(
@@ -110,25 +1158,107 @@ impl Ciphertext {
         rlk: &RelinearizationKey2,
     ) -> Ciphertext {
         let degree = c_0.degree();
-        let p = rlk.p as f64;
+        let p = rlk.p;
 
-        let c_2_0 = (c_2.clone() * rlk.rlk_0.clone() / p) % (self.q, degree);
-        let c_2_1 = (c_2.clone() * rlk.rlk_1.clone() / p) % (self.q, degree);
+        // `c_2 * rlk_i` has up to the bits of `N * p * q^2`, so divide it by `p` exactly in `i128`.
+        let c_2_0 = c_2
+            .mul_wide(&rlk.rlk_0)
+            .scale_round(1, p)
+            .reduce(self.q, degree);
+        let c_2_1 = c_2
+            .mul_wide(&rlk.rlk_1)
+            .scale_round(1, p)
+            .reduce(self.q, degree);
         Ciphertext {
             c_0: (c_0 + c_2_0) % (self.q, degree),
             c_1: (c_1 + c_2_1) % (self.q, degree),
             q: self.q,
             t: self.t,
+            level: self.level,
         }
     }
 }
 
-/// Add two ciphertexts. They can be of different degrees.
+/// Decode a ciphertext written by `Ciphertext::to_bytes`.
+impl TryFrom<&[u8]> for Ciphertext {
+    type Error = BfvError;
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ciphertext::from_bytes(bytes)
+    }
+}
+
+/// Show the degree, the moduli and the infinity norms of the centered components instead of
+/// the full coefficient vectors.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 4;
+/// # let q = 65536;
+/// # let t = 4;
+/// #
+/// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+///
+/// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+/// assert!(format!("{ct:?}").starts_with("Ciphertext { degree: 4, q: 65536, t: 4, c_0_norm: "));
+/// ```
+impl fmt::Debug for Ciphertext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ciphertext")
+            .field("degree", &self.c_0.degree())
+            .field("q", &self.q)
+            .field("t", &self.t)
+            .field("c_0_norm", &self.c_0.norm_canonical(self.q))
+            .field("c_1_norm", &self.c_1.norm_canonical(self.q))
+            .finish()
+    }
+}
+
+/// Compare ciphertexts by their moduli, degree and coefficients canonicalized into `[0, q)`, so
+/// that the same ciphertext stored with different representatives, e.g. before and after
+/// `canonicalize`, compares equal.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 4;
+/// # let q = 65536;
+/// # let t = 4;
+/// #
+/// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+///
+/// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+/// let neg = -&ct;
+/// let mut canonical = neg.clone();
+/// canonical.canonicalize();
+/// assert_ne!(neg.c_0(), canonical.c_0());
+/// assert_eq!(neg, canonical);
+/// ```
+impl PartialEq for Ciphertext {
+    fn eq(&self, other: &Ciphertext) -> bool {
+        (self.q, self.t) == (other.q, other.t)
+            && self.c_0.eq_mod(&other.c_0, self.q)
+            && self.c_1.eq_mod(&other.c_1, self.q)
+    }
+}
+
+impl Eq for Ciphertext {}
+
+/// Add two ciphertexts. Panics, naming both values, if they differ in degree or are not in one
+/// modulus chain (see `try_add`).
+///
+/// An operand at a higher level of the modulus chain is first switched down to the other's level.
 ///
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use verifiable_bfv::bfv::{SecretKey, Plaintext};
 /// #
 /// # let t = 12;         // Plaintext modulus
 /// # let q = 65536;      // Ciphertext modulus
@@ -159,21 +1289,39 @@ impl Ciphertext {
 impl Add<Ciphertext> for Ciphertext {
     type Output = Self;
     fn add(self, other: Ciphertext) -> Self::Output {
-        Ciphertext {
-            c_0: self.c_0 + other.c_0,
-            c_1: self.c_1 + other.c_1,
-            q: self.q,
-            t: self.t,
-        }
+        &self + &other
     }
 }
 
-/// Subtract one ciphertext from another. They can be of different degrees.
+/// Add two ciphertexts by reference, so that `&ct_1 + &ct_2` leaves both operands available.
+impl Add<&Ciphertext> for &Ciphertext {
+    type Output = Ciphertext;
+    fn add(self, other: &Ciphertext) -> Self::Output {
+        let (this, other) = self
+            .align_compatible(other)
+            .unwrap_or_else(|err| panic!("cannot add: {err}"));
+        let out = Ciphertext {
+            c_0: &this.c_0 + &other.c_0,
+            c_1: &this.c_1 + &other.c_1,
+            q: this.q,
+            t: this.t,
+            level: this.level,
+        };
+        #[cfg(feature = "transparency-warnings")]
+        out.warn_if_transparent("add");
+        out
+    }
+}
+
+/// Subtract one ciphertext from another. Panics, naming both values, if they differ in degree or
+/// are not in one modulus chain (see `try_sub`).
+///
+/// An operand at a higher level of the modulus chain is first switched down to the other's level.
 ///
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use verifiable_bfv::bfv::{SecretKey, Plaintext};
 /// #
 /// # let t = 12;         // Plaintext modulus
 /// # let q = 65536;      // Ciphertext modulus
@@ -204,12 +1352,88 @@ impl Add<Ciphertext> for Ciphertext {
 impl Sub<Ciphertext> for Ciphertext {
     type Output = Self;
     fn sub(self, other: Ciphertext) -> Self::Output {
-        Ciphertext {
-            c_0: self.c_0 - other.c_0,
-            c_1: self.c_1 - other.c_1,
-            q: self.q,
-            t: self.t,
+        &self - &other
+    }
+}
+
+/// Subtract one ciphertext from another by reference, leaving both operands available.
+impl Sub<&Ciphertext> for &Ciphertext {
+    type Output = Ciphertext;
+    fn sub(self, other: &Ciphertext) -> Self::Output {
+        let (this, other) = self
+            .align_compatible(other)
+            .unwrap_or_else(|err| panic!("cannot sub: {err}"));
+        let out = Ciphertext {
+            c_0: &this.c_0 - &other.c_0,
+            c_1: &this.c_1 - &other.c_1,
+            q: this.q,
+            t: this.t,
+            level: this.level,
+        };
+        #[cfg(feature = "transparency-warnings")]
+        out.warn_if_transparent("sub");
+        out
+    }
+}
+
+/// Add a ciphertext into another in place, reducing the result modulo `q`.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// #
+/// # use verifiable_bfv::bfv::{SecretKey, Plaintext};
+/// #
+/// # let t = 12;         // Plaintext modulus
+/// # let q = 65536;      // Ciphertext modulus
+/// # let std_dev = 3.2;  // Standard deviation for generating the error
+/// # let degree = 4;     // Degree of polynomials used for encoding and encrypting messages
+/// #
+/// # // Generate a seeded RNG. Any Rng that implements RngCore + CryptoRng can be used.
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// #
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+///
+/// let pts: Vec<_> = (0..3).map(|_| Plaintext::rand(degree, t, &mut rng)).collect();
+/// let cts: Vec<_> = pts.iter().map(|pt| pt.encrypt(&public_key, std_dev, &mut rng)).collect();
+///
+/// // Accumulate: acc = ct_0 + ct_1 + ct_2
+/// let mut acc = cts[0].clone();
+/// for ct in &cts[1..] {
+///     acc += ct;
+/// }
+///
+/// let expected_pt = (pts[0].poly() + pts[1].poly() + pts[2].poly()) % (t, degree);
+/// assert_eq!(acc.decrypt(&secret_key).poly(), expected_pt)
+/// ```
+impl AddAssign<&Ciphertext> for Ciphertext {
+    fn add_assign(&mut self, other: &Ciphertext) {
+        let (this, other) = self
+            .align_compatible(other)
+            .unwrap_or_else(|err| panic!("cannot add: {err}"));
+        if let Cow::Owned(this) = this {
+            *self = this;
+        }
+        self.c_0.add_assign_mod(&other.c_0, self.q);
+        self.c_1.add_assign_mod(&other.c_1, self.q);
+        #[cfg(feature = "transparency-warnings")]
+        self.warn_if_transparent("add_assign");
+    }
+}
+
+/// Subtract a ciphertext from another in place, reducing the result modulo `q`.
+impl SubAssign<&Ciphertext> for Ciphertext {
+    fn sub_assign(&mut self, other: &Ciphertext) {
+        let (this, other) = self
+            .align_compatible(other)
+            .unwrap_or_else(|err| panic!("cannot sub: {err}"));
+        if let Cow::Owned(this) = this {
+            *self = this;
         }
+        self.c_0.sub_assign_mod(&other.c_0, self.q);
+        self.c_1.sub_assign_mod(&other.c_1, self.q);
+        #[cfg(feature = "transparency-warnings")]
+        self.warn_if_transparent("sub_assign");
     }
 }
 
@@ -218,7 +1442,7 @@ impl Sub<Ciphertext> for Ciphertext {
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use verifiable_bfv::bfv::{SecretKey, Plaintext};
 /// #
 /// # let t = 12;         // Plaintext modulus
 /// # let q = 65536;      // Ciphertext modulus
@@ -253,15 +1477,65 @@ impl Neg for Ciphertext {
     }
 }
 
+/// Take the negation of a ciphertext by reference.
+impl Neg for &Ciphertext {
+    type Output = Ciphertext;
+    fn neg(self) -> Self::Output {
+        Ciphertext {
+            c_0: -&self.c_0,
+            c_1: -&self.c_1,
+            q: self.q,
+            t: self.t,
+            level: self.level,
+        }
+    }
+}
+
+/// Sum ciphertexts under the same moduli with `Ciphertext::add_many`, which panics if the
+/// iterator is empty as a ciphertext has no zero without a degree and moduli.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 4;
+/// # let q = 65536;
+/// # let t = 8;
+/// #
+/// use verifiable_bfv::bfv::{Ciphertext, Plaintext, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+///
+/// let cts = (0..4)
+///     .map(|i| Plaintext::new(vec![i, 1, 0, 0], t).encrypt(&public_key, std_dev, &mut rng))
+///     .collect::<Vec<_>>();
+/// let sum: Ciphertext = cts.iter().sum();
+/// assert_eq!(sum.decrypt(&secret_key), Plaintext::new(vec![6, 4, 0, 0], t));
+/// ```
+impl Sum<Ciphertext> for Ciphertext {
+    fn sum<I: Iterator<Item = Ciphertext>>(iter: I) -> Self {
+        Ciphertext::add_many(&iter.collect::<Vec<_>>())
+    }
+}
+
+/// Sum ciphertexts by reference, see `Sum<Ciphertext>`.
+impl<'a> Sum<&'a Ciphertext> for Ciphertext {
+    fn sum<I: Iterator<Item = &'a Ciphertext>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
 /// Multiply two ciphertexts, using Relinearization Version 1.
 /// Since multiplication requires a relinearization key, you must multiply a ciphertext
 /// with a tuple of (Ciphertext, &RelinearizationKey1). The type of the relinearization
 /// key determines whether the multiplication uses Relinearization Version 1 or 2.
 ///
+/// An operand at a higher level of the modulus chain is first switched down to the other's level.
+///
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use verifiable_bfv::bfv::{SecretKey, Plaintext};
 /// #
 /// # let t = 12;         // Plaintext modulus
 /// # let q = 65536;      // Ciphertext modulus
@@ -274,7 +1548,7 @@ impl Neg for Ciphertext {
 /// #
 /// let secret_key = SecretKey::generate(degree, &mut rng);
 /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
-/// let rlk_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, rlk_base);
+/// let rlk_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, rlk_base).unwrap();
 ///
 /// let pt_1 = Plaintext::rand(degree, t, &mut rng);
 /// let pt_2 = Plaintext::rand(degree, t, &mut rng);
@@ -295,10 +1569,13 @@ impl Mul<(Ciphertext, &RelinearizationKey1)> for Ciphertext {
     type Output = Self;
     fn mul(self, other: (Ciphertext, &RelinearizationKey1)) -> Self::Output {
         let (other_ct, rlk_1) = other;
+        let (this, other_ct) = self
+            .align_compatible(&other_ct)
+            .unwrap_or_else(|err| panic!("cannot multiply: {err}"));
 
-        let (c_0, c_1, c_2) = self.basic_mul(other_ct);
+        let (c_0, c_1, c_2) = this.basic_mul(other_ct.into_owned());
 
-        self.relinearization_1(c_0, c_1, c_2, rlk_1)
+        this.relinearization_1(c_0, c_1, c_2, rlk_1)
     }
 }
 
@@ -307,10 +1584,12 @@ impl Mul<(Ciphertext, &RelinearizationKey1)> for Ciphertext {
 /// with a tuple of (Ciphertext, &RelinearizationKey2). The type of the relinearization
 /// key determines whether the multiplication uses Relinearization Version 1 or 2.
 ///
+/// An operand at a higher level of the modulus chain is first switched down to the other's level.
+///
 /// ```rust
 /// # use rand::SeedableRng;
 /// #
-/// # use bfv12::{SecretKey, Plaintext};
+/// # use verifiable_bfv::bfv::{SecretKey, Plaintext};
 /// #
 /// # let t = 12;         // Plaintext modulus
 /// # let q = 65536;      // Ciphertext modulus
@@ -327,7 +1606,7 @@ impl Mul<(Ciphertext, &RelinearizationKey1)> for Ciphertext {
 /// // Technically p should be >= q^3 for security (see paper discussion on Relinearization Version 2),
 /// // but setting p = q^3 results in an overflow when taking p * q so we will test with a smaller p.
 /// let p = 2_i64.pow(13) * q;
-/// let rlk_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p);
+/// let rlk_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p).unwrap();
 ///
 /// let pt_1 = Plaintext::rand(degree, t, &mut rng);
 /// let pt_2 = Plaintext::rand(degree, t, &mut rng);
@@ -348,9 +1627,61 @@ impl Mul<(Ciphertext, &RelinearizationKey2)> for Ciphertext {
     type Output = Self;
     fn mul(self, other: (Ciphertext, &RelinearizationKey2)) -> Self::Output {
         let (other_ct, rlk_2) = other;
+        let (this, other_ct) = self
+            .align_compatible(&other_ct)
+            .unwrap_or_else(|err| panic!("cannot multiply: {err}"));
 
-        let (c_0, c_1, c_2) = self.basic_mul(other_ct);
+        let (c_0, c_1, c_2) = this.basic_mul(other_ct.into_owned());
+
+        this.relinearization_2(c_0, c_1, c_2, rlk_2)
+    }
+}
+
+/// Multiply ciphertexts under the same moduli as a balanced tree of `Mul` with the Version 1
+/// relinearization key `rlk`, so that the product of `n` ciphertexts has a multiplicative depth
+/// of `ceil(log_2(n))` rather than `n - 1`. Panics if `cts` is empty.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 4;
+/// # let q = 1 << 40;
+/// # let t = 8;
+/// #
+/// use verifiable_bfv::bfv::{product_with, Plaintext, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+/// let rlk = secret_key.relin_key_gen_1(q, std_dev, &mut rng, 1 << 10).unwrap();
+///
+/// let cts = [2, 3, 5]
+///     .map(|m| Plaintext::new(vec![m, 0, 0, 0], t).encrypt(&public_key, std_dev, &mut rng));
+/// let product = product_with(cts, &rlk);
+/// assert_eq!(product.decrypt(&secret_key), Plaintext::new(vec![6, 0, 0, 0], t));
+/// ```
+pub fn product_with(
+    cts: impl IntoIterator<Item = Ciphertext>,
+    rlk: &RelinearizationKey1,
+) -> Ciphertext {
+    let mut layer = cts.into_iter().collect::<Vec<_>>();
+    assert!(!layer.is_empty(), "no ciphertexts to multiply");
+    while layer.len() > 1 {
+        let mut cts = layer.into_iter();
+        layer = Vec::with_capacity(cts.len().div_ceil(2));
+        while let Some(lhs) = cts.next() {
+            layer.push(match cts.next() {
+                Some(rhs) => lhs * (rhs, rlk),
+                None => lhs,
+            });
+        }
+    }
+    layer.pop().unwrap()
+}
 
-        self.relinearization_2(c_0, c_1, c_2, rlk_2)
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
     }
 }
@@ -0,0 +1,167 @@
+use super::ciphertext::Ciphertext;
+use super::error::BfvError;
+use super::keys::{PublicKey, SecretKey};
+use super::plaintext::Plaintext;
+use rand::{CryptoRng, RngCore};
+use std::ops::{Add, Sub};
+
+/// A vector of BFV12 Ciphertexts sharing the same degree and moduli `(q, t)`, with element-wise
+/// operations
+///
+/// The shared parameters are checked when the vector is built, and the lengths and parameters of
+/// both operands are checked by every element-wise operation, which return `LengthMismatch` or
+/// `ParameterMismatch` instead of combining unrelated ciphertexts.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 4;
+/// # let q = 65536;
+/// # let t = 8;
+/// #
+/// use verifiable_bfv::bfv::{CiphertextVec, Plaintext, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+///
+/// let pts_1 = [Plaintext::new(vec![1, 2, 3, 4], t), Plaintext::new(vec![5, 6, 7, 0], t)];
+/// let pts_2 = [Plaintext::new(vec![1, 1, 1, 1], t), Plaintext::new(vec![0, 0, 0, 1], t)];
+/// let cts_1 = CiphertextVec::encrypt(&pts_1, &public_key, std_dev, &mut rng);
+/// let cts_2 = CiphertextVec::encrypt(&pts_2, &public_key, std_dev, &mut rng);
+///
+/// let sum = (&cts_1 + &cts_2).unwrap();
+/// assert_eq!(
+///     sum.decrypt_all(&secret_key),
+///     [Plaintext::new(vec![2, 3, 4, 5], t), Plaintext::new(vec![5, 6, 7, 1], t)]
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CiphertextVec {
+    cts: Vec<Ciphertext>,
+}
+
+impl CiphertextVec {
+    /// Wrap ciphertexts sharing the degree and moduli of the first one, or return
+    /// `ParameterMismatch` with the index of the first one that does not
+    pub fn new(cts: Vec<Ciphertext>) -> Result<CiphertextVec, BfvError> {
+        if let Some(first) = cts.first() {
+            let first = params(first);
+            if let Some(index) = cts.iter().position(|ct| params(ct) != first) {
+                return Err(BfvError::ParameterMismatch { index });
+            }
+        }
+        Ok(CiphertextVec { cts })
+    }
+
+    /// Encrypt every plaintext with the public key. The plaintexts must share the same degree and
+    /// plaintext modulus.
+    pub fn encrypt<T: RngCore + CryptoRng>(
+        pts: &[Plaintext],
+        pub_key: &PublicKey,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> CiphertextVec {
+        let cts = pts
+            .iter()
+            .map(|pt| pt.encrypt(pub_key, std_dev, rng))
+            .collect();
+        CiphertextVec::new(cts).expect("the plaintexts have different degrees or moduli")
+    }
+
+    /// Decrypt every ciphertext with the secret key
+    pub fn decrypt_all(&self, secret_key: &SecretKey) -> Vec<Plaintext> {
+        self.cts.iter().map(|ct| ct.decrypt(secret_key)).collect()
+    }
+
+    /// Multiply each ciphertext by the plaintext of the same index
+    pub fn mul_plain(&self, pts: &[Plaintext]) -> Result<CiphertextVec, BfvError> {
+        self.check_len(pts.len())?;
+        let cts = self
+            .cts
+            .iter()
+            .zip(pts)
+            .map(|(ct, pt)| ct.mul_plain(pt))
+            .collect();
+        Ok(CiphertextVec { cts })
+    }
+
+    /// Return the number of ciphertexts
+    pub fn len(&self) -> usize {
+        self.cts.len()
+    }
+
+    /// Return whether the vector holds no ciphertext
+    pub fn is_empty(&self) -> bool {
+        self.cts.is_empty()
+    }
+
+    /// Return the ciphertexts
+    pub fn as_slice(&self) -> &[Ciphertext] {
+        &self.cts
+    }
+
+    /// Return the ciphertexts, consuming the vector
+    pub fn into_vec(self) -> Vec<Ciphertext> {
+        self.cts
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), BfvError> {
+        if len != self.len() {
+            return Err(BfvError::LengthMismatch {
+                expected: self.len(),
+                actual: len,
+            });
+        }
+        Ok(())
+    }
+
+    // Apply `op` to the ciphertexts of the same index, after checking that both vectors have the
+    // same length and parameters.
+    fn zip_with(
+        &self,
+        other: &CiphertextVec,
+        op: impl Fn(&Ciphertext, &Ciphertext) -> Ciphertext,
+    ) -> Result<CiphertextVec, BfvError> {
+        self.check_len(other.len())?;
+        if let (Some(lhs), Some(rhs)) = (self.cts.first(), other.cts.first()) {
+            if params(lhs) != params(rhs) {
+                return Err(BfvError::ParameterMismatch { index: 0 });
+            }
+        }
+        let cts = self
+            .cts
+            .iter()
+            .zip(&other.cts)
+            .map(|(lhs, rhs)| op(lhs, rhs))
+            .collect();
+        Ok(CiphertextVec { cts })
+    }
+}
+
+// The degree and moduli `(q, t)` the ciphertexts of a vector share.
+fn params(ct: &Ciphertext) -> (usize, i64, i64) {
+    (ct.c_0.degree(), ct.q, ct.t)
+}
+
+/// Collect ciphertexts into a `CiphertextVec`, checking their parameters as `CiphertextVec::new`
+impl FromIterator<Ciphertext> for Result<CiphertextVec, BfvError> {
+    fn from_iter<I: IntoIterator<Item = Ciphertext>>(iter: I) -> Self {
+        CiphertextVec::new(iter.into_iter().collect())
+    }
+}
+
+/// Add two ciphertext vectors element-wise
+impl Add<&CiphertextVec> for &CiphertextVec {
+    type Output = Result<CiphertextVec, BfvError>;
+    fn add(self, other: &CiphertextVec) -> Self::Output {
+        self.zip_with(other, |lhs, rhs| lhs + rhs)
+    }
+}
+
+/// Subtract two ciphertext vectors element-wise
+impl Sub<&CiphertextVec> for &CiphertextVec {
+    type Output = Result<CiphertextVec, BfvError>;
+    fn sub(self, other: &CiphertextVec) -> Self::Output {
+        self.zip_with(other, |lhs, rhs| lhs - rhs)
+    }
+}
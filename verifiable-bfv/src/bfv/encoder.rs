@@ -0,0 +1,78 @@
+use super::plaintext::Plaintext;
+
+/// A CKKS-style approximate encoder of real numbers into BFV12 plaintexts
+///
+/// A vector `x` of at most `degree` reals is encoded coefficient-wise as the polynomial with
+/// coefficients `[round(x_i * scale)]_t`, and decoded by dividing the centered coefficients by
+/// `scale`. Each encoded value is off by at most `1 / (2 * scale)`, and the encoding only holds
+/// while `|x_i| * scale < t / 2`.
+///
+/// Homomorphic addition of ciphertexts adds the encoded vectors, accumulating the rounding errors.
+/// Multiplying by a plaintext multiplies the polynomials, so the product is encoded at
+/// `scale^2`, to be decoded with `product_encoder`. A constant polynomial multiplies every value,
+/// while others convolve the vectors negacyclically. There is no rescaling: the plaintext
+/// modulus `t` must leave room for the largest scale used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CkksEncoder {
+    degree: usize,
+    t: i64,
+    scale: f64,
+}
+
+impl CkksEncoder {
+    /// Instantiate a new encoder
+    ///
+    /// * `degree`: the degree of the encoded plaintexts
+    /// * `t`: the plaintext modulus
+    /// * `scale`: the scale factor `Delta` the reals are multiplied with before rounding
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::CkksEncoder;
+    /// let encoder = CkksEncoder::new(4, 1 << 20, 1024.0);
+    ///
+    /// let pt = encoder.encode(&[0.5, -1.25, 3.0]);
+    /// assert_eq!(encoder.decode(&pt), vec![0.5, -1.25, 3.0, 0.0]);
+    /// ```
+    pub fn new(degree: usize, t: i64, scale: f64) -> CkksEncoder {
+        assert!(t > 1);
+        assert!(scale >= 1.0, "scale {scale} is below 1");
+        CkksEncoder { degree, t, scale }
+    }
+
+    /// Return the scale factor `Delta`
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Return the encoder for products of two plaintexts encoded by `self`, at `scale^2`
+    pub fn product_encoder(&self) -> CkksEncoder {
+        CkksEncoder::new(self.degree, self.t, self.scale * self.scale)
+    }
+
+    /// Encode `values` into a plaintext of degree `degree`, zero-padding the remaining coefficients
+    pub fn encode(&self, values: &[f64]) -> Plaintext {
+        assert!(values.len() <= self.degree);
+        let val = (0..self.degree)
+            .map(|i| {
+                let value = values.get(i).copied().unwrap_or(0.0);
+                let scaled = (value * self.scale).round();
+                assert!(
+                    scaled.abs() < (self.t / 2) as f64,
+                    "{value} overflows the plaintext modulus {} at scale {}",
+                    self.t,
+                    self.scale
+                );
+                scaled as i64
+            })
+            .collect();
+        Plaintext::new(val, self.t)
+    }
+
+    /// Decode a plaintext into its `degree` reals
+    pub fn decode(&self, pt: &Plaintext) -> Vec<f64> {
+        pt.to_vec()
+            .into_iter()
+            .map(|coeff| coeff as f64 / self.scale)
+            .collect()
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+
+use super::poly::Cyclotomic;
+
+/// `BfvError` is the error returned when decoding BFV12 values from bytes or combining
+/// ciphertexts
+#[derive(Debug, PartialEq)]
+pub enum BfvError {
+    /// A buffer has `actual` bytes where `expected` are required
+    BufferLength { expected: usize, actual: usize },
+    /// A ciphertext of degree `0`, which has no coefficients, is decoded
+    InvalidDegree { degree: u64 },
+    /// The moduli `(q, t)` do not satisfy `1 < t < q`
+    InvalidModulus { q: i64, t: i64 },
+    /// A coefficient lies outside of `[0, q)`
+    CoefficientOutOfRange { value: i64, q: i64 },
+    /// A ciphertext of `size` polynomials must be relinearized, but no key is attached
+    MissingRelinearizationKey { size: usize },
+    /// No Galois key for the automorphism `X -> X^k` is available
+    MissingGaloisKey { k: usize },
+    /// A collection holds `actual` elements where `expected` are required
+    LengthMismatch { expected: usize, actual: usize },
+    /// The ciphertext at `index` does not share the degree and moduli of the others
+    ParameterMismatch { index: usize },
+    /// A ciphertext has a noise budget of `budget <= 0` bits, so it may not decrypt correctly
+    NoiseBudgetExhausted { budget: i64 },
+    /// The modulus at `index` of a modulus chain is not in `(1, q_{index - 1})`
+    InvalidModulusChain { index: usize },
+    /// The modulus `q` is not in the modulus chain
+    ModulusNotInChain { q: i64 },
+    /// The modulus `q` is the last one of the modulus chain, so there is no level to switch to
+    BottomOfModulusChain { q: i64 },
+    /// Ciphertexts under `q` at level `level` and under `other_q` at level `other_level` are not
+    /// in one modulus chain, as the one with the larger modulus is not at the lower level
+    ModulusChainMismatch {
+        q: i64,
+        level: usize,
+        other_q: i64,
+        other_level: usize,
+    },
+    /// A ciphertext, plaintext or key of degree `actual` meets one of degree `expected`
+    DegreeMismatch { expected: usize, actual: usize },
+    /// A ciphertext, plaintext or key under the modulus `actual` meets one under `expected`
+    ModulusMismatch { expected: i64, actual: i64 },
+    /// The coefficient `index` of a polynomial of degree `degree` is accessed
+    IndexOutOfRange { index: usize, degree: usize },
+    /// An operation that is only implemented in `Z[X]/(X^N + 1)` is used in the ring `ring`
+    UnsupportedRing { ring: Cyclotomic },
+}
+
+impl fmt::Display for BfvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BfvError::BufferLength { expected, actual } => {
+                write!(f, "expected {expected} bytes, got {actual}")
+            }
+            BfvError::InvalidDegree { degree } => {
+                write!(f, "invalid degree {degree}")
+            }
+            BfvError::InvalidModulus { q, t } => {
+                write!(f, "invalid moduli q = {q} and t = {t}")
+            }
+            BfvError::CoefficientOutOfRange { value, q } => {
+                write!(f, "{value} is not in [0, {q})")
+            }
+            BfvError::MissingRelinearizationKey { size } => {
+                write!(f, "a ciphertext of size {size} needs a relinearization key")
+            }
+            BfvError::MissingGaloisKey { k } => {
+                write!(f, "no Galois key for the automorphism X -> X^{k}")
+            }
+            BfvError::LengthMismatch { expected, actual } => {
+                write!(f, "expected {expected} elements, got {actual}")
+            }
+            BfvError::ParameterMismatch { index } => {
+                write!(f, "ciphertext {index} has a different degree or moduli")
+            }
+            BfvError::NoiseBudgetExhausted { budget } => {
+                write!(f, "the noise budget of {budget} bits is exhausted")
+            }
+            BfvError::InvalidModulusChain { index } => {
+                write!(
+                    f,
+                    "modulus {index} of the chain is not below the previous one"
+                )
+            }
+            BfvError::ModulusNotInChain { q } => {
+                write!(f, "the modulus {q} is not in the modulus chain")
+            }
+            BfvError::BottomOfModulusChain { q } => {
+                write!(f, "the modulus {q} is the bottom of the modulus chain")
+            }
+            BfvError::ModulusChainMismatch {
+                q,
+                level,
+                other_q,
+                other_level,
+            } => {
+                write!(
+                    f,
+                    "the moduli {q} at level {level} and {other_q} at level {other_level} are not \
+                     in one modulus chain"
+                )
+            }
+            BfvError::DegreeMismatch { expected, actual } => {
+                write!(f, "expected degree {expected}, got degree {actual}")
+            }
+            BfvError::ModulusMismatch { expected, actual } => {
+                write!(f, "expected modulus {expected}, got modulus {actual}")
+            }
+            BfvError::IndexOutOfRange { index, degree } => {
+                write!(f, "index {index} is out of range for degree {degree}")
+            }
+            BfvError::UnsupportedRing { ring } => {
+                write!(
+                    f,
+                    "the ring {ring:?} is not supported, only Z[X]/(X^N + 1) is"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BfvError {}
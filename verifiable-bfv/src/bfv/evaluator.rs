@@ -0,0 +1,120 @@
+use super::ciphertext::Ciphertext;
+use super::keys::{PublicKey, RelinearizationKey1, SecretKey};
+use super::params::BfvParameters;
+use super::plaintext::Plaintext;
+use rand::{CryptoRng, RngCore};
+
+/// Encrypts plaintexts with a public key and the error of a set of parameters
+#[derive(Clone, Debug)]
+pub struct Encryptor {
+    params: BfvParameters,
+    public_key: PublicKey,
+}
+
+impl Encryptor {
+    /// Instantiate an encryptor for the public key of a key generated for `params`
+    pub fn new(params: BfvParameters, public_key: PublicKey) -> Encryptor {
+        Encryptor { params, public_key }
+    }
+
+    /// Encrypt a plaintext, sampling the error with `params.std_dev()`
+    pub fn encrypt<T: RngCore + CryptoRng>(&self, pt: &Plaintext, rng: &mut T) -> Ciphertext {
+        pt.encrypt(&self.public_key, self.params.std_dev, rng)
+    }
+}
+
+/// Decrypts ciphertexts with a secret key, which is only held by the decryptor so that the
+/// parties evaluating or encrypting never see it
+#[derive(Clone, Debug)]
+pub struct Decryptor {
+    secret_key: SecretKey,
+}
+
+impl Decryptor {
+    /// Instantiate a decryptor for the secret key
+    pub fn new(secret_key: SecretKey) -> Decryptor {
+        Decryptor { secret_key }
+    }
+
+    /// Decrypt a ciphertext
+    pub fn decrypt(&self, ct: &Ciphertext) -> Plaintext {
+        ct.decrypt(&self.secret_key)
+    }
+}
+
+/// Evaluates homomorphic operations over ciphertexts with the public keys of a BFV12 instance, so
+/// that expressions do not thread the keys and parameters through every operation
+///
+/// The operations delegate to those of `Ciphertext` and panic as they do on ciphertexts under
+/// different moduli. Products are relinearized with the Version 1 relinearization key.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// #
+/// use verifiable_bfv::bfv::{BfvParameters, Decryptor, Evaluator, KeyGenerator, Plaintext};
+/// let params = BfvParameters::new(4, 65536, 16, 3.2);
+/// let mut keygen = KeyGenerator::new(params, &mut rng);
+/// let rlk = keygen.relinearization_key(16).unwrap();
+/// let ev = Evaluator::new(params, keygen.public_key(), rlk);
+/// let decryptor = Decryptor::new(keygen.secret_key().clone());
+///
+/// let encryptor = ev.encryptor();
+/// let pt = Plaintext::new(vec![1, 2, 0, 0], params.t());
+/// let ct = encryptor.encrypt(&pt, &mut rng);
+///
+/// let square = ev.mul(&ct, &ct);
+/// assert_eq!(decryptor.decrypt(&square), Plaintext::new(vec![1, 4, 4, 0], params.t()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Evaluator {
+    params: BfvParameters,
+    public_key: PublicKey,
+    rlk: RelinearizationKey1,
+}
+
+impl Evaluator {
+    /// Instantiate an evaluator for the public key and relinearization key of a key generated for
+    /// `params`
+    pub fn new(
+        params: BfvParameters,
+        public_key: PublicKey,
+        rlk: RelinearizationKey1,
+    ) -> Evaluator {
+        Evaluator {
+            params,
+            public_key,
+            rlk,
+        }
+    }
+
+    /// Return the parameters of the instance
+    pub fn params(&self) -> &BfvParameters {
+        &self.params
+    }
+
+    /// Return an encryptor for the public key of the evaluator
+    pub fn encryptor(&self) -> Encryptor {
+        Encryptor::new(self.params, self.public_key.clone())
+    }
+
+    /// Add two ciphertexts
+    pub fn add(&self, a: &Ciphertext, b: &Ciphertext) -> Ciphertext {
+        a + b
+    }
+
+    /// Subtract two ciphertexts
+    pub fn sub(&self, a: &Ciphertext, b: &Ciphertext) -> Ciphertext {
+        a - b
+    }
+
+    /// Multiply two ciphertexts and relinearize the product
+    pub fn mul(&self, a: &Ciphertext, b: &Ciphertext) -> Ciphertext {
+        a.clone() * (b.clone(), &self.rlk)
+    }
+
+    /// Multiply a ciphertext by a plaintext, see `Ciphertext::mul_plain`
+    pub fn mul_plain(&self, a: &Ciphertext, pt: &Plaintext) -> Ciphertext {
+        a.mul_plain(pt)
+    }
+}
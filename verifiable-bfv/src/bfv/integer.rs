@@ -0,0 +1,96 @@
+use super::ciphertext::Ciphertext;
+use super::keys::{PublicKey, RelinearizationKey1, SecretKey};
+use super::plaintext::Plaintext;
+use rand::{CryptoRng, RngCore};
+use std::ops::{Add, Mul, Sub};
+
+/// A single integer modulo `t`, encrypted as the constant coefficient of a BFV12 Ciphertext, with
+/// the Version 1 relinearization key applied after every multiplication
+///
+/// * `ct` = an encryption of the constant plaintext `m`, `[m, 0, ..., 0]`
+/// * `rlk` = the relinearization key of the secret key `ct` decrypts with
+///
+/// Constant polynomials are closed under the ring operations, so `+`, `-` and `*` act on the
+/// integers modulo `t` directly. Combining integers under different moduli panics, as `Ciphertext`
+/// operations do.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 4;
+/// # let q = 1 << 40;
+/// # let t = 16;
+/// #
+/// use verifiable_bfv::bfv::{EncryptedInteger, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+/// let rlk = secret_key.relin_key_gen_1(q, std_dev, &mut rng, 1 << 10).unwrap();
+///
+/// let [a, b, c] =
+///     [3, 4, 5].map(|m| EncryptedInteger::encrypt(m, t, &public_key, &rlk, std_dev, &mut rng));
+/// assert_eq!(((a + b) * c).decrypt(&secret_key), 35 % t);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EncryptedInteger<'a> {
+    ct: Ciphertext,
+    rlk: &'a RelinearizationKey1,
+}
+
+impl<'a> EncryptedInteger<'a> {
+    /// Wrap a ciphertext of a constant plaintext, with the relinearization key of its secret key
+    pub fn new(ct: Ciphertext, rlk: &'a RelinearizationKey1) -> EncryptedInteger<'a> {
+        EncryptedInteger { ct, rlk }
+    }
+
+    /// Encrypt `value mod t` with the public key, as the constant plaintext of its degree
+    pub fn encrypt<T: RngCore + CryptoRng>(
+        value: i64,
+        t: i64,
+        pub_key: &PublicKey,
+        rlk: &'a RelinearizationKey1,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> EncryptedInteger<'a> {
+        let mut val = vec![0; pub_key.p_0.degree()];
+        val[0] = value.rem_euclid(t);
+        let ct = Plaintext::new(val, t).encrypt(pub_key, std_dev, rng);
+        EncryptedInteger::new(ct, rlk)
+    }
+
+    /// Return the underlying ciphertext
+    pub fn ciphertext(&self) -> &Ciphertext {
+        &self.ct
+    }
+
+    /// Decrypt with the secret key, returning the integer as its residue in `[0, t)`
+    pub fn decrypt(&self, secret_key: &SecretKey) -> i64 {
+        let pt = self.ct.decrypt(secret_key);
+        pt.poly().val()[0].rem_euclid(pt.t())
+    }
+}
+
+/// Add two encrypted integers modulo `t`
+impl<'a> Add<EncryptedInteger<'a>> for EncryptedInteger<'a> {
+    type Output = EncryptedInteger<'a>;
+    fn add(self, other: EncryptedInteger<'a>) -> Self::Output {
+        EncryptedInteger::new(self.ct + other.ct, self.rlk)
+    }
+}
+
+/// Subtract two encrypted integers modulo `t`
+impl<'a> Sub<EncryptedInteger<'a>> for EncryptedInteger<'a> {
+    type Output = EncryptedInteger<'a>;
+    fn sub(self, other: EncryptedInteger<'a>) -> Self::Output {
+        EncryptedInteger::new(self.ct - other.ct, self.rlk)
+    }
+}
+
+/// Multiply two encrypted integers modulo `t`, relinearizing the product with the key of the left
+/// operand
+impl<'a> Mul<EncryptedInteger<'a>> for EncryptedInteger<'a> {
+    type Output = EncryptedInteger<'a>;
+    fn mul(self, other: EncryptedInteger<'a>) -> Self::Output {
+        EncryptedInteger::new(self.ct * (other.ct, self.rlk), self.rlk)
+    }
+}
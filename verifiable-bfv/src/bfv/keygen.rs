@@ -0,0 +1,155 @@
+use super::error::BfvError;
+use super::keys::{
+    GaloisKeySet, PublicKey, RelinearizationKey1, RelinearizationKeyChain, SecretKey,
+};
+use super::params::BfvParameters;
+use rand::{CryptoRng, RngCore};
+
+/// All the keys of a BFV12 instance, as produced by `KeyGenerator::key_set`
+#[derive(Clone, Debug)]
+pub struct KeySet {
+    pub secret_key: SecretKey,
+    pub public_key: PublicKey,
+    pub relinearization_key: RelinearizationKey1,
+    pub galois_keys: GaloisKeySet,
+}
+
+/// A generator of the keys of a BFV12 instance
+///
+/// The secret key is sampled once in `new`, and every other key is derived from it with the
+/// parameters and the RNG the generator was instantiated with.
+pub struct KeyGenerator<R: RngCore + CryptoRng> {
+    params: BfvParameters,
+    rng: R,
+    secret_key: SecretKey,
+}
+
+impl<R: RngCore + CryptoRng> KeyGenerator<R> {
+    /// Instantiate a new key generator, sampling a secret key of degree `params.degree()`
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let keygen_rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+    /// #
+    /// use verifiable_bfv::bfv::{BfvParameters, KeyGenerator, Plaintext};
+    /// let params = BfvParameters::new(4, 65536, 16, 3.2);
+    /// let mut keygen = KeyGenerator::new(params, keygen_rng);
+    ///
+    /// let public_key = keygen.public_key();
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], params.t());
+    /// let ct = pt.encrypt(&public_key, params.std_dev(), &mut rng);
+    /// assert_eq!(ct.decrypt(keygen.secret_key()), pt);
+    /// ```
+    pub fn new(params: BfvParameters, mut rng: R) -> KeyGenerator<R> {
+        let secret_key = SecretKey::generate_in(params.ring, &mut rng);
+        KeyGenerator {
+            params,
+            rng,
+            secret_key,
+        }
+    }
+
+    /// Return the parameters the keys are generated for
+    pub fn params(&self) -> &BfvParameters {
+        &self.params
+    }
+
+    /// Return the secret key
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.secret_key
+    }
+
+    /// Generate a public key for the secret key
+    pub fn public_key(&mut self) -> PublicKey {
+        self.secret_key
+            .public_key_gen(self.params.q, self.params.std_dev, &mut self.rng)
+    }
+
+    /// Generate a relinearization key (Version 1) in decomposition base `base`
+    ///
+    /// See `SecretKey::relin_key_gen_1` for the choice of `base`, and for the rings it supports.
+    pub fn relinearization_key(&mut self, base: i64) -> Result<RelinearizationKey1, BfvError> {
+        self.secret_key
+            .relin_key_gen_1(self.params.q, self.params.std_dev, &mut self.rng, base)
+    }
+
+    /// Generate a chain of relinearization keys (Version 1) for `s^2, ..., s^max_degree` in
+    /// decomposition base `base`, see `SecretKey::relin_key_chain_gen`
+    pub fn relinearization_key_chain(
+        &mut self,
+        base: i64,
+        max_degree: usize,
+    ) -> Result<RelinearizationKeyChain, BfvError> {
+        self.secret_key.relin_key_chain_gen(
+            self.params.q,
+            self.params.std_dev,
+            &mut self.rng,
+            base,
+            max_degree,
+        )
+    }
+
+    /// Generate the Galois keys for the rotation steps `steps`
+    ///
+    /// The keys use the decomposition base `floor(log_2(q))`, which keeps the key switching error
+    /// low.
+    pub fn galois_keys(&mut self, steps: &[usize]) -> Result<GaloisKeySet, BfvError> {
+        let base = (self.params.q as f64).log2() as i64;
+        let keys = steps
+            .iter()
+            .map(|step| {
+                let gk = self.secret_key.galois_key_gen(
+                    self.params.q,
+                    self.params.std_dev,
+                    &mut self.rng,
+                    base,
+                    *step,
+                )?;
+                Ok((*step, gk))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(GaloisKeySet {
+            keys,
+            conjugation: None,
+        })
+    }
+
+    /// Generate the Galois keys needed by `Ciphertext::slot_sum`: the rotation steps
+    /// `1, 2, 4, ..., N/4` and the conjugation, in the same base as `galois_keys`
+    pub fn slot_sum_keys(&mut self) -> Result<GaloisKeySet, BfvError> {
+        let degree = self.params.degree;
+        let steps = (0..(degree / 2).trailing_zeros())
+            .map(|j| 1 << j)
+            .collect::<Vec<_>>();
+        let base = (self.params.q as f64).log2() as i64;
+        let conjugation = self.secret_key.conjugation_key_gen(
+            self.params.q,
+            self.params.std_dev,
+            &mut self.rng,
+            base,
+        )?;
+        Ok(self.galois_keys(&steps)?.with_conjugation(conjugation))
+    }
+
+    /// Generate all the keys at once: the public key, a relinearization key in base `base` and
+    /// the Galois keys for the rotation steps `steps`
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use verifiable_bfv::bfv::{BfvParameters, KeyGenerator};
+    /// let params = BfvParameters::new(4, 65536, 16, 3.2);
+    /// let keys = KeyGenerator::new(params, rng).key_set(16, &[1]).unwrap();
+    /// assert!(keys.galois_keys.get(1).is_some());
+    /// ```
+    pub fn key_set(&mut self, base: i64, steps: &[usize]) -> Result<KeySet, BfvError> {
+        Ok(KeySet {
+            secret_key: self.secret_key.clone(),
+            public_key: self.public_key(),
+            relinearization_key: self.relinearization_key(base)?,
+            galois_keys: self.galois_keys(steps)?,
+        })
+    }
+}
@@ -1,12 +1,25 @@
-use super::poly::Poly;
+use super::error::BfvError;
+use super::poly::{Cyclotomic, Poly};
 use super::random_source;
 use rand::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
 
 /// A BFV12 Secret Key
+///
+/// Side channels: the operations using the secret key (`Ciphertext::decrypt`,
+/// `Plaintext::to_vec` and `Ciphertext::noise_budget`) have no early exits and no branches on
+/// secret coefficients, so their control flow and memory accesses only depend on the public degree
+/// and moduli. This guards against timing attacks by a remote observer of the decryption, but not
+/// against an attacker sharing the hardware: the native division and the floating-point rounding
+/// used in the reductions may still take data-dependent time, and the memory holding the key is
+/// not zeroized on drop. Key generation is not covered either, as the Gaussian error is sampled by
+/// rejection.
 #[derive(Clone, Debug)]
 pub struct SecretKey {
     ///`s <- R_2`
     pub(crate) poly: Poly,
+    /// The ring `R = Z[X]/(Phi)` the keys derived from `s` live in
+    pub(crate) ring: Cyclotomic,
 }
 
 /// A BFV12 Public Key
@@ -14,25 +27,53 @@ pub struct SecretKey {
 /// * `p_0` = `[-(a * s + e)]_q`
 /// * `p_1` = `a`
 /// * `q` = ciphertext modulus
+/// * `ring` = the cyclotomic polynomial `Phi` of the ring `R_q = Z_q[X]/(Phi)`
 #[derive(Clone, Debug)]
 pub struct PublicKey {
     pub(crate) p_0: Poly,
     pub(crate) p_1: Poly,
     pub(crate) q: i64,
+    pub(crate) ring: Cyclotomic,
 }
 
-/// A BFV12 Relinearization Key, Version 1
+/// A BFV12 Key Switching Key, switching a ciphertext under `s'` to one under `s`
 ///
-/// * `val` = `[ ( [-(a_i * s + e_i) + T^i * s^2]_q, a_i) : i \in (0..l)]`
-/// * `T` = the decomposition base used for relinearization
+/// * `val` = `[ ( [-(a_i * s + e_i) + T^i * s']_q, a_i) : i \in (0..l)]`
+/// * `T` = the decomposition base used for key switching
 /// * `l` = `floor(log_t(q))`, the number of levels to decompose
 #[derive(Clone, Debug)]
-pub struct RelinearizationKey1 {
+pub struct KeySwitchKey {
     pub(crate) val: Vec<(Poly, Poly)>,
     pub(crate) base: i64,
     pub(crate) l: usize,
 }
 
+/// A BFV12 Relinearization Key, Version 1
+///
+/// * `ksk` = the key switching key from `s^2` to `s`
+#[derive(Clone, Debug)]
+pub struct RelinearizationKey1 {
+    pub(crate) ksk: KeySwitchKey,
+}
+
+/// A chain of BFV12 Relinearization Keys, Version 1, for ciphertexts of any size up to a maximum
+///
+/// * `keys` = `[ksk_i : i \in (2..=max_degree)]`, where `ksk_i` switches from `s^i` to `s`
+///
+/// A ciphertext `(c_0, ..., c_k)` decrypting with `(1, s, ..., s^k)` is relinearized by key
+/// switching every `c_i` with `i >= 2`, which needs `k <= max_degree`.
+#[derive(Clone, Debug)]
+pub struct RelinearizationKeyChain {
+    pub(crate) keys: Vec<KeySwitchKey>,
+}
+
+impl RelinearizationKeyChain {
+    /// Return the highest power of `s` the chain relinearizes
+    pub fn max_degree(&self) -> usize {
+        self.keys.len() + 1
+    }
+}
+
 /// A BFV12 Relinearization Key, Version 2
 ///
 /// * `rlk_0` = `([-(a * s + e) + p * s^2]_{p*q})`
@@ -45,6 +86,164 @@ pub struct RelinearizationKey2 {
     pub(crate) p: i64,
 }
 
+/// A BFV12 Galois Key, switching a ciphertext under `s(X^k)` back to one under `s`
+///
+/// * `ksk` = the key switching key from `s(X^k)` to `s`
+/// * `k` = the odd exponent of the automorphism `X -> X^k`
+#[derive(Clone, Debug)]
+pub struct GaloisKey {
+    pub(crate) ksk: KeySwitchKey,
+    pub(crate) k: usize,
+}
+
+/// A set of BFV12 Galois Keys, indexed by rotation step
+///
+/// The key for step `r` applies the automorphism `X -> X^{3^r mod 2N}`. As `3` only generates half
+/// of the Galois group, the set may also hold a key for the conjugation `X -> X^{2N - 1}`.
+#[derive(Clone, Debug, Default)]
+pub struct GaloisKeySet {
+    pub(crate) keys: BTreeMap<usize, GaloisKey>,
+    pub(crate) conjugation: Option<GaloisKey>,
+}
+
+impl GaloisKeySet {
+    /// Return the Galois key for rotation step `step`, if the set holds one
+    pub fn get(&self, step: usize) -> Option<&GaloisKey> {
+        self.keys.get(&step)
+    }
+
+    /// Return the rotation steps the set holds keys for, in increasing order
+    pub fn steps(&self) -> impl Iterator<Item = usize> + '_ {
+        self.keys.keys().copied()
+    }
+
+    /// Return the key for the conjugation `X -> X^{2N - 1}`, if the set holds one
+    pub fn conjugation(&self) -> Option<&GaloisKey> {
+        self.conjugation.as_ref()
+    }
+
+    /// Add the key for the conjugation, as generated by `SecretKey::conjugation_key_gen`
+    pub fn with_conjugation(mut self, gk: GaloisKey) -> GaloisKeySet {
+        self.conjugation = Some(gk);
+        self
+    }
+}
+
+impl KeySwitchKey {
+    /// Generate a key switching key from `from_sk` to `to_sk`, such that
+    /// `Ciphertext::key_switch` turns a ciphertext decrypting under `from_sk` into one decrypting
+    /// to the same plaintext under `to_sk`
+    ///
+    /// * `base`: the decomposition base, chosen as for `SecretKey::relin_key_gen_1`
+    /// * `q`: the ciphertext modulus
+    /// * `std_dev`: the standard deviation for error generation
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{KeySwitchKey, Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let new_secret_key = SecretKey::generate(degree, &mut rng);
+    /// let ksk = KeySwitchKey::generate(&secret_key, &new_secret_key, 16, q, std_dev, &mut rng);
+    ///
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+    /// assert_eq!(ct.key_switch(&ksk).decrypt(&new_secret_key), pt);
+    /// ```
+    pub fn generate<T: RngCore + CryptoRng>(
+        from_sk: &SecretKey,
+        to_sk: &SecretKey,
+        base: i64,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> KeySwitchKey {
+        KeySwitchKey::from_poly(&from_sk.poly, to_sk, base, q, std_dev, rng)
+    }
+
+    // Generate the key switching `from`, any polynomial of the same degree as `to_sk`, to `to_sk`.
+    pub(crate) fn from_poly<T: RngCore + CryptoRng>(
+        from: &Poly,
+        to_sk: &SecretKey,
+        base: i64,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> KeySwitchKey {
+        let degree = to_sk.poly.degree();
+        assert_eq!(from.degree(), degree);
+        let s = &to_sk.poly;
+        // l is the number of levels to decompose `from` and the switched polynomial into.
+        // l is a function of base (T in the paper): l = floor(log_T(q)).
+        let l = (q as f64).log(base as f64).floor() as usize;
+
+        let val = (0..l)
+            .map(|i| {
+                let a_i = random_source::get_uniform(q, degree, rng);
+                let e_i = random_source::get_gaussian(std_dev, degree, rng);
+                let base_i = base.pow(i as u32);
+                let ksk_i_raw = -(a_i.mul_ntt(s, q) + e_i) + from.mul_int_mod(base_i, q);
+                let ksk_i = ksk_i_raw % (q, degree);
+                (ksk_i, a_i)
+            })
+            .collect();
+        KeySwitchKey { val, base, l }
+    }
+
+    /// Return the decomposition base `T`
+    pub fn base(&self) -> i64 {
+        self.base
+    }
+}
+
+impl PublicKey {
+    /// Build a public key from its components `p_0 = [-(a * s + e)]_q` and `p_1 = a`, e.g. to
+    /// use a key generated elsewhere. Panics if the degrees of `p_0` and `p_1` differ.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// #
+    /// use verifiable_bfv::bfv::{PublicKey, SecretKey};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let rebuilt = PublicKey::from_parts(public_key.p0().clone(), public_key.p1().clone(), q);
+    /// assert_eq!(rebuilt.q(), q);
+    /// ```
+    pub fn from_parts(p_0: Poly, p_1: Poly, q: i64) -> PublicKey {
+        assert_eq!(p_0.degree(), p_1.degree());
+        assert!(q > 1);
+        let ring = Cyclotomic::Negacyclic(p_0.degree());
+        PublicKey { p_0, p_1, q, ring }
+    }
+
+    /// Return `p_0 = [-(a * s + e)]_q`
+    pub fn p0(&self) -> &Poly {
+        &self.p_0
+    }
+
+    /// Return `p_1 = a`
+    pub fn p1(&self) -> &Poly {
+        &self.p_1
+    }
+
+    /// Return the ciphertext modulus
+    pub fn q(&self) -> i64 {
+        self.q
+    }
+}
+
 impl SecretKey {
     /// Generate a secret key by sampling the coefficients of s uniformly
     /// from R_2, which in this implementation is the set {0, 1}.
@@ -56,14 +255,21 @@ impl SecretKey {
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::SecretKey;
+    /// use verifiable_bfv::bfv::SecretKey;
     ///
     /// let degree = 4;
     /// let secret_key = SecretKey::generate(degree, &mut rng);
     /// ```
     pub fn generate<T: RngCore + CryptoRng>(degree: usize, rng: &mut T) -> SecretKey {
+        SecretKey::generate_in(Cyclotomic::Negacyclic(degree), rng)
+    }
+
+    /// Generate a new secret key in the ring `Z[X]/(Phi)` for the cyclotomic polynomial `ring`,
+    /// which the public key and the encryptions derived from it then use
+    pub fn generate_in<T: RngCore + CryptoRng>(ring: Cyclotomic, rng: &mut T) -> SecretKey {
         SecretKey {
-            poly: random_source::get_uniform(2, degree, rng),
+            poly: random_source::get_uniform(2, ring.degree(), rng),
+            ring,
         }
     }
 
@@ -77,7 +283,7 @@ impl SecretKey {
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::SecretKey;
+    /// use verifiable_bfv::bfv::SecretKey;
     ///
     /// let degree = 4;
     /// let std_dev = 3.2;
@@ -98,9 +304,14 @@ impl SecretKey {
         let a = random_source::get_uniform(q, degree, rng);
         let e = random_source::get_gaussian(std_dev, degree, rng);
         let p_1 = a.clone();
-        let p_0 = (-(a.clone() * s.clone() + e)) % (q, degree);
+        let p_0 = (-(a.mul_ring(&s, q, self.ring) + e)) % (q, self.ring);
 
-        PublicKey { p_0, p_1, q }
+        PublicKey {
+            p_0,
+            p_1,
+            q,
+            ring: self.ring,
+        }
     }
 
     /// Generate a relinearization key, using the approach in Version 1
@@ -120,7 +331,7 @@ impl SecretKey {
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::SecretKey;
+    /// use verifiable_bfv::bfv::SecretKey;
     ///
     /// let degree = 4;
     /// let std_dev = 3.2;
@@ -128,32 +339,51 @@ impl SecretKey {
     /// let rlk_base = (q as f64).log2() as i64;
     ///
     /// let secret_key = SecretKey::generate(degree, &mut rng);
-    /// let relin_key_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, rlk_base);
+    /// let relin_key_1 = secret_key
+    ///     .relin_key_gen_1(q, std_dev, &mut rng, rlk_base)
+    ///     .unwrap();
     /// ```
+    ///
+    /// Ciphertext multiplication is only implemented in `Z[X]/(X^N + 1)`, so this returns
+    /// `BfvError::UnsupportedRing` for a secret key in a prime cyclotomic ring.
     pub fn relin_key_gen_1<T: RngCore + CryptoRng>(
         &self,
         q: i64,
         std_dev: f64,
         rng: &mut T,
         base: i64,
-    ) -> RelinearizationKey1 {
-        let degree = self.poly.degree();
-        let s = self.poly.clone();
-        // l is the number of levels to decompose s^2 and c_2 into.
-        // l is a function of base (T in the paper): l = floor(log_T(q)).
-        let l = (q as f64).log(base as f64).floor() as usize;
+    ) -> Result<RelinearizationKey1, BfvError> {
+        self.check_negacyclic()?;
+        let s_2 = self.poly.mul_ntt(&self.poly, q);
+        Ok(RelinearizationKey1 {
+            ksk: KeySwitchKey::from_poly(&s_2, self, base, q, std_dev, rng),
+        })
+    }
 
-        let val = (0..l)
-            .map(|i| {
-                let a_i = random_source::get_uniform(q, degree, rng);
-                let e_i = random_source::get_gaussian(std_dev, degree, rng);
-                let base_i = base.pow(i as u32);
-                let rlk_i_raw = -(a_i.clone() * s.clone() + e_i) + s.clone() * s.clone() * base_i;
-                let rlk_i = rlk_i_raw % (q, degree);
-                (rlk_i, a_i)
+    /// Generate a chain of relinearization keys (Version 1) for the powers `s^2, ..., s^max_degree`
+    /// of the secret key, which relinearizes ciphertexts of up to `max_degree + 1` polynomials
+    /// (see `SizedCiphertext::relinearize_with_chain`)
+    ///
+    /// * `q`, `std_dev`, `rng` and `base` are as for `relin_key_gen_1`
+    /// * `max_degree`: the highest power of `s` to relinearize, at least 2
+    pub fn relin_key_chain_gen<T: RngCore + CryptoRng>(
+        &self,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+        base: i64,
+        max_degree: usize,
+    ) -> Result<RelinearizationKeyChain, BfvError> {
+        assert!(max_degree >= 2);
+        self.check_negacyclic()?;
+        let mut s_i = self.poly.clone();
+        let keys = (2..=max_degree)
+            .map(|_| {
+                s_i = s_i.mul_ntt(&self.poly, q);
+                KeySwitchKey::from_poly(&s_i, self, base, q, std_dev, rng)
             })
             .collect();
-        RelinearizationKey1 { val, base, l }
+        Ok(RelinearizationKeyChain { keys })
     }
 
     /// Generate a relinearization key, using the approach in Version 2
@@ -171,7 +401,7 @@ impl SecretKey {
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::SecretKey;
+    /// use verifiable_bfv::bfv::SecretKey;
     ///
     /// let degree = 4;
     /// let std_dev = 3.2;
@@ -179,22 +409,110 @@ impl SecretKey {
     /// let p = 2_i64.pow(13) * q;
     ///
     /// let secret_key = SecretKey::generate(degree, &mut rng);
-    /// let relin_key_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p);
+    /// let relin_key_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p).unwrap();
     /// ```
+    ///
+    /// As for `relin_key_gen_1`, a secret key in a prime cyclotomic ring is rejected.
     pub fn relin_key_gen_2<T: RngCore + CryptoRng>(
         &self,
         q: i64,
         std_dev: f64,
         rng: &mut T,
         p: i64,
-    ) -> RelinearizationKey2 {
+    ) -> Result<RelinearizationKey2, BfvError> {
+        self.check_negacyclic()?;
         let degree = self.poly.degree();
         let s = self.poly.clone();
 
-        let a = random_source::get_uniform(p * q, degree, rng);
+        let pq = p * q;
+        let a = random_source::get_uniform(pq, degree, rng);
         let e = random_source::get_gaussian(std_dev, degree, rng);
-        let rlk_0 = (-(a.clone() * s.clone() + e) + s.clone() * s.clone() * p) % (p * q, degree);
+        let s_2 = s.mul_ntt(&s, pq).mul_int_mod(p, pq);
+        let rlk_0 = (-(a.mul_ntt(&s, pq) + e) + s_2) % (pq, degree);
+
+        Ok(RelinearizationKey2 { rlk_0, rlk_1: a, p })
+    }
+
+    /// Generate a Galois key for rotation step `step`, which switches ciphertexts under the
+    /// automorphism `X -> X^k` of the secret key back to `s`, with `k = 3^step mod 2N`
+    ///
+    /// * `q`: the ciphertext modulus
+    /// * `std_dev`: the standard deviation for error generation
+    /// * `rng`: the RNG used to generate randomness
+    /// * `base`: the decomposition base used for key switching, chosen as for `relin_key_gen_1`
+    /// * `step`: the rotation step
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use verifiable_bfv::bfv::SecretKey;
+    ///
+    /// let degree = 4;
+    /// let std_dev = 3.2;
+    /// let q = 65536;
+    /// let base = (q as f64).log2() as i64;
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let galois_key = secret_key
+    ///     .galois_key_gen(q, std_dev, &mut rng, base, 1)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// The automorphisms are those of `Z[X]/(X^N + 1)`, so a secret key in a prime cyclotomic
+    /// ring is rejected with `BfvError::UnsupportedRing`.
+    pub fn galois_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+        base: i64,
+        step: usize,
+    ) -> Result<GaloisKey, BfvError> {
+        let k = galois_element(step, self.poly.degree());
+        self.automorphism_key_gen(q, std_dev, rng, base, k)
+    }
 
-        RelinearizationKey2 { rlk_0, rlk_1: a, p }
+    /// Generate the Galois key for the conjugation `X -> X^{2N - 1}`, which no rotation step
+    /// reaches, with the same arguments as `galois_key_gen`
+    pub fn conjugation_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+        base: i64,
+    ) -> Result<GaloisKey, BfvError> {
+        let k = 2 * self.poly.degree() - 1;
+        self.automorphism_key_gen(q, std_dev, rng, base, k)
     }
+
+    // Generate the key switching `s(X^k)` back to `s`.
+    fn automorphism_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+        base: i64,
+        k: usize,
+    ) -> Result<GaloisKey, BfvError> {
+        self.check_negacyclic()?;
+        let s_k = self.poly.automorphism(k);
+        Ok(GaloisKey {
+            ksk: KeySwitchKey::from_poly(&s_k, self, base, q, std_dev, rng),
+            k,
+        })
+    }
+
+    // Multiplication and rotation use the arithmetic of `Z[X]/(X^N + 1)`.
+    fn check_negacyclic(&self) -> Result<(), BfvError> {
+        match self.ring {
+            Cyclotomic::Negacyclic(_) => Ok(()),
+            ring => Err(BfvError::UnsupportedRing { ring }),
+        }
+    }
+}
+
+/// Returns the Galois element `3^step mod 2N` of rotation step `step` in a ring of degree `N`.
+pub(crate) fn galois_element(step: usize, degree: usize) -> usize {
+    (0..step).fold(1, |k, _| k * 3 % (2 * degree))
 }
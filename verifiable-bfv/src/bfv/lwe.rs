@@ -0,0 +1,53 @@
+use super::keys::SecretKey;
+use super::poly::{rounded_div, Cyclotomic};
+
+/// An LWE ciphertext `(a, b)` encrypting a single message coefficient `m` in `Z_t` under the
+/// coefficients of a BFV12 secret key `s`, such that `b + <a, s> = delta * m + e mod q`
+///
+/// It is built from one coefficient of an RLWE ciphertext by `Ciphertext::extract_lwe`, the first
+/// step of bootstrapping a ciphertext one coefficient at a time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LweCiphertext {
+    pub(crate) a: Vec<i64>,
+    pub(crate) b: i64,
+    pub(crate) q: i64,
+    pub(crate) t: i64,
+}
+
+impl LweCiphertext {
+    /// Decrypt the LWE ciphertext to recover the message coefficient in `[0, t)`, given the
+    /// secret key of the RLWE ciphertext it was extracted from
+    ///
+    /// As for `Ciphertext::decrypt`, there is no branch on the secret coefficients.
+    pub fn decrypt(&self, secret_key: &SecretKey) -> i64 {
+        assert!(
+            matches!(secret_key.ring, Cyclotomic::Negacyclic(_)),
+            "sample extraction needs the ring Z[X]/(X^N + 1)"
+        );
+        assert_eq!(self.a.len(), secret_key.poly.degree());
+        let q = self.q as i128;
+        let raw = self
+            .a
+            .iter()
+            .zip(secret_key.poly.val())
+            .fold(self.b as i128, |acc, (a_j, s_j)| {
+                (acc + *a_j as i128 * *s_j as i128).rem_euclid(q)
+            });
+        rounded_div(raw * self.t as i128, q).rem_euclid(self.t as i128) as i64
+    }
+
+    /// Return the mask `a`, one coefficient per secret key coefficient
+    pub fn a(&self) -> &[i64] {
+        &self.a
+    }
+
+    /// Return the body `b`
+    pub fn b(&self) -> i64 {
+        self.b
+    }
+
+    /// Return the ciphertext modulus
+    pub fn modulus(&self) -> i64 {
+        self.q
+    }
+}
@@ -1,10 +1,49 @@
+mod bootstrap;
 mod ciphertext;
+mod ciphertext_vec;
+mod encoder;
+mod error;
+mod evaluator;
+mod integer;
+mod keygen;
 mod keys;
+mod lwe;
+mod modulus_chain;
+mod noise;
+mod ntt;
+mod ntt_ciphertext;
+mod params;
 mod plaintext;
-mod poly;
-mod random_source;
+pub(crate) mod poly;
+#[cfg(test)]
+mod proptests;
+pub(crate) mod random_source;
+pub(crate) mod rns;
+mod sampler;
+mod sized;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 mod tests;
 
-pub use ciphertext::Ciphertext;
-pub use keys::{PublicKey, RelinearizationKey1, RelinearizationKey2, SecretKey};
-pub use plaintext::Plaintext;
+pub use bootstrap::{blind_rotate, BootstrappingKey, GswCiphertext};
+pub use ciphertext::{product_with, Ciphertext};
+pub use ciphertext_vec::CiphertextVec;
+pub use encoder::CkksEncoder;
+pub use error::BfvError;
+pub use evaluator::{Decryptor, Encryptor, Evaluator};
+pub use integer::EncryptedInteger;
+pub use keygen::{KeyGenerator, KeySet};
+pub use keys::{
+    GaloisKey, GaloisKeySet, KeySwitchKey, PublicKey, RelinearizationKey1, RelinearizationKey2,
+    RelinearizationKeyChain, SecretKey,
+};
+pub use lwe::LweCiphertext;
+pub use modulus_chain::ModulusChain;
+pub use noise::{EncryptionNoise, NoiseEstimate, NoiseOp, NoiseSimulator};
+pub use ntt_ciphertext::NttCiphertext;
+pub use params::BfvParameters;
+pub use plaintext::{Plaintext, Seed, SeededRng};
+pub use poly::{Cyclotomic, NttPoly, Poly};
+pub use rns::RnsCiphertext;
+pub use sampler::{ErrorSampler, GaussianSampler, UniformSampler};
+pub use sized::SizedCiphertext;
@@ -0,0 +1,59 @@
+use super::error::BfvError;
+
+/// A ladder of ciphertext moduli `q_0 > q_1 > ... > q_L`, fixed up front, down which ciphertexts
+/// are switched one level at a time with `Ciphertext::mod_switch_next`, e.g. after every
+/// multiplication
+///
+/// The level of a ciphertext is the index of its modulus in the chain, so that a verifier that
+/// knows the chain also knows the modulus every ciphertext is under.
+///
+/// ```rust
+/// use verifiable_bfv::bfv::{BfvError, ModulusChain};
+/// let chain = ModulusChain::new(vec![1 << 40, 1 << 30, 1 << 20]).unwrap();
+/// assert_eq!(chain.level(1 << 30), Some(1));
+/// assert_eq!(chain.next(1 << 30), Ok(1 << 20));
+/// assert_eq!(chain.next(1 << 20), Err(BfvError::BottomOfModulusChain { q: 1 << 20 }));
+///
+/// assert!(ModulusChain::new(vec![1 << 30, 1 << 40]).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModulusChain {
+    moduli: Vec<i64>,
+}
+
+impl ModulusChain {
+    /// Build a chain from its moduli, from the top one down. Returns `InvalidModulusChain` with
+    /// the index of the first modulus that is not above `1` and below the previous one, or `0` if
+    /// `moduli` is empty.
+    pub fn new(moduli: Vec<i64>) -> Result<ModulusChain, BfvError> {
+        if moduli.is_empty() {
+            return Err(BfvError::InvalidModulusChain { index: 0 });
+        }
+        let invalid =
+            (0..moduli.len()).find(|i| moduli[*i] <= 1 || *i > 0 && moduli[*i] >= moduli[i - 1]);
+        match invalid {
+            Some(index) => Err(BfvError::InvalidModulusChain { index }),
+            None => Ok(ModulusChain { moduli }),
+        }
+    }
+
+    /// Return the moduli, from the top one down
+    pub fn moduli(&self) -> &[i64] {
+        &self.moduli
+    }
+
+    /// Return the level of the modulus `q`, i.e. its index in the chain, if it is in the chain
+    pub fn level(&self, q: i64) -> Option<usize> {
+        self.moduli.iter().position(|q_i| *q_i == q)
+    }
+
+    /// Return the modulus one level below `q`, or `BottomOfModulusChain` if `q` is the last one
+    /// and `ModulusNotInChain` if it is not in the chain
+    pub fn next(&self, q: i64) -> Result<i64, BfvError> {
+        let level = self.level(q).ok_or(BfvError::ModulusNotInChain { q })?;
+        self.moduli
+            .get(level + 1)
+            .copied()
+            .ok_or(BfvError::BottomOfModulusChain { q })
+    }
+}
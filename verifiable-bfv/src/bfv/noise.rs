@@ -0,0 +1,211 @@
+use super::keys::{PublicKey, SecretKey};
+use super::params::BfvParameters;
+use super::poly::{centered_coeff, Poly};
+
+/// The randomness of a public key encryption, as returned by `Plaintext::encrypt_with_noise`
+///
+/// * `u` = the ternary randomness multiplying the public key
+/// * `e_1` = the error added to `c_0`
+/// * `e_2` = the error added to `c_1`
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptionNoise {
+    pub(crate) u: Poly,
+    pub(crate) e_1: Poly,
+    pub(crate) e_2: Poly,
+}
+
+impl EncryptionNoise {
+    /// Return the randomness `u`
+    pub fn u(&self) -> &Poly {
+        &self.u
+    }
+
+    /// Return the error `e_1` added to `c_0`
+    pub fn e_1(&self) -> &Poly {
+        &self.e_1
+    }
+
+    /// Return the error `e_2` added to `c_1`
+    pub fn e_2(&self) -> &Poly {
+        &self.e_2
+    }
+
+    /// Return the error `v = e_1 + e_2 * s - e * u` of the ciphertext, such that
+    /// `[c_0 + c_1 * s]_q = [delta * m + v]_q`, with the coefficients of `v` centered in
+    /// `(-q/2, q/2]`. The public key error `e` is recovered as `-[p_0 + p_1 * s]_q`.
+    pub fn error(&self, secret_key: &SecretKey, public_key: &PublicKey) -> Poly {
+        let (s, q, ring) = (&secret_key.poly, public_key.q, public_key.ring);
+        let neg_e = public_key.p_0.clone() + public_key.p_1.mul_ring(s, q, ring);
+        let v = self.e_1.clone()
+            + self.e_2.mul_ring(s, q, ring)
+            + (neg_e % (q, ring)).mul_ring(&self.u, q, ring);
+        let v = v % (q, ring);
+        Poly::new(
+            v.val()
+                .iter()
+                .map(|coeff| centered_coeff(*coeff, q))
+                .collect(),
+        )
+    }
+}
+
+/// An abstract homomorphic operation tracked by a `NoiseSimulator`
+///
+/// The binary operations combine the current ciphertext with one of the same noise, e.g. its
+/// copy, so that `n` multiplications evaluate a circuit of multiplicative depth `n`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoiseOp {
+    /// Add a ciphertext of the same noise
+    Add,
+    /// Multiply by a plaintext with coefficients in `[0, t)`
+    MulPlain,
+    /// Multiply by a ciphertext of the same noise, without relinearizing
+    Mul,
+    /// Relinearize a degree 2 ciphertext with a Version 1 relinearization key of base `base`
+    Relinearize { base: i64 },
+    /// Switch the ciphertext modulus to `q`, rounding the components
+    ModSwitch(i64),
+}
+
+/// An upper bound on the noise of a ciphertext, as predicted by a `NoiseSimulator`
+///
+/// * `noise` = the bound on the infinity norm of the noise `v` in `[c(s)]_q = delta * m + v`
+/// * `q` = the ciphertext modulus
+/// * `t` = the plaintext modulus
+/// * `size` = the number of ciphertext components, 3 after a multiplication until relinearized
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoiseEstimate {
+    noise: f64,
+    q: i64,
+    t: i64,
+    size: usize,
+}
+
+impl NoiseEstimate {
+    /// Return the bound on the infinity norm of the noise
+    pub fn noise(&self) -> f64 {
+        self.noise
+    }
+
+    /// Return the predicted noise budget in bits, defined as for `Ciphertext::noise_budget`:
+    /// `floor(log_2(delta / 2) - log_2(noise))`
+    pub fn budget(&self) -> i64 {
+        let delta = (self.q / self.t) as f64;
+        ((delta / 2.0) / self.noise.max(1.0)).log2().floor() as i64
+    }
+
+    /// Return whether decryption is guaranteed to succeed, i.e. the noise stays below `delta / 2`
+    pub fn decrypts(&self) -> bool {
+        let delta = (self.q / self.t) as f64;
+        self.noise < delta / 2.0
+    }
+}
+
+/// Predicts the noise growth of a homomorphic computation from the parameters alone, without
+/// encrypting anything, to check whether `q` and `std_dev` survive a given circuit
+///
+/// The estimates are worst-case bounds following the BFV12 noise analysis, for a secret key, an
+/// encryption randomness `u` and plaintexts with coefficients in `[0, 2)`, `[0, 2)` and `[0, t)`,
+/// and errors bounded by `6 * std_dev`. With `N` the degree and `B` the error bound:
+///
+/// * a fresh ciphertext has noise `e * u + e_1 + e_2 * s`, at most `(2N + 1) * B`;
+/// * `Add` doubles the noise;
+/// * `MulPlain` by `m'` multiplies it by at most `N * (t - 1)`, and adds `N * t * [q]_t` from
+///   reducing `delta * m * m'` modulo `t`;
+/// * `Mul` scales the tensor product down by `t/q`, which gives
+///   `2 * N * t * (N + 1) * (2 * noise) + 8 * t^2 * N^2` as in Fan and Vercauteren (Lemma 2);
+/// * `Relinearize` adds `\sum_i c_2^(i) * e_i` over the base `T` digits of `c_2`, at most
+///   `N * B * ((l - 1) * (T - 1) + q / T^(l - 1))` with `l = floor(log_T(q))` levels;
+/// * `ModSwitch` to `q'` scales the noise by `q'/q` and adds the rounding error `(N + 1) / 2`
+///   and the error `t` of rescaling `delta`.
+///
+/// ```rust
+/// use verifiable_bfv::bfv::{BfvParameters, NoiseOp, NoiseSimulator};
+/// let simulator = NoiseSimulator::new(BfvParameters::new(8, 1 << 24, 4, 3.2));
+///
+/// let depth_1 = [NoiseOp::Mul, NoiseOp::Relinearize { base: 16 }];
+/// assert!(simulator.simulate(&depth_1).decrypts());
+///
+/// let depth_2 = [depth_1, depth_1].concat();
+/// assert!(!simulator.simulate(&depth_2).decrypts());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoiseSimulator {
+    params: BfvParameters,
+}
+
+impl NoiseSimulator {
+    /// Instantiate a simulator for the parameters `params`
+    pub fn new(params: BfvParameters) -> NoiseSimulator {
+        NoiseSimulator { params }
+    }
+
+    /// Return the bound on the error coefficients, `6 * std_dev`
+    fn error_bound(&self) -> f64 {
+        6.0 * self.params.std_dev
+    }
+
+    /// Return the noise estimate of a fresh ciphertext encrypted with a public key
+    pub fn fresh(&self) -> NoiseEstimate {
+        let n = self.params.degree as f64;
+        NoiseEstimate {
+            noise: (2.0 * n + 1.0) * self.error_bound(),
+            q: self.params.q,
+            t: self.params.t,
+            size: 2,
+        }
+    }
+
+    /// Apply `op` to a noise estimate
+    pub fn apply(&self, estimate: NoiseEstimate, op: NoiseOp) -> NoiseEstimate {
+        let n = self.params.degree as f64;
+        let (q, t) = (estimate.q as f64, estimate.t as f64);
+        let noise = estimate.noise;
+        match op {
+            NoiseOp::Add => NoiseEstimate {
+                noise: 2.0 * noise,
+                ..estimate
+            },
+            NoiseOp::MulPlain => {
+                let q_mod_t = estimate.q.rem_euclid(estimate.t) as f64;
+                NoiseEstimate {
+                    noise: n * (t - 1.0) * noise + n * t * q_mod_t,
+                    ..estimate
+                }
+            }
+            NoiseOp::Mul => {
+                assert_eq!(estimate.size, 2, "relinearize before multiplying again");
+                NoiseEstimate {
+                    noise: 2.0 * n * t * (n + 1.0) * (2.0 * noise) + 8.0 * t * t * n * n,
+                    size: 3,
+                    ..estimate
+                }
+            }
+            NoiseOp::Relinearize { base } => {
+                assert_eq!(
+                    estimate.size, 3,
+                    "only degree 2 ciphertexts are relinearized"
+                );
+                let l = (q.log2() / (base as f64).log2()).floor();
+                let top_digit = q / (base as f64).powf(l - 1.0);
+                let digits = (l - 1.0) * (base as f64 - 1.0) + top_digit;
+                NoiseEstimate {
+                    noise: noise + n * self.error_bound() * digits,
+                    size: 2,
+                    ..estimate
+                }
+            }
+            NoiseOp::ModSwitch(new_q) => NoiseEstimate {
+                noise: noise * new_q as f64 / q + (n + 1.0) / 2.0 + t,
+                q: new_q,
+                ..estimate
+            },
+        }
+    }
+
+    /// Apply `ops` in turn to a fresh ciphertext and return the final noise estimate
+    pub fn simulate(&self, ops: &[NoiseOp]) -> NoiseEstimate {
+        ops.iter()
+            .fold(self.fresh(), |estimate, op| self.apply(estimate, *op))
+    }
+}
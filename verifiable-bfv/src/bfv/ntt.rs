@@ -0,0 +1,172 @@
+use crate::ntt_params::bit_reverse_index;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// The transforms built by `NegacyclicNtt::cached`, `None` for the `(degree, q)` without one
+type NttCache = HashMap<(usize, i64), Option<Arc<NegacyclicNtt>>>;
+
+/// Negacyclic NTT over `\mathbb{Z}_q[X]/(X^N+1)` for runtime `N` and `q`.
+///
+/// Uses the same Cooley-Tukey/Gentleman-Sande butterflies as `vbfv::ntt_forward`: the roots
+/// are stored in bit-reversed order, the forward transform maps natural-order coefficients to
+/// bit-reversed evaluations and the backward transform maps them back.
+#[derive(Debug, PartialEq)]
+pub(crate) struct NegacyclicNtt {
+    q: u64,
+    roots: Vec<u64>,
+    inv_roots: Vec<u64>,
+    n_inv: u64,
+}
+
+impl NegacyclicNtt {
+    /// Returns `None` unless `degree` is a power of two, `q` is a prime and `2 * degree | q - 1`,
+    /// i.e. unless `X^N+1` fully splits in `\mathbb{Z}_q`.
+    pub(crate) fn new(degree: usize, q: i64) -> Option<Self> {
+        if !degree.is_power_of_two() || !(2..1 << 62).contains(&q) {
+            return None;
+        }
+        let q = q as u64;
+        let two_n = 2 * degree as u64;
+        if !(q - 1).is_multiple_of(two_n) || !is_prime(q) {
+            return None;
+        }
+
+        // `psi` is a primitive `2N`-th root of unity iff `psi^N = -1`.
+        let psi = (2..q)
+            .map(|x| pow_mod(x, (q - 1) / two_n, q))
+            .find(|psi| pow_mod(*psi, degree as u64, q) == q - 1)?;
+        let psi_inv = pow_mod(psi, q - 2, q);
+
+        let logn = degree.trailing_zeros();
+        let mut roots = vec![0; degree];
+        let mut inv_roots = vec![0; degree];
+        let (mut power, mut inv_power) = (1, 1);
+        for j in 0..degree {
+            let index = bit_reverse_index(j, logn);
+            roots[index] = power;
+            inv_roots[index] = inv_power;
+            power = mul_mod(power, psi, q);
+            inv_power = mul_mod(inv_power, psi_inv, q);
+        }
+        let n_inv = pow_mod(degree as u64, q - 2, q);
+
+        Some(Self {
+            q,
+            roots,
+            inv_roots,
+            n_inv,
+        })
+    }
+
+    /// Returns the transform of `new(degree, q)`, computing its tables only on the first call for
+    /// each `(degree, q)`: finding `psi` and testing `q` for primality dominate small products.
+    pub(crate) fn cached(degree: usize, q: i64) -> Option<Arc<Self>> {
+        static CACHE: OnceLock<Mutex<NttCache>> = OnceLock::new();
+        let mut cache = CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        cache
+            .entry((degree, q))
+            .or_insert_with(|| Self::new(degree, q).map(Arc::new))
+            .clone()
+    }
+
+    pub(crate) fn forward(&self, a: &mut [u64]) {
+        let n = self.roots.len();
+        assert_eq!(a.len(), n);
+        let mut m = 1;
+        while m < n {
+            let t = n / (2 * m);
+            for i in 0..m {
+                let s = self.roots[m + i];
+                let j1 = 2 * i * t;
+                for j in j1..j1 + t {
+                    let u = a[j];
+                    let v = mul_mod(a[j + t], s, self.q);
+                    a[j] = (u + v) % self.q;
+                    a[j + t] = (u + self.q - v) % self.q;
+                }
+            }
+            m *= 2;
+        }
+    }
+
+    pub(crate) fn backward(&self, a: &mut [u64]) {
+        let n = self.inv_roots.len();
+        assert_eq!(a.len(), n);
+        let mut m = n / 2;
+        while m >= 1 {
+            let t = n / (2 * m);
+            for i in 0..m {
+                let s = self.inv_roots[m + i];
+                let j1 = 2 * i * t;
+                for j in j1..j1 + t {
+                    let u = a[j];
+                    let v = a[j + t];
+                    a[j] = (u + v) % self.q;
+                    a[j + t] = mul_mod((u + self.q - v) % self.q, s, self.q);
+                }
+            }
+            m /= 2;
+        }
+        for coeff in a.iter_mut() {
+            *coeff = mul_mod(*coeff, self.n_inv, self.q);
+        }
+    }
+
+    /// Multiplies two polynomials in `R_q`, given their coefficients in `[0, q)`.
+    pub(crate) fn mul(&self, lhs: &[u64], rhs: &[u64]) -> Vec<u64> {
+        let mut lhs = lhs.to_vec();
+        let mut rhs = rhs.to_vec();
+        self.forward(&mut lhs);
+        self.forward(&mut rhs);
+        let mut out = lhs
+            .iter()
+            .zip(rhs.iter())
+            .map(|(x, y)| mul_mod(*x, *y, self.q))
+            .collect::<Vec<_>>();
+        self.backward(&mut out);
+        out
+    }
+}
+
+fn mul_mod(x: u64, y: u64, q: u64) -> u64 {
+    ((x as u128 * y as u128) % q as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, q: u64) -> u64 {
+    let mut result = 1 % q;
+    base %= q;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, q);
+        }
+        base = mul_mod(base, base, q);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Deterministic Miller-Rabin test, exact for all `u64`.
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(p) = BASES.iter().find(|p| n.is_multiple_of(**p)) {
+        return n == *p;
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    BASES.iter().all(|a| {
+        let mut x = pow_mod(*a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..s).any(|_| {
+            x = mul_mod(x, x, n);
+            x == n - 1
+        })
+    })
+}
@@ -0,0 +1,94 @@
+use super::ciphertext::Ciphertext;
+use super::keys::SecretKey;
+use super::plaintext::Plaintext;
+use super::poly::NttPoly;
+use std::ops::{Add, Sub};
+
+/// A BFV12 Ciphertext whose polynomials are kept in NTT form, built with `Ciphertext::to_ntt`
+///
+/// Plaintext multiplications and additions are pointwise, so a ciphertext going through many of
+/// them is transformed once, and `decrypt` or `Ciphertext::from_ntt` transform it back.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 8;
+/// # let q = 12289;
+/// # let t = 4;
+/// #
+/// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+///
+/// let pt = Plaintext::new(vec![1, 2, 3, 0, 0, 0, 0, 0], t);
+/// let x = Plaintext::new(vec![0, 1, 0, 0, 0, 0, 0, 0], t);
+/// let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+///
+/// let ct_ntt = ct.to_ntt().unwrap().mul_plain(&x).mul_plain(&x);
+/// assert_eq!(ct_ntt.decrypt(&secret_key).to_vec(), vec![0, 0, 1, 2, -1, 0, 0, 0]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NttCiphertext {
+    pub(crate) c_0: NttPoly,
+    pub(crate) c_1: NttPoly,
+    pub(crate) t: i64,
+}
+
+impl NttCiphertext {
+    /// Decrypt the ciphertext, transforming it back to coefficient form first
+    pub fn decrypt(&self, secret_key: &SecretKey) -> Plaintext {
+        Ciphertext::from_ntt(self).decrypt(secret_key)
+    }
+
+    /// Multiply the ciphertext by a plaintext polynomial, transforming only the plaintext
+    pub fn mul_plain(&self, pt: &Plaintext) -> NttCiphertext {
+        let m = pt.poly() % (self.t, self.c_0.degree());
+        NttCiphertext {
+            c_0: &self.c_0 * &m,
+            c_1: &self.c_1 * &m,
+            t: self.t,
+        }
+    }
+
+    /// Multiply the ciphertext by a plaintext polynomial already in NTT form, without any
+    /// transform
+    pub fn mul_plain_ntt(&self, m: &NttPoly) -> NttCiphertext {
+        NttCiphertext {
+            c_0: &self.c_0 * m,
+            c_1: &self.c_1 * m,
+            t: self.t,
+        }
+    }
+
+    /// Return the ciphertext modulus
+    pub fn modulus(&self) -> i64 {
+        self.c_0.modulus()
+    }
+}
+
+/// Add two ciphertexts in NTT form
+impl Add<&NttCiphertext> for &NttCiphertext {
+    type Output = NttCiphertext;
+    fn add(self, other: &NttCiphertext) -> Self::Output {
+        assert_eq!(self.t, other.t);
+        NttCiphertext {
+            c_0: &self.c_0 + &other.c_0,
+            c_1: &self.c_1 + &other.c_1,
+            t: self.t,
+        }
+    }
+}
+
+/// Subtract two ciphertexts in NTT form
+impl Sub<&NttCiphertext> for &NttCiphertext {
+    type Output = NttCiphertext;
+    fn sub(self, other: &NttCiphertext) -> Self::Output {
+        assert_eq!(self.t, other.t);
+        NttCiphertext {
+            c_0: &self.c_0 - &other.c_0,
+            c_1: &self.c_1 - &other.c_1,
+            t: self.t,
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use super::poly::Cyclotomic;
+
+/// The parameters of a BFV12 instance
+///
+/// * `degree` = the degree `N` of the ring `R = Z[X]/(Phi)`
+/// * `ring` = the cyclotomic polynomial `Phi`, `X^N + 1` unless built with `new_prime_cyclotomic`
+/// * `q` = the ciphertext modulus
+/// * `t` = the plaintext modulus
+/// * `std_dev` = the standard deviation for error generation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BfvParameters {
+    pub(crate) degree: usize,
+    pub(crate) ring: Cyclotomic,
+    pub(crate) q: i64,
+    pub(crate) t: i64,
+    pub(crate) std_dev: f64,
+}
+
+impl BfvParameters {
+    /// Instantiate a new set of parameters
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::BfvParameters;
+    /// let params = BfvParameters::new(4, 65536, 16, 3.2);
+    /// ```
+    pub fn new(degree: usize, q: i64, t: i64, std_dev: f64) -> BfvParameters {
+        assert!(degree > 0);
+        // The plaintext space is taken as R_t for some integer t > 1, and must fit below q.
+        assert!(t > 1 && t < q, "plaintext modulus {t} is not in (1, {q})");
+        BfvParameters {
+            degree,
+            ring: Cyclotomic::Negacyclic(degree),
+            q,
+            t,
+            std_dev,
+        }
+    }
+
+    /// Instantiate a new set of parameters over the prime cyclotomic ring
+    /// `R = Z[X]/(1 + X + ... + X^{p-1})` of degree `N = p - 1`
+    ///
+    /// Keys generated by a `KeyGenerator` for these parameters encrypt and decrypt in that ring,
+    /// and ciphertexts can be added. Homomorphic multiplications, key switching and the NTT
+    /// assume `X^N + 1` and are not supported.
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::{BfvParameters, Cyclotomic};
+    /// let params = BfvParameters::new_prime_cyclotomic(7, 65536, 16, 3.2);
+    /// assert_eq!(params.degree(), 6);
+    /// assert_eq!(params.ring(), Cyclotomic::Prime(7));
+    /// ```
+    pub fn new_prime_cyclotomic(p: usize, q: i64, t: i64, std_dev: f64) -> BfvParameters {
+        assert!(
+            p >= 2
+                && (2..p)
+                    .take_while(|d| d * d <= p)
+                    .all(|d| !p.is_multiple_of(d)),
+            "{p} is not a prime"
+        );
+        BfvParameters {
+            ring: Cyclotomic::Prime(p),
+            ..BfvParameters::new(p - 1, q, t, std_dev)
+        }
+    }
+
+    /// Return the ring degree `N`
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Return the cyclotomic polynomial defining the ring
+    pub fn ring(&self) -> Cyclotomic {
+        self.ring
+    }
+
+    /// Return the ciphertext modulus
+    pub fn q(&self) -> i64 {
+        self.q
+    }
+
+    /// Return the plaintext modulus
+    pub fn t(&self) -> i64 {
+        self.t
+    }
+
+    /// Return the standard deviation for error generation
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+}
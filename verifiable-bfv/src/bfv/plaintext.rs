@@ -1,16 +1,30 @@
 use super::ciphertext::Ciphertext;
-use super::keys::PublicKey;
-use super::poly::Poly;
+use super::error::BfvError;
+use super::keys::{PublicKey, SecretKey};
+use super::noise::EncryptionNoise;
+use super::poly::{centered_coeff, Poly};
 use super::random_source;
-use rand::{CryptoRng, RngCore};
+use super::sampler::{ErrorSampler, GaussianSampler};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use std::fmt;
+
+// `Display` truncates plaintexts of a larger degree to their first and last coefficients.
+const DISPLAY_MAX_COEFFS: usize = 16;
+const DISPLAY_EDGE_COEFFS: usize = 4;
+
+/// The RNG used by `Plaintext::rand_with_seed`
+pub type SeededRng = rand::rngs::StdRng;
+
+/// The seed of a `SeededRng`, from which `Plaintext::encrypt_seeded` derives `c_1`
+pub type Seed = <SeededRng as SeedableRng>::Seed;
 
 /// A BFV12 Plaintext (an encoded message)
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct Plaintext {
     /// The polynomial representing the list of plaintext values
-    poly: Poly,
+    pub(crate) poly: Poly,
     /// The modulus of the plaintext space
-    t: i64,
+    pub(crate) t: i64,
 }
 
 impl Plaintext {
@@ -20,7 +34,7 @@ impl Plaintext {
     /// * `t`: the plaintext modulus
     ///
     /// ```rust
-    /// use bfv12::Plaintext;
+    /// use verifiable_bfv::bfv::Plaintext;
     /// let pt = Plaintext::new(vec![0, 1, 2, 3], 4);
     /// ```
     pub fn new(val: Vec<i64>, t: i64) -> Plaintext {
@@ -35,6 +49,9 @@ impl Plaintext {
 
     /// Instantiate a new random Plaintext uniformly over [0, t) with length `degree`
     ///
+    /// The coefficients are the residues in `[0, t)`, not the centered ones in `(-t/2, t/2]`; use
+    /// `to_vec` for the centered message.
+    ///
     /// * `degree`: the degree (length) of the newly generated plaintext
     /// * `t`: the plaintext modulus
     /// # `rng`: the RNG used to generate randomness. Any Rng that imlements RngCore + CryptoRng can be used.
@@ -43,7 +60,7 @@ impl Plaintext {
     /// # use rand::SeedableRng;
     /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
     /// #
-    /// use bfv12::Plaintext;
+    /// use verifiable_bfv::bfv::Plaintext;
     /// let rand_pt = Plaintext::rand(10, 4, &mut rng);
     /// ```
     pub fn rand<T: RngCore + CryptoRng>(degree: usize, t: i64, rng: &mut T) -> Plaintext {
@@ -54,18 +71,142 @@ impl Plaintext {
         }
     }
 
+    /// Instantiate a new random Plaintext like `rand`, from a `SeededRng` seeded with `seed`
+    ///
+    /// The same `seed` always yields the same plaintext, which makes benchmarks and fuzz corpora
+    /// reproducible.
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::Plaintext;
+    /// let rand_pt = Plaintext::rand_with_seed(10, 4, 18);
+    /// assert_eq!(rand_pt, Plaintext::rand_with_seed(10, 4, 18));
+    /// ```
+    pub fn rand_with_seed(degree: usize, t: i64, seed: u64) -> Plaintext {
+        Plaintext::rand(degree, t, &mut SeededRng::seed_from_u64(seed))
+    }
+
     /// Return the polynomial that represents the encoded message
     ///
     /// ```rust
-    /// use bfv12::Plaintext;
+    /// use verifiable_bfv::bfv::Plaintext;
     /// let pt = Plaintext::new(vec![0, 1, 2, 3], 4);
     /// let pt_poly = pt.poly();
-    /// assert_eq!(pt_poly.val(), vec![0, 1, 2, 3])
+    /// assert_eq!(pt_poly.val(), &vec![0, 1, 2, 3])
     /// ```
     pub fn poly(&self) -> Poly {
         self.poly.clone()
     }
 
+    /// Return the plaintext modulus
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::Plaintext;
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], 4);
+    /// assert_eq!((pt.t(), pt.degree()), (4, 4));
+    /// ```
+    pub fn t(&self) -> i64 {
+        self.t
+    }
+
+    /// Return the degree `N` of the plaintext polynomial
+    pub fn degree(&self) -> usize {
+        self.poly.degree()
+    }
+
+    /// Return the `i`-th coefficient as its representative in `[0, t)`, or `IndexOutOfRange` if
+    /// `i` is not below the degree
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::{BfvError, Plaintext};
+    /// let mut pt = Plaintext::new(vec![0; 4], 4);
+    /// pt.set_coefficient(2, -1).unwrap();
+    /// assert_eq!(pt.get_coefficient(2), Ok(3));
+    /// assert_eq!(pt, Plaintext::new(vec![0, 0, 3, 0], 4));
+    /// assert_eq!(
+    ///     pt.get_coefficient(4),
+    ///     Err(BfvError::IndexOutOfRange { index: 4, degree: 4 })
+    /// );
+    /// ```
+    pub fn get_coefficient(&self, i: usize) -> Result<i64, BfvError> {
+        self.check_index(i)?;
+        Ok(self.poly.val()[i].rem_euclid(self.t))
+    }
+
+    /// Set the `i`-th coefficient to `value` reduced into `[0, t)`, or return `IndexOutOfRange`
+    /// if `i` is not below the degree
+    pub fn set_coefficient(&mut self, i: usize, value: i64) -> Result<(), BfvError> {
+        self.check_index(i)?;
+        self.poly.val_mut()[i] = value.rem_euclid(self.t);
+        Ok(())
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), BfvError> {
+        let degree = self.degree();
+        if index >= degree {
+            return Err(BfvError::IndexOutOfRange { index, degree });
+        }
+        Ok(())
+    }
+
+    /// Return the message encoded in the plaintext, as the centered representatives of its
+    /// coefficients in `(-t/2, t/2]`. For even `t`, `t/2` is kept positive.
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::Plaintext;
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], 4);
+    /// assert_eq!(pt.to_vec(), vec![0, 1, 2, -1])
+    /// ```
+    pub fn to_vec(&self) -> Vec<i64> {
+        let degree = self.poly.degree();
+        (self.poly.clone() % (self.t, degree))
+            .val()
+            .iter()
+            .map(|coeff| centered_coeff(*coeff, self.t))
+            .collect()
+    }
+
+    /// Split a message into plaintexts of length `degree`, zero-padding the last one
+    ///
+    /// * `msg`: the message, of any length
+    /// * `t`: the plaintext modulus
+    /// * `degree`: the degree (length) of each plaintext
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::Plaintext;
+    /// let pts = Plaintext::encode_chunks(&[0, 1, 2, 3, 1, 2], 4, 4);
+    /// assert_eq!(pts, vec![Plaintext::new(vec![0, 1, 2, 3], 4), Plaintext::new(vec![1, 2, 0, 0], 4)]);
+    /// ```
+    pub fn encode_chunks(msg: &[i64], t: i64, degree: usize) -> Vec<Plaintext> {
+        msg.chunks(degree)
+            .map(|chunk| {
+                let mut val = chunk.to_vec();
+                val.resize(degree, 0);
+                Plaintext::new(val, t)
+            })
+            .collect()
+    }
+
+    /// Concatenate the messages of plaintexts produced by `encode_chunks`, as their
+    /// representatives in `[0, t)`, and drop the padding beyond `len`
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::Plaintext;
+    /// let pts = Plaintext::encode_chunks(&[0, 1, 2, 3, 1, 2], 4, 4);
+    /// assert_eq!(Plaintext::decode_chunks(&pts, 6), vec![0, 1, 2, 3, 1, 2]);
+    /// ```
+    pub fn decode_chunks(plaintexts: &[Plaintext], len: usize) -> Vec<i64> {
+        let mut msg = plaintexts
+            .iter()
+            .flat_map(|pt| {
+                let degree = pt.poly.degree();
+                (pt.poly.clone() % (pt.t, degree)).val().clone()
+            })
+            .collect::<Vec<_>>();
+        assert!(len <= msg.len());
+        msg.truncate(len);
+        msg
+    }
+
     /// Encrypt a plaintext with a given public key
     ///
     /// * `pub_key`: the public key used to encrypt plaintext
@@ -80,7 +221,7 @@ impl Plaintext {
     /// # let q = 65536;
     /// # let t = 4;
     /// #
-    /// use bfv12::{Plaintext, SecretKey};
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
     /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
     ///
     /// let secret_key = SecretKey::generate(degree, &mut rng);
@@ -94,25 +235,329 @@ impl Plaintext {
         std_dev: f64,
         rng: &mut T,
     ) -> Ciphertext {
-        assert_eq!(self.poly.degree(), pub_key.p_0.degree());
+        self.encrypt_with_sampler(pub_key, &GaussianSampler::new(std_dev), rng)
+    }
+
+    /// Encrypt a plaintext with a given public key, like `encrypt`, but return `DegreeMismatch` if
+    /// the degrees of the plaintext and the key differ, and `InvalidModulus` unless `1 < t < q`,
+    /// where `encrypt` panics
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{BfvError, Plaintext, SecretKey};
+    /// let secret_key = SecretKey::generate(8, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    /// let err = pt.try_encrypt(&public_key, std_dev, &mut rng).unwrap_err();
+    /// assert_eq!(err, BfvError::DegreeMismatch { expected: 8, actual: 4 });
+    /// ```
+    pub fn try_encrypt<T: RngCore + CryptoRng>(
+        &self,
+        pub_key: &PublicKey,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> Result<Ciphertext, BfvError> {
+        self.check_key(pub_key)?;
+        Ok(self.encrypt(pub_key, std_dev, rng))
+    }
+
+    // Check that the plaintext can be encrypted under `pub_key`.
+    fn check_key(&self, pub_key: &PublicKey) -> Result<(), BfvError> {
+        let (expected, actual) = (pub_key.p_0.degree(), self.poly.degree());
+        if expected != actual {
+            return Err(BfvError::DegreeMismatch { expected, actual });
+        }
+        if self.t <= 1 || self.t >= pub_key.q {
+            return Err(BfvError::InvalidModulus {
+                q: pub_key.q,
+                t: self.t,
+            });
+        }
+        Ok(())
+    }
+
+    /// Encrypt a plaintext with a given public key, like `encrypt`, drawing the errors `e_1` and
+    /// `e_2` from `sampler`
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey, UniformSampler};
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let ct = pt.encrypt_with_sampler(&public_key, &UniformSampler::new(4), &mut rng);
+    /// assert_eq!(ct.decrypt(&secret_key), pt);
+    /// ```
+    pub fn encrypt_with_sampler<S: ErrorSampler, T: RngCore + CryptoRng>(
+        &self,
+        pub_key: &PublicKey,
+        sampler: &S,
+        rng: &mut T,
+    ) -> Ciphertext {
+        self.encrypt_with_noise_sampler(pub_key, sampler, rng).0
+    }
+
+    /// Encrypt a plaintext with a given public key, like `encrypt`, and also return the
+    /// randomness `u`, `e_1` and `e_2` of the encryption, for debugging and noise measurements
+    ///
+    /// The error of the ciphertext, `e_1 + e_2 * s - e * u`, also depends on the secret key and on
+    /// the error `e` of the public key, so it is recovered with `EncryptionNoise::error`.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    ///
+    /// let (ct, noise) = pt.encrypt_with_noise(&public_key, std_dev, &mut rng);
+    /// let error = noise.error(&secret_key, &public_key);
+    /// assert!(error.norm_infinity() < q / t / 2);
+    /// ```
+    pub fn encrypt_with_noise<T: RngCore + CryptoRng>(
+        &self,
+        pub_key: &PublicKey,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> (Ciphertext, EncryptionNoise) {
+        self.encrypt_with_noise_sampler(pub_key, &GaussianSampler::new(std_dev), rng)
+    }
+
+    /// Encrypts like `encrypt_with_sampler` and also returns the randomness of the encryption.
+    pub(crate) fn encrypt_with_noise_sampler<S: ErrorSampler, T: RngCore + CryptoRng>(
+        &self,
+        pub_key: &PublicKey,
+        sampler: &S,
+        rng: &mut T,
+    ) -> (Ciphertext, EncryptionNoise) {
+        if let Err(err) = self.check_key(pub_key) {
+            panic!("cannot encrypt: {err}");
+        }
         let q = pub_key.q;
         let degree = self.poly.degree();
         let m = self.poly.clone();
 
         let u = random_source::get_uniform(2, degree, rng);
-        let e_1 = random_source::get_gaussian(std_dev, degree, rng);
-        let e_2 = random_source::get_gaussian(std_dev, degree, rng);
+        let e_1 = sampler.sample(degree, rng);
+        let e_2 = sampler.sample(degree, rng);
+
+        let delta = q / self.t;
+
+        let ring = pub_key.ring;
+        let c_0 = (pub_key.p_0.mul_ring(&u, q, ring) + e_1.clone() + m * delta) % (q, ring);
+        let c_1 = (pub_key.p_1.mul_ring(&u, q, ring) + e_2.clone()) % (q, ring);
+
+        let ct = Ciphertext {
+            c_0,
+            c_1,
+            q,
+            t: self.t,
+            level: 0,
+        };
+        (ct, EncryptionNoise { u, e_1, e_2 })
+    }
+
+    /// Encrypt a plaintext directly with a secret key
+    ///
+    /// The ciphertext only carries the fresh error `e`, so it has less noise than one produced by
+    /// `encrypt`, which is useful for key-switching material and test vectors.
+    ///
+    /// * `secret_key`: the secret key used to encrypt plaintext
+    /// * `q`: the ciphertext modulus
+    /// * `std_dev`: the standard deviation used for generating the error in the encryption
+    /// # `rng`: the RNG used to generate randomness. Any Rng that imlements RngCore + CryptoRng can be used.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    ///
+    /// let ct = pt.encrypt_symmetric(&secret_key, q, std_dev, &mut rng);
+    /// assert_eq!(ct.decrypt(&secret_key), pt);
+    /// ```
+    pub fn encrypt_symmetric<T: RngCore + CryptoRng>(
+        &self,
+        secret_key: &SecretKey,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> Ciphertext {
+        self.encrypt_symmetric_with_sampler(secret_key, q, &GaussianSampler::new(std_dev), rng)
+    }
 
-        let delta = (q as f64 / self.t as f64).floor() as i64;
+    /// Encrypt a plaintext directly with a secret key, like `encrypt_symmetric`, drawing the error
+    /// `e` from `sampler`
+    pub fn encrypt_symmetric_with_sampler<S: ErrorSampler, T: RngCore + CryptoRng>(
+        &self,
+        secret_key: &SecretKey,
+        q: i64,
+        sampler: &S,
+        rng: &mut T,
+    ) -> Ciphertext {
+        assert_eq!(self.poly.degree(), secret_key.poly.degree());
+        let degree = self.poly.degree();
+        let s = secret_key.poly.clone();
+        let m = self.poly.clone();
 
-        let c_0 = (pub_key.p_0.clone() * u.clone() + e_1 + m * delta) % (q, degree);
-        let c_1 = (pub_key.p_1.clone() * u + e_2) % (q, degree);
+        let a = random_source::get_uniform(q, degree, rng);
+        let e = sampler.sample(degree, rng);
+
+        let delta = q / self.t;
+
+        let ring = secret_key.ring;
+        let c_0 = (-(a.mul_ring(&s, q, ring) + e) + m * delta) % (q, ring);
+        let c_1 = a;
 
         Ciphertext {
             c_0,
             c_1,
             q,
             t: self.t,
+            level: 0,
+        }
+    }
+
+    /// Encrypt a plaintext directly with a secret key, like `encrypt_symmetric`, deriving the
+    /// uniform `c_1` from a fresh seed
+    ///
+    /// Only `c_0` and the 32-byte seed need to be stored or transmitted, roughly halving the size of
+    /// the ciphertext. `Ciphertext::from_seeded` regenerates the full ciphertext.
+    ///
+    /// * `secret_key`: the secret key used to encrypt plaintext
+    /// * `q`: the ciphertext modulus
+    /// * `std_dev`: the standard deviation used for generating the error in the encryption
+    /// # `rng`: the RNG used to generate randomness, including the seed. Any Rng that imlements RngCore + CryptoRng can be used.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Ciphertext, Plaintext, SecretKey};
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    ///
+    /// let (ct, seed) = pt.encrypt_seeded(&secret_key, q, std_dev, &mut rng);
+    /// let expanded = Ciphertext::from_seeded(ct.c_0(), seed, q, t);
+    /// assert_eq!(expanded.decrypt(&secret_key), pt);
+    /// ```
+    pub fn encrypt_seeded<T: RngCore + CryptoRng>(
+        &self,
+        secret_key: &SecretKey,
+        q: i64,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> (Ciphertext, Seed) {
+        assert_eq!(self.poly.degree(), secret_key.poly.degree());
+        let degree = self.poly.degree();
+        let s = secret_key.poly.clone();
+        let m = self.poly.clone();
+
+        let mut seed = Seed::default();
+        rng.fill_bytes(&mut seed);
+        let a = random_source::expand_seed(seed, q, degree);
+        let e = random_source::get_gaussian(std_dev, degree, rng);
+
+        let delta = q / self.t;
+
+        let ring = secret_key.ring;
+        let c_0 = (-(a.mul_ring(&s, q, ring) + e) + m * delta) % (q, ring);
+        let c_1 = a;
+
+        let ct = Ciphertext {
+            c_0,
+            c_1,
+            q,
+            t: self.t,
+            level: 0,
+        };
+        (ct, seed)
+    }
+}
+
+/// Compare plaintexts by their plaintext modulus, degree and coefficients reduced modulo `t`, so
+/// that e.g. `-1` and `t - 1` are the same message.
+///
+/// ```rust
+/// use verifiable_bfv::bfv::Plaintext;
+/// assert_eq!(Plaintext::new(vec![-1, 5, 2, 3], 4), Plaintext::new(vec![3, 1, 2, 3], 4));
+/// assert_ne!(Plaintext::new(vec![0, 1, 2, 3], 4), Plaintext::new(vec![0, 1, 2, 3], 8));
+/// ```
+impl PartialEq for Plaintext {
+    fn eq(&self, other: &Plaintext) -> bool {
+        self.t == other.t && self.poly.eq_mod(&other.poly, self.t)
+    }
+}
+
+impl Eq for Plaintext {}
+
+/// Show the coefficients and the plaintext modulus, e.g. `[0, 1, 2, 3] (t = 4)`.
+/// Plaintexts of degree above 16 only show their first and last 4 coefficients.
+///
+/// ```rust
+/// use verifiable_bfv::bfv::Plaintext;
+/// let pt = Plaintext::new(vec![0, 1, 2, 3], 4);
+/// assert_eq!(pt.to_string(), "[0, 1, 2, 3] (t = 4)");
+///
+/// let pt = Plaintext::new((0..32).collect(), 64);
+/// assert_eq!(pt.to_string(), "[0, 1, 2, 3, ..., 28, 29, 30, 31] (degree 32, t = 64)");
+/// ```
+impl fmt::Display for Plaintext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let val = self.poly.val();
+        if val.len() <= DISPLAY_MAX_COEFFS {
+            write!(f, "{:?} (t = {})", val, self.t)
+        } else {
+            let head = &val[..DISPLAY_EDGE_COEFFS];
+            let tail = &val[val.len() - DISPLAY_EDGE_COEFFS..];
+            let fmt_coeffs = |coeffs: &[i64]| {
+                coeffs
+                    .iter()
+                    .map(|coeff| coeff.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            write!(
+                f,
+                "[{}, ..., {}] (degree {}, t = {})",
+                fmt_coeffs(head),
+                fmt_coeffs(tail),
+                val.len(),
+                self.t
+            )
         }
     }
 }
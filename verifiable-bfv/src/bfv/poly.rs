@@ -1,5 +1,9 @@
+use super::ntt::NegacyclicNtt;
+use num_traits::Signed;
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::sync::Arc;
 use std::{cmp, fmt};
+use subtle::{ConditionallySelectable, ConstantTimeGreater};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Poly(Vec<i64>);
@@ -7,6 +11,13 @@ pub struct Poly(Vec<i64>);
 impl Add<Poly> for Poly {
     type Output = Poly;
     fn add(self, other: Poly) -> Self::Output {
+        &self + &other
+    }
+}
+
+impl Add<&Poly> for &Poly {
+    type Output = Poly;
+    fn add(self, other: &Poly) -> Self::Output {
         let max_degree = cmp::max(self.degree(), other.degree());
 
         let out_val = (0..max_degree)
@@ -23,6 +34,13 @@ impl Add<Poly> for Poly {
 impl Sub<Poly> for Poly {
     type Output = Poly;
     fn sub(self, other: Poly) -> Self::Output {
+        &self - &other
+    }
+}
+
+impl Sub<&Poly> for &Poly {
+    type Output = Poly;
+    fn sub(self, other: &Poly) -> Self::Output {
         let max_degree = cmp::max(self.degree(), other.degree());
 
         let out_val = (0..max_degree)
@@ -46,6 +64,13 @@ impl Neg for Poly {
     }
 }
 
+impl Neg for &Poly {
+    type Output = Poly;
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
 impl Mul<i64> for Poly {
     type Output = Poly;
     fn mul(self, other: i64) -> Self::Output {
@@ -76,48 +101,53 @@ impl Div<f64> for Poly {
     }
 }
 
+// Multiply two polynomials over the integers. The products are accumulated in `i128` and the
+// result panics rather than wrapping if a coefficient does not fit in an `i64`; products that are
+// reduced or scaled afterwards should go through `Poly::mul_wide` instead.
 impl Mul<Poly> for Poly {
     type Output = Poly;
     fn mul(self, other: Poly) -> Self::Output {
-        let mut out_val = vec![0; self.0.len() + other.0.len() - 1];
-        for (i, self_i) in self.0.iter().enumerate() {
-            for (j, other_j) in other.0.iter().enumerate() {
-                let target_degree = i + j;
-                out_val[target_degree] += self_i * other_j;
-            }
-        }
-        Poly(out_val)
+        self.mul_wide(&other).narrow()
     }
 }
 
 impl Rem<(i64, usize)> for Poly {
     type Output = Poly;
+    /// Same as `Poly::reduce(modulus.0, modulus.1)`
     fn rem(self, modulus: (i64, usize)) -> Self::Output {
-        let coeff_mod = modulus.0;
-        let degree = modulus.1;
-        let mut out_val = vec![0; degree];
+        self.reduce(modulus.0, modulus.1)
+    }
+}
 
-        // Take the polynomial mod (X^N + 1).
-        // 1. After a multiplication by X^{2N}, the polynomial is unchanged mod (X^N + 1).
-        //    Therefore, we can take the degree % 2N.
-        // 2. If degree % 2N > N, the coefficients should be negated and added to the degree % N.
-        // 3. If degree % 2N <= N, the coefficients should be added to the degree % 2N.
-        for (i, coeff) in self.0.iter().enumerate() {
-            // $ X^i == X^{i + j * 2N} mod (X^N + 1) for all j $
-            // So we can take the coeff degree mod 2N.
-            let reduced_i = i % (2 * degree);
-            if reduced_i >= degree {
-                out_val[reduced_i % degree] -= coeff;
-            } else {
-                out_val[reduced_i] += coeff;
-            }
-        }
+/// The cyclotomic polynomial `Phi` defining the ring `R_q = Z_q[X]/(Phi)` that `Poly % (q, Phi)`
+/// reduces into
+///
+/// * `Negacyclic(n)` = `X^n + 1`, the `2n`-th cyclotomic polynomial for a power of two `n`, and the
+///   ring of `Poly % (q, n)`
+/// * `Prime(p)` = `1 + X + ... + X^{p-1}`, the `p`-th cyclotomic polynomial for a prime `p`, of
+///   degree `p - 1`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cyclotomic {
+    Negacyclic(usize),
+    Prime(usize),
+}
 
-        // Take each coefficient % coeff_mod
-        for coeff in out_val.iter_mut() {
-            *coeff = Poly::mod_coeff(*coeff, coeff_mod)
+impl Cyclotomic {
+    /// Return the degree of the cyclotomic polynomial, the number of coefficients of reduced
+    /// polynomials
+    pub fn degree(&self) -> usize {
+        match self {
+            Cyclotomic::Negacyclic(n) => *n,
+            Cyclotomic::Prime(p) => p - 1,
         }
-        Poly(out_val)
+    }
+}
+
+impl Rem<(i64, Cyclotomic)> for Poly {
+    type Output = Poly;
+    /// Same as `Poly::reduce_cyclotomic(modulus.0, modulus.1)`
+    fn rem(self, modulus: (i64, Cyclotomic)) -> Self::Output {
+        self.reduce_cyclotomic(modulus.0, modulus.1)
     }
 }
 
@@ -153,11 +183,254 @@ impl Poly {
         &self.0
     }
 
+    pub(crate) fn val_mut(&mut self) -> &mut [i64] {
+        &mut self.0
+    }
+
+    /// Return whether both polynomials have the same degree and coefficients modulo `q`, e.g.
+    /// `-1` and `q - 1`
+    pub(crate) fn eq_mod(&self, other: &Poly, q: i64) -> bool {
+        self.degree() == other.degree()
+            && self
+                .0
+                .iter()
+                .zip(&other.0)
+                .all(|(a, b)| a.rem_euclid(q) == b.rem_euclid(q))
+    }
+
+    /// Reduce the polynomial into `R_t = Z_t[X]/(X^degree + 1)`.
+    ///
+    /// The coefficient of `X^i` with `i >= degree` folds back onto `X^{i - degree}` with its sign
+    /// flipped, since `X^degree = -1`, and every coefficient is then reduced into `[0, t)`.
+    pub fn reduce(&self, t: i64, degree: usize) -> Poly {
+        let mut out_val = vec![0i128; degree];
+
+        // Take the polynomial mod (X^N + 1).
+        // 1. After a multiplication by X^{2N}, the polynomial is unchanged mod (X^N + 1).
+        //    Therefore, we can take the degree % 2N.
+        // 2. If degree % 2N > N, the coefficients should be negated and added to the degree % N.
+        // 3. If degree % 2N <= N, the coefficients should be added to the degree % 2N.
+        for (i, coeff) in self.0.iter().enumerate() {
+            // $ X^i == X^{i + j * 2N} mod (X^N + 1) for all j $
+            // So we can take the coeff degree mod 2N.
+            let reduced_i = i % (2 * degree);
+            if reduced_i >= degree {
+                out_val[reduced_i % degree] -= *coeff as i128;
+            } else {
+                out_val[reduced_i] += *coeff as i128;
+            }
+        }
+
+        // Take each coefficient % t, the folded sums are accumulated in `i128` so they cannot
+        // overflow.
+        Poly(
+            out_val
+                .into_iter()
+                .map(|coeff| coeff.rem_euclid(t as i128) as i64)
+                .collect(),
+        )
+    }
+
+    /// Reduce the polynomial into `R_t = Z_t[X]/(Phi)` for the cyclotomic polynomial `ring`.
+    ///
+    /// For `Phi_p`, which divides `X^p - 1`, the coefficient of `X^i` first folds onto
+    /// `X^{i mod p}`, and the coefficient of `X^{p-1} = -(1 + X + ... + X^{p-2})` is then
+    /// subtracted from all the others.
+    pub fn reduce_cyclotomic(&self, t: i64, ring: Cyclotomic) -> Poly {
+        match ring {
+            Cyclotomic::Negacyclic(degree) => self.reduce(t, degree),
+            Cyclotomic::Prime(p) => {
+                let mut out_val = vec![0i128; p];
+                for (i, coeff) in self.0.iter().enumerate() {
+                    out_val[i % p] += *coeff as i128;
+                }
+                let top = out_val.pop().unwrap();
+                Poly(
+                    out_val
+                        .into_iter()
+                        .map(|coeff| (coeff - top).rem_euclid(t as i128) as i64)
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// Add `other` into `self` in place, reducing each coefficient into `[0, q)`.
+    pub(crate) fn add_assign_mod(&mut self, other: &Poly, q: i64) {
+        self.zip_assign_mod(other, q, |lhs, rhs| lhs + rhs);
+    }
+
+    /// Subtract `other` from `self` in place, reducing each coefficient into `[0, q)`.
+    pub(crate) fn sub_assign_mod(&mut self, other: &Poly, q: i64) {
+        self.zip_assign_mod(other, q, |lhs, rhs| lhs - rhs);
+    }
+
+    fn zip_assign_mod(&mut self, other: &Poly, q: i64, op: impl Fn(i64, i64) -> i64) {
+        if self.degree() < other.degree() {
+            self.0.resize(other.degree(), 0);
+        }
+        for (i, coeff) in self.0.iter_mut().enumerate() {
+            let other_i = if i < other.degree() { other.0[i] } else { 0 };
+            *coeff = Poly::mod_coeff(op(*coeff, other_i), q);
+        }
+    }
+
+    /// Multiply every coefficient by `k` and reduce it into `[0, q)`, widening the product to
+    /// `i128` so that it cannot overflow.
+    pub(crate) fn mul_int_mod(&self, k: i64, q: i64) -> Poly {
+        let out_val = self
+            .0
+            .iter()
+            .map(|coeff| (*coeff as i128 * k as i128).rem_euclid(q as i128) as i64)
+            .collect();
+        Poly(out_val)
+    }
+
+    /// Multiply every coefficient by `numer / denom` and round to the nearest integer, with ties
+    /// away from zero as for `Mul<f64>`. The product is computed exactly in `i128`, so unlike
+    /// multiplying by the float `numer / denom` it is correct for any coefficient.
+    ///
+    /// Panics if a scaled coefficient does not fit in an `i64`, which needs `|numer| > |denom|`.
+    pub fn scale_round(&self, numer: i64, denom: i64) -> Poly {
+        WidePoly(self.0.iter().map(|coeff| *coeff as i128).collect())
+            .scale_round(numer, denom)
+            .narrow()
+    }
+
+    /// Return the infinity norm of the coefficients as they are, i.e. the largest absolute value.
+    pub fn norm_infinity(&self) -> i64 {
+        self.0.iter().map(|coeff| coeff.abs()).max().unwrap_or(0)
+    }
+
+    /// Return the infinity norm of the coefficients modulo `q`, taking the centered
+    /// representative in `(-q/2, q/2]` of each one. The centering is constant time, as the
+    /// coefficients are typically noise derived from the secret key.
+    pub fn norm_canonical(&self, q: i64) -> i64 {
+        self.0
+            .iter()
+            .map(|coeff| centered_coeff(coeff.rem_euclid(q), q).abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Apply the automorphism `X -> X^k` of `Z[X]/(X^N + 1)` for an odd `k`, which maps the
+    /// coefficient of `X^i` to `X^{i * k mod 2N}`, negated when `i * k mod 2N >= N`.
+    pub(crate) fn automorphism(&self, k: usize) -> Poly {
+        let degree = self.degree();
+        assert_eq!(k % 2, 1, "automorphism exponent {k} is not odd");
+        let mut out_val = vec![0; degree];
+        for (i, coeff) in self.0.iter().enumerate() {
+            let target = i * k % (2 * degree);
+            if target >= degree {
+                out_val[target - degree] -= coeff;
+            } else {
+                out_val[target] += coeff;
+            }
+        }
+        Poly(out_val)
+    }
+
+    /// Multiply by the monomial `X^k` in `Z[X]/(X^N + 1)`, which rotates the coefficients by `k`
+    /// and negates those wrapping around past `X^{N-1}`, since `X^N = -1`. `k` is taken modulo
+    /// `2N`, so that `k = N` negates the polynomial and a negative `k` divides by `X^{-k}`.
+    ///
+    /// This is only correct in the negacyclic ring: in a prime cyclotomic ring, multiply by the
+    /// monomial with `mul_ring` instead.
+    pub fn mul_monomial(&self, k: i64) -> Poly {
+        let degree = self.degree();
+        if degree == 0 {
+            return self.clone();
+        }
+        let shift = k.rem_euclid(2 * degree as i64) as usize;
+        let mut out_val = vec![0; degree];
+        for (i, coeff) in self.0.iter().enumerate() {
+            let target = (i + shift) % (2 * degree);
+            if target >= degree {
+                out_val[target - degree] = -coeff;
+            } else {
+                out_val[target] = *coeff;
+            }
+        }
+        Poly(out_val)
+    }
+
     // Reduce a coefficient into the [0, q) bounds.
     fn mod_coeff(coeff: i64, q: i64) -> i64 {
         (coeff % q + q) % q
     }
 
+    /// Multiply two polynomials inside `R_q = Z_q[X]/(Phi)` for the cyclotomic polynomial `ring`.
+    /// The negacyclic ring goes through `mul_ntt`, the other rings have no NTT and use the
+    /// schoolbook product.
+    pub fn mul_ring(&self, other: &Poly, q: i64, ring: Cyclotomic) -> Poly {
+        match ring {
+            Cyclotomic::Negacyclic(_) => self.mul_ntt(other, q),
+            Cyclotomic::Prime(_) => self.mul_wide(other).reduce_cyclotomic(q, ring),
+        }
+    }
+
+    /// Multiply two polynomials of the same degree `N` inside `R_q = Z_q[X]/(X^N + 1)`.
+    ///
+    /// When `q` is a prime with `2N | q - 1`, both operands are transformed with a negacyclic NTT,
+    /// multiplied pointwise and transformed back in `O(N log N)`. Otherwise this falls back to
+    /// schoolbook multiplication. Either way the result equals `(self * other) % (q, N)`.
+    pub fn mul_ntt(&self, other: &Poly, q: i64) -> Poly {
+        let degree = self.degree();
+        assert_eq!(degree, other.degree());
+
+        match NegacyclicNtt::cached(degree, q) {
+            Some(ntt) => {
+                let out_val = ntt.mul(&self.reduce_unsigned(q), &other.reduce_unsigned(q));
+                Poly(out_val.into_iter().map(|coeff| coeff as i64).collect())
+            }
+            None => self.mul_wide(other).reduce(q, degree),
+        }
+    }
+
+    /// Multiply two polynomials exactly over the integers, with the coefficients of the product
+    /// in `i128` so that it cannot overflow before being scaled or reduced.
+    pub(crate) fn mul_wide(&self, other: &Poly) -> WidePoly {
+        let mut out_val = vec![0i128; (self.degree() + other.degree()).max(1) - 1];
+        for (i, self_i) in self.0.iter().enumerate() {
+            for (j, other_j) in other.0.iter().enumerate() {
+                out_val[i + j] += *self_i as i128 * *other_j as i128;
+            }
+        }
+        WidePoly(out_val)
+    }
+
+    /// Transform the polynomial into NTT form inside `R_q = Z_q[X]/(X^N + 1)`, where products
+    /// are pointwise, or return `None` unless `q` is a prime with `2N | q - 1` (see `mul_ntt`).
+    ///
+    /// ```rust
+    /// use verifiable_bfv::bfv::Poly;
+    /// let a = Poly::new(vec![1, 2, 3, 4]);
+    /// let b = Poly::new(vec![0, 1, 0, 0]);
+    ///
+    /// let a_ntt = a.to_ntt(17).unwrap();
+    /// let b_ntt = b.to_ntt(17).unwrap();
+    /// let product = &(&a_ntt * &b_ntt) * &b_ntt;
+    /// assert_eq!(Poly::from_ntt(&product), a.mul_ntt(&b, 17).mul_ntt(&b, 17));
+    /// ```
+    pub fn to_ntt(&self, q: i64) -> Option<NttPoly> {
+        let ntt = NegacyclicNtt::cached(self.degree(), q)?;
+        Some(NttPoly::forward(self, q, ntt))
+    }
+
+    /// Transform a polynomial in NTT form back to its coefficients in `[0, q)`
+    pub fn from_ntt(poly: &NttPoly) -> Poly {
+        let mut val = poly.val.clone();
+        poly.ntt.backward(&mut val);
+        Poly(val.into_iter().map(|coeff| coeff as i64).collect())
+    }
+
+    fn reduce_unsigned(&self, q: i64) -> Vec<u64> {
+        self.0
+            .iter()
+            .map(|coeff| Poly::mod_coeff(*coeff, q) as u64)
+            .collect()
+    }
+
     // Decompose a polynomial to l levels, with each level base T, such that:
     // $ poly = sum_{i=0}^l poly^(i) T^i $ with $ poly^(i) \in R_T $
     pub fn decompose(self, l: usize, base: i64) -> Vec<Poly> {
@@ -197,9 +470,180 @@ impl Poly {
     }
 }
 
+/// The exact product of polynomials over the integers, with `i128` coefficients, built with
+/// `Poly::mul_wide`. It is narrowed back to a `Poly` of `i64` coefficients only once scaled or
+/// reduced modulo `q`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct WidePoly(Vec<i128>);
+
+impl WidePoly {
+    /// Multiply every coefficient by `numer / denom` and round to the nearest integer, as
+    /// `Poly::scale_round`, panicking if a product with `numer` overflows an `i128`
+    pub(crate) fn scale_round(&self, numer: i64, denom: i64) -> WidePoly {
+        let out_val = self
+            .0
+            .iter()
+            .map(|coeff| {
+                let product = coeff
+                    .checked_mul(numer as i128)
+                    .expect("scaled coefficient overflows i128");
+                rounded_div(product, denom as i128)
+            })
+            .collect();
+        WidePoly(out_val)
+    }
+
+    /// Reduce the polynomial into `R_q = Z_q[X]/(X^degree + 1)`, as `Poly::reduce`
+    pub(crate) fn reduce(&self, q: i64, degree: usize) -> Poly {
+        self.reduce_cyclotomic(q, Cyclotomic::Negacyclic(degree))
+    }
+
+    /// Reduce the polynomial into `R_q = Z_q[X]/(Phi)`, as `Poly::reduce_cyclotomic`
+    pub(crate) fn reduce_cyclotomic(&self, q: i64, ring: Cyclotomic) -> Poly {
+        // Fold the coefficients modulo `q` first, the reduced ones fit in an `i64`.
+        let folded = self
+            .0
+            .iter()
+            .map(|coeff| coeff.rem_euclid(q as i128) as i64)
+            .collect::<Vec<_>>();
+        Poly(folded) % (q, ring)
+    }
+
+    /// Narrow the coefficients back to `i64`, panicking if one does not fit
+    pub(crate) fn narrow(self) -> Poly {
+        let out_val = self
+            .0
+            .into_iter()
+            .map(|coeff| i64::try_from(coeff).expect("polynomial coefficient overflows i64"))
+            .collect();
+        Poly(out_val)
+    }
+}
+
+/// Add two wide polynomials, padding the shorter one with zeros
+impl Add<WidePoly> for WidePoly {
+    type Output = WidePoly;
+    fn add(mut self, other: WidePoly) -> Self::Output {
+        if self.0.len() < other.0.len() {
+            self.0.resize(other.0.len(), 0);
+        }
+        for (i, coeff) in other.0.into_iter().enumerate() {
+            self.0[i] += coeff;
+        }
+        self
+    }
+}
+
+/// A polynomial of `R_q = Z_q[X]/(X^N + 1)` in NTT form, i.e. as its evaluations at the roots
+/// of `X^N + 1`, built with `Poly::to_ntt`.
+///
+/// Products of two `NttPoly` are pointwise, so a polynomial taking part in many multiplications
+/// is transformed once instead of once per product. Multiplying by a `Poly` transforms only that
+/// operand, and `Poly::from_ntt` transforms the result back. Both operands must share `q` and
+/// the degree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NttPoly {
+    val: Vec<u64>,
+    q: i64,
+    ntt: Arc<NegacyclicNtt>,
+}
+
+impl NttPoly {
+    fn forward(poly: &Poly, q: i64, ntt: Arc<NegacyclicNtt>) -> NttPoly {
+        let mut val = poly.reduce_unsigned(q);
+        ntt.forward(&mut val);
+        NttPoly { val, q, ntt }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.val.len()
+    }
+
+    pub fn modulus(&self) -> i64 {
+        self.q
+    }
+
+    // Combine the evaluations of both operands pointwise with `op`, modulo `q`.
+    fn zip_with(&self, other: &NttPoly, op: impl Fn(u128, u128, u128) -> u128) -> NttPoly {
+        assert_eq!(self.q, other.q);
+        assert_eq!(self.degree(), other.degree());
+        let q = self.q as u128;
+        let val = self
+            .val
+            .iter()
+            .zip(other.val.iter())
+            .map(|(x, y)| (op(*x as u128, *y as u128, q) % q) as u64)
+            .collect();
+        NttPoly {
+            val,
+            q: self.q,
+            ntt: self.ntt.clone(),
+        }
+    }
+}
+
+impl Add<&NttPoly> for &NttPoly {
+    type Output = NttPoly;
+    fn add(self, other: &NttPoly) -> Self::Output {
+        self.zip_with(other, |x, y, _| x + y)
+    }
+}
+
+impl Sub<&NttPoly> for &NttPoly {
+    type Output = NttPoly;
+    fn sub(self, other: &NttPoly) -> Self::Output {
+        self.zip_with(other, |x, y, q| x + q - y)
+    }
+}
+
+/// Multiply two polynomials in NTT form pointwise
+impl Mul<&NttPoly> for &NttPoly {
+    type Output = NttPoly;
+    fn mul(self, other: &NttPoly) -> Self::Output {
+        self.zip_with(other, |x, y, _| x * y)
+    }
+}
+
+/// Multiply a polynomial in NTT form by one in coefficient form, transforming the latter
+impl Mul<&Poly> for &NttPoly {
+    type Output = NttPoly;
+    fn mul(self, other: &Poly) -> Self::Output {
+        assert_eq!(self.degree(), other.degree());
+        self * &NttPoly::forward(other, self.q, self.ntt.clone())
+    }
+}
+
+/// Return the centered representative in `(-q/2, q/2]` of a coefficient in `[0, q)`.
+///
+/// The coefficient is typically derived from the secret key (a decrypted message or its noise), so
+/// the upper half is selected in constant time rather than with a branch on its value.
+pub(crate) fn centered_coeff(coeff: i64, q: i64) -> i64 {
+    let is_upper = (coeff as u64).ct_gt(&((q / 2) as u64));
+    i64::conditional_select(&coeff, &(coeff - q), is_upper)
+}
+
+/// Return `a / b` rounded to the nearest integer, with ties away from zero.
+///
+/// Generic over the signed integers whose `/` truncates towards zero, e.g. `i128` for products of
+/// `i64` coefficients and `BigInt` for moduli beyond `i64`.
+pub(crate) fn rounded_div<T: Signed + PartialOrd + Clone>(a: T, b: T) -> T {
+    assert!(!b.is_zero(), "division by zero");
+    let (a, b) = if b.is_negative() { (-a, -b) } else { (a, b) };
+    let two = T::one() + T::one();
+    let b_2 = two.clone() * b.clone();
+    if !a.is_negative() {
+        (two * a + b) / b_2
+    } else {
+        -((two * -a + b) / b_2)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::bfv::poly::Poly;
+    use crate::bfv::poly::{rounded_div, Cyclotomic, Poly};
+    use num_bigint::BigInt;
+    use rand::{Rng, SeedableRng};
+    use std::sync::Arc;
 
     fn a_poly() -> Poly {
         Poly(vec![-7, 0, 0, 3, -1, 6, -3, 5, 9, -5])
@@ -263,6 +707,107 @@ mod tests {
         assert_eq!(mul.0, vec![28, 71, 63, 23, 2]);
     }
 
+    #[test]
+    fn mul_ntt_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(32);
+        // 3329 supports the NTT up to degree 128, 65536 always falls back to schoolbook.
+        for q in [3329, 65536].iter() {
+            for degree in [1, 2, 4, 8, 16, 64, 128].iter() {
+                let a = Poly((0..*degree).map(|_| rng.gen_range(-q, *q)).collect());
+                let b = Poly((0..*degree).map(|_| rng.gen_range(-q, *q)).collect());
+                let expected = (a.clone() * b.clone()) % (*q, *degree);
+                assert_eq!(a.mul_ntt(&b, *q), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn ntt_form_test() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(33);
+        let q = 3329;
+        for degree in [2, 8, 64].iter() {
+            let polys = (0..4)
+                .map(|_| Poly((0..*degree).map(|_| rng.gen_range(-q, q)).collect()))
+                .collect::<Vec<_>>();
+
+            // Chain of products in NTT form against the same chain in coefficient form.
+            let mut expected = polys[0].clone();
+            let mut product = polys[0].to_ntt(q).unwrap();
+            for poly in polys[1..].iter() {
+                expected = expected.mul_ntt(poly, q);
+                product = &product * &poly.to_ntt(q).unwrap();
+            }
+            assert_eq!(Poly::from_ntt(&product), expected);
+
+            // Mixing representations only transforms the coefficient-form operand.
+            let mixed = &polys[0].to_ntt(q).unwrap() * &polys[1];
+            assert_eq!(Poly::from_ntt(&mixed), polys[0].mul_ntt(&polys[1], q));
+
+            let (a, b) = (polys[2].to_ntt(q).unwrap(), polys[3].to_ntt(q).unwrap());
+            assert_eq!(
+                Poly::from_ntt(&(&a + &b)),
+                (polys[2].clone() + polys[3].clone()) % (q, *degree)
+            );
+            assert_eq!(
+                Poly::from_ntt(&(&a - &b)),
+                (polys[2].clone() - polys[3].clone()) % (q, *degree)
+            );
+        }
+        // 65536 is not a prime, so it has no NTT form.
+        assert!(Poly::new(vec![1, 2, 3, 4]).to_ntt(65536).is_none());
+
+        // The tables are computed once per `(degree, q)` and shared by every transform.
+        let a = Poly::new(vec![1, 2, 3, 4]).to_ntt(q).unwrap();
+        let b = Poly::new(vec![4, 3, 2, 1]).to_ntt(q).unwrap();
+        assert!(Arc::ptr_eq(&a.ntt, &b.ntt));
+    }
+
+    #[test]
+    fn mul_wide_near_2_31() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(34);
+        // Products of coefficients below these moduli exceed 2^62, so summing a few of them
+        // overflows an i64. 2013265921 = 15 * 2^27 + 1 has an NTT to check the schoolbook against.
+        let q = 2013265921;
+        for degree in [2, 8, 64].iter() {
+            let a = Poly((0..*degree).map(|_| rng.gen_range(0, q)).collect());
+            let b = Poly((0..*degree).map(|_| rng.gen_range(0, q)).collect());
+            assert_eq!(a.mul_wide(&b).reduce(q, *degree), a.mul_ntt(&b, q));
+            assert_eq!(
+                a.mul_wide(&b)
+                    .reduce_cyclotomic(q, Cyclotomic::Negacyclic(*degree)),
+                a.mul_ntt(&b, q)
+            );
+        }
+
+        // Without an NTT, `mul_ntt` and `mul_ring` fall back to the widened schoolbook.
+        let q = (1 << 31) - 1;
+        let a = Poly(vec![q - 1; 8]);
+        let b = Poly(vec![q - 2; 8]);
+        // (-1) * (-2) = 2 for each pair, and X^8 = -1 folds the upper half back negated.
+        let expected = [-12, -8, -4, 0, 4, 8, 12, 16].map(|c: i64| c.rem_euclid(q));
+        assert_eq!(a.mul_ntt(&b, q).0, expected);
+        let ring = Cyclotomic::Prime(5);
+        let (a, b) = (Poly(vec![q - 1; 4]), Poly(vec![q - 2; 4]));
+        assert_eq!(
+            a.mul_ring(&b, q, ring),
+            (Poly(vec![2; 4]) * Poly(vec![1; 4])) % (q, ring)
+        );
+
+        // Folding large coefficients onto the same degree does not overflow either.
+        let poly = Poly(vec![i64::MAX, i64::MAX, -i64::MAX, -i64::MAX]);
+        let expected = ((i64::MAX as i128 * 2) % q as i128) as i64;
+        assert_eq!((poly.clone() % (q, 1)).0, vec![0]);
+        assert_eq!((poly.clone() % (q, 2)).0, vec![expected, expected]);
+        assert_eq!((poly % (q, Cyclotomic::Prime(2))).0, vec![0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows i64")]
+    fn mul_overflow_panics() {
+        let a = Poly(vec![1 << 32, 1 << 32]);
+        let _ = a.clone() * a;
+    }
+
     #[test]
     fn poly_modulo_test() {
         let a = a_poly();
@@ -292,6 +837,23 @@ mod tests {
         assert_eq!(modulo.0, vec![1, 0, 0, 3, 3, 2, 1, 1, 1, 3]);
     }
 
+    #[test]
+    fn reduce_wrap_around_test() {
+        // X^4 = -1 mod (X^4 + 1)
+        let x_4 = Poly::new(vec![0, 0, 0, 0, 1]);
+        assert_eq!(x_4.reduce(17, 4).0, vec![16, 0, 0, 0]);
+
+        // X^5 + 2X^6 = -X - 2X^2 and X^8 = 1
+        let x_5_6 = Poly::new(vec![0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(x_5_6.reduce(17, 4).0, vec![0, 16, 15, 0]);
+        let x_8 = Poly::new(vec![0, 0, 0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(x_8.reduce(17, 4).0, vec![3, 0, 0, 0]);
+
+        // folded coefficients accumulate before the coefficient reduction
+        let folded = Poly::new(vec![5, 0, 0, 0, 7, 0, 0, 0, 2]);
+        assert_eq!(folded.reduce(17, 4).0, vec![0, 0, 0, 0]);
+    }
+
     #[test]
     fn decomposition_test() {
         let a = a_poly();
@@ -306,4 +868,164 @@ mod tests {
             dec[0].clone() + dec[1].clone() * 2 + dec[2].clone() * 4 + dec[3].clone() * 8;
         assert_eq!(recomposed, a);
     }
+
+    #[test]
+    fn norm_infinity_test() {
+        assert_eq!(a_poly().norm_infinity(), 9);
+        assert_eq!(b_poly().norm_infinity(), 1);
+        assert_eq!(Poly(vec![3, -12, 11]).norm_infinity(), 12);
+        assert_eq!(Poly(vec![0, 0]).norm_infinity(), 0);
+        assert_eq!(Poly(vec![]).norm_infinity(), 0);
+    }
+
+    #[test]
+    fn norm_canonical_test() {
+        // The centered residues mod 17 are [-7, 0, 0, 3, -1, 6, -3, 5, -8, -5].
+        assert_eq!(a_poly().norm_canonical(17), 8);
+        assert_eq!(b_poly().norm_canonical(17), 1);
+        // For an even q, q/2 stays positive and is the largest centered value.
+        assert_eq!(Poly(vec![8, 9, 15]).norm_canonical(16), 8);
+        assert_eq!(Poly(vec![7, 9, -7]).norm_canonical(16), 7);
+        assert_eq!(Poly(vec![-8]).norm_canonical(16), 8);
+        // For an odd q, the centered range (-q/2, q/2] is symmetric.
+        assert_eq!(Poly(vec![8, 9]).norm_canonical(17), 8);
+        assert_eq!(Poly(vec![34, -51, 68]).norm_canonical(17), 0);
+        assert_eq!(Poly(vec![]).norm_canonical(17), 0);
+    }
+
+    #[test]
+    fn mul_monomial_test() {
+        let a = Poly(vec![1, -2, 3, 4]);
+        assert_eq!(a.mul_monomial(0), a);
+        assert_eq!(a.mul_monomial(1).0, vec![-4, 1, -2, 3]);
+        assert_eq!(a.mul_monomial(3).0, vec![2, -3, -4, 1]);
+        // X^N = -1 and X^{2N} = 1
+        assert_eq!(a.mul_monomial(4), -a.clone());
+        assert_eq!(a.mul_monomial(8), a);
+        assert_eq!(a.mul_monomial(-1).0, vec![-2, 3, 4, -1]);
+        assert_eq!(a.mul_monomial(-1).mul_monomial(1), a);
+        assert_eq!(a.mul_monomial(-5), a.mul_monomial(3));
+        assert_eq!(a.mul_monomial(19), a.mul_monomial(3));
+        assert_eq!(Poly(vec![]).mul_monomial(3), Poly(vec![]));
+
+        // Agrees with a general multiplication by X^k
+        let q = 65536;
+        let a = a_poly();
+        let degree = a.degree();
+        for k in [0i64, 1, 5, 9, 10, 13, 19, 20, 31, -1, -11, -20].iter() {
+            let mut monomial = vec![0; degree];
+            let shift = k.rem_euclid(2 * degree as i64) as usize;
+            monomial[shift % degree] = if shift >= degree { -1 } else { 1 };
+            let expected = (a.clone() * Poly(monomial)) % (q, degree);
+            assert_eq!(a.mul_monomial(*k) % (q, degree), expected);
+        }
+    }
+
+    #[test]
+    fn rounded_div_bigint() {
+        let int = BigInt::from;
+        // `2^200 = 3k + 1`, so `2^200` rounds down and `2^200 + 1` rounds up
+        let big = int(1) << 200u32;
+        let third = &big / int(3);
+        let cases = [
+            (big.clone(), int(3), third.clone()),
+            (&big + int(1), int(3), &third + int(1)),
+            (&big + int(1), int(-3), -(&third + int(1))),
+            // Ties round away from zero
+            (&big + int(1), int(2), (&big >> 1u32) + int(1)),
+            (
+                int(1001) * &big + int(1),
+                int(2),
+                int(1001) * (&big >> 1u32) + int(1),
+            ),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(rounded_div(a.clone(), b.clone()), expected);
+            assert_eq!(rounded_div(-a.clone(), b.clone()), -expected.clone());
+            assert_eq!(rounded_div(a, -b), -expected);
+        }
+
+        // Agrees with `i128` where both apply
+        let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+        for _ in 0..100 {
+            let a = rng.gen::<i64>() as i128 * rng.gen::<i32>() as i128;
+            let b = rng.gen_range(1, 1i128 << 40) * if rng.gen() { 1 } else { -1 };
+            assert_eq!(rounded_div(int(a), int(b)), int(rounded_div(a, b)));
+        }
+    }
+
+    #[test]
+    fn scale_round_test() {
+        assert_eq!(rounded_div(7, 2), 4);
+        assert_eq!(rounded_div(-7, 2), -4);
+        assert_eq!(rounded_div(7, -2), -4);
+        assert_eq!(rounded_div(5, 3), 2);
+        assert_eq!(rounded_div(-4, 3), -1);
+        assert_eq!(rounded_div(0, 5), 0);
+        assert_eq!(rounded_div(i128::MAX / 4, 3), (i128::MAX / 4 + 1) / 3);
+
+        // Agrees with the float path where `f64` is precise enough
+        let a = a_poly();
+        for (numer, denom) in [(1, 2), (3, 7), (4, 65536), (-5, 3), (16, 1 << 20)].iter() {
+            let float = a.clone() * (*numer as f64 / *denom as f64);
+            assert_eq!(a.scale_round(*numer, *denom), float);
+        }
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let (q, t) = (1i64 << 24, 4);
+        let raw = Poly((0..64).map(|_| rng.gen_range(-(q * q), q * q)).collect());
+        assert_eq!(raw.scale_round(t, q), raw * (t as f64 / q as f64));
+
+        // The float path loses the low bits of large coefficients, and an `i64` product overflows
+        let a = Poly(vec![(1 << 53) + 1, 3 << 60]);
+        assert_eq!(a.scale_round(1, 2).0, vec![(1 << 52) + 1, 3 << 59]);
+        assert_ne!((a.clone() * 0.5).0[0], (1 << 52) + 1);
+        assert_eq!(a.scale_round(3, 3 << 40).0, vec![8192, 3 << 20]);
+    }
+
+    #[test]
+    #[should_panic(expected = "polynomial coefficient overflows i64")]
+    fn scale_round_overflow() {
+        Poly(vec![1, i64::MAX]).scale_round(3, 2);
+    }
+
+    #[test]
+    fn reduce_cyclotomic_test() {
+        let q = 65536;
+        let a = a_poly();
+        assert_eq!(
+            a.clone() % (q, Cyclotomic::Negacyclic(4)),
+            a.clone() % (q, 4)
+        );
+
+        // Modulo `Phi_5 = 1 + X + X^2 + X^3 + X^4`, `X^5 = 1` and `X^4 = -(1 + X + X^2 + X^3)`
+        let phi_5 = Cyclotomic::Prime(5);
+        assert_eq!(phi_5.degree(), 4);
+        assert_eq!(
+            Poly(vec![0, 0, 0, 0, 0, 1]) % (q, phi_5),
+            Poly(vec![1, 0, 0, 0])
+        );
+        assert_eq!(
+            Poly(vec![0, 0, 0, 0, 1]) % (q, phi_5),
+            Poly(vec![q - 1, q - 1, q - 1, q - 1])
+        );
+        assert_eq!(Poly(vec![1, 1, 1, 1, 1]) % (q, phi_5), Poly(vec![0; 4]));
+
+        // Agrees with reducing `a` by the long division by `Phi_5`
+        let mut remainder = a.0.clone();
+        for i in (4..remainder.len()).rev() {
+            let lead = remainder.pop().unwrap();
+            for coeff in remainder[i - 4..].iter_mut() {
+                *coeff -= lead;
+            }
+        }
+        let expected = Poly(remainder.iter().map(|coeff| coeff.rem_euclid(q)).collect());
+        assert_eq!(a.clone() % (q, phi_5), expected);
+
+        // and the product is compatible with the reduction
+        let b = b_poly();
+        assert_eq!(
+            (a.clone() % (q, phi_5)).mul_ring(&(b.clone() % (q, phi_5)), q, phi_5),
+            (a * b) % (q, phi_5)
+        );
+    }
 }
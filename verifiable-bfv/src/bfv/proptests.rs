@@ -0,0 +1,134 @@
+//! Property-based tests of the homomorphic operations, over random messages, plaintext moduli
+//! and seeds.
+//!
+//! The noise parameters are chosen so that every generated case decrypts correctly: with
+//! `q = 2^20`, `degree <= 8`, `t <= 16` and `std_dev = 1.0`, the noise of a product stays
+//! far below `delta / 2`, so a failure is a bug rather than an unlucky sample.
+//!
+//! The deserializer `Ciphertext::from_bytes` is fuzzed with random and mutated encodings, which
+//! must be rejected or decoded without panicking.
+
+use crate::bfv::ciphertext::Ciphertext;
+use crate::bfv::keys::SecretKey;
+use crate::bfv::plaintext::Plaintext;
+use crate::bfv::poly::Poly;
+use proptest::prelude::*;
+use rand::SeedableRng;
+
+const Q: i64 = 1 << 20;
+const STD_DEV: f64 = 1.0;
+
+/// Generates `(t, m_1, m_2, seed)` with `m_1`, `m_2` of the same power-of-two degree.
+fn messages() -> impl Strategy<Value = (i64, Vec<i64>, Vec<i64>, u64)> {
+    (2..=16i64, 1..=3u32, any::<u64>()).prop_flat_map(|(t, log_degree, seed)| {
+        let degree = 1 << log_degree;
+        (
+            Just(t),
+            prop::collection::vec(0..t, degree),
+            prop::collection::vec(0..t, degree),
+            Just(seed),
+        )
+    })
+}
+
+fn encrypt_pair(
+    t: i64,
+    m_1: &[i64],
+    m_2: &[i64],
+    seed: u64,
+) -> (SecretKey, Ciphertext, Ciphertext) {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let secret_key = SecretKey::generate(m_1.len(), &mut rng);
+    let public_key = secret_key.public_key_gen(Q, STD_DEV, &mut rng);
+    let ciphertext_1 = Plaintext::new(m_1.to_vec(), t).encrypt(&public_key, STD_DEV, &mut rng);
+    let ciphertext_2 = Plaintext::new(m_2.to_vec(), t).encrypt(&public_key, STD_DEV, &mut rng);
+    (secret_key, ciphertext_1, ciphertext_2)
+}
+
+proptest! {
+    #[test]
+    fn add_is_homomorphic((t, m_1, m_2, seed) in messages()) {
+        let degree = m_1.len();
+        let (secret_key, ciphertext_1, ciphertext_2) = encrypt_pair(t, &m_1, &m_2, seed);
+
+        let decrypted = (ciphertext_1 + ciphertext_2).decrypt(&secret_key);
+        let expected = (Poly::new(m_1) + Poly::new(m_2)) % (t, degree);
+        prop_assert_eq!(decrypted.poly(), expected);
+    }
+
+    #[test]
+    fn mul_plain_is_homomorphic((t, m_1, m_2, seed) in messages()) {
+        let degree = m_1.len();
+        let (secret_key, ciphertext_1, _) = encrypt_pair(t, &m_1, &m_2, seed);
+
+        let decrypted = ciphertext_1
+            .mul_plain(&Plaintext::new(m_2.clone(), t))
+            .decrypt(&secret_key);
+        let expected = (Poly::new(m_1) * Poly::new(m_2)) % (t, degree);
+        prop_assert_eq!(decrypted.poly(), expected);
+    }
+
+    #[test]
+    fn basic_mul_is_homomorphic((t, m_1, m_2, seed) in messages()) {
+        let degree = m_1.len();
+        let (secret_key, ciphertext_1, ciphertext_2) = encrypt_pair(t, &m_1, &m_2, seed);
+
+        // Decrypt the degree-2 ciphertext with `(1, s, s^2)`
+        let (c_0, c_1, c_2) = ciphertext_1.basic_mul(ciphertext_2);
+        let s = secret_key.poly;
+        let raw = (c_0 + c_1.mul_ntt(&s, Q) + c_2.mul_ntt(&s.mul_ntt(&s, Q), Q)) % (Q, degree);
+        let decrypted = raw.scale_round(t, Q) % (t, degree);
+        let expected = (Poly::new(m_1) * Poly::new(m_2)) % (t, degree);
+        prop_assert_eq!(decrypted, expected);
+    }
+}
+
+/// Generates the encoding of a fresh ciphertext, whose bytes `from_bytes_never_panics` mutates so
+/// that the header and length checks pass more often than for uniformly random bytes.
+fn encoded_ciphertext() -> impl Strategy<Value = Vec<u8>> {
+    messages().prop_map(|(t, m_1, m_2, seed)| encrypt_pair(t, &m_1, &m_2, seed).1.to_bytes())
+}
+
+// Fuzz the deserializer: any byte string either fails to decode or decodes to a ciphertext of a
+// positive degree that encodes back to the same bytes, and neither panics.
+proptest! {
+    #[test]
+    fn from_random_bytes_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..128)) {
+        if let Ok(ciphertext) = Ciphertext::from_bytes(&bytes) {
+            prop_assert!(ciphertext.degree() > 0);
+            prop_assert_eq!(ciphertext.to_bytes(), bytes);
+        }
+    }
+
+    #[test]
+    fn from_bytes_never_panics(
+        bytes in encoded_ciphertext(),
+        edits in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+        len in any::<prop::sample::Index>(),
+    ) {
+        let mut bytes = bytes;
+        for (index, byte) in edits {
+            let i = index.index(bytes.len());
+            bytes[i] = byte;
+        }
+        for bytes in [&bytes[..], &bytes[..len.index(bytes.len() + 1)]] {
+            if let Ok(ciphertext) = Ciphertext::from_bytes(bytes) {
+                prop_assert!(ciphertext.degree() > 0);
+                prop_assert_eq!(ciphertext.to_bytes(), bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn from_header_never_panics(
+        degree in prop_oneof![Just(0), any::<u64>()],
+        q in any::<i64>(),
+        t in any::<i64>(),
+    ) {
+        let bytes = [degree.to_le_bytes(), q.to_le_bytes(), t.to_le_bytes()].concat();
+        if let Ok(ciphertext) = Ciphertext::from_bytes(&bytes) {
+            prop_assert!(ciphertext.degree() > 0);
+            prop_assert_eq!(ciphertext.to_bytes(), bytes);
+        }
+    }
+}
@@ -1,6 +1,7 @@
+use super::plaintext::{Seed, SeededRng};
 use super::poly::Poly;
 use rand::distributions::{Distribution, Normal, Uniform};
-use rand::{CryptoRng, RngCore};
+use rand::{CryptoRng, RngCore, SeedableRng};
 
 /// Gets the discrete Gaussian distribution D_{Z, sigma} centered over 0, and take the absolute value.
 /// Returns a dimension-length vector of gaussian samples.
@@ -19,3 +20,9 @@ pub fn get_uniform<T: RngCore + CryptoRng>(bound: i64, dimension: usize, rng: &m
     let val: Vec<i64> = (0..dimension).map(|_| between.sample(rng)).collect();
     Poly::new(val)
 }
+
+/// Returns the dimension-length vector sampled uniformly from [0, bound) by a `SeededRng` seeded
+/// with `seed`, so that the same seed always expands to the same vector.
+pub fn expand_seed(seed: Seed, bound: i64, dimension: usize) -> Poly {
+    get_uniform(bound, dimension, &mut SeededRng::from_seed(seed))
+}
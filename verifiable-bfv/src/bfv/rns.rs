@@ -0,0 +1,177 @@
+use super::plaintext::Plaintext;
+use super::poly::Poly;
+use std::ops::{Add, Neg, Sub};
+
+/// A BFV12 Ciphertext in residue number system (RNS) form
+///
+/// For a ciphertext modulus `q = q_1 * ... * q_k` of pairwise coprime (ideally NTT-friendly)
+/// primes, limb `i` holds the residues of the ciphertext modulo `q_i`:
+///
+/// * `c_0[i]` = `[c_0]_{q_i}`
+/// * `c_1[i]` = `[c_1]_{q_i}`
+/// * `primes` = `[q_1, ..., q_k]`
+/// * `t` = the plaintext modulus
+///
+/// Addition, subtraction and multiplication by a plaintext operate on each limb independently.
+///
+/// The RNS form is only a representation of a `Ciphertext`, created by `Ciphertext::to_rns`: `q`
+/// still has to fit in an `i64`, and the limbs do not lift that limit on the modulus.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RnsCiphertext {
+    pub(crate) c_0: Vec<Poly>,
+    pub(crate) c_1: Vec<Poly>,
+    pub(crate) primes: Vec<i64>,
+    pub(crate) t: i64,
+}
+
+impl RnsCiphertext {
+    /// Return the primes whose product is the ciphertext modulus
+    pub fn primes(&self) -> &[i64] {
+        &self.primes
+    }
+
+    /// Multiply a ciphertext by a plaintext polynomial, limb by limb
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Ciphertext, Plaintext, SecretKey};
+    /// let primes = [17, 97];
+    /// let q = 17 * 97;
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let ct = Plaintext::new(vec![0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+    ///
+    /// let rns_ct = ct.to_rns(&primes).mul_plaintext(&Plaintext::new(vec![1, 0, 0, 0], t));
+    /// assert_eq!(Ciphertext::from_rns(&rns_ct).decrypt(&secret_key), ct.decrypt(&secret_key));
+    /// ```
+    pub fn mul_plaintext(&self, pt: &Plaintext) -> RnsCiphertext {
+        let m = pt.poly();
+        let limb_mul = |c: &Vec<Poly>| {
+            c.iter()
+                .zip(self.primes.iter())
+                .map(|(limb, p)| limb.mul_ntt(&m, *p))
+                .collect()
+        };
+        RnsCiphertext {
+            c_0: limb_mul(&self.c_0),
+            c_1: limb_mul(&self.c_1),
+            primes: self.primes.clone(),
+            t: self.t,
+        }
+    }
+
+    fn limb_wise(
+        self,
+        other: RnsCiphertext,
+        op: impl Fn(Poly, Poly, i64) -> Poly,
+    ) -> RnsCiphertext {
+        assert_eq!(self.primes, other.primes);
+        assert_eq!(self.t, other.t);
+        let apply = |lhs: Vec<Poly>, rhs: Vec<Poly>| {
+            lhs.into_iter()
+                .zip(rhs)
+                .zip(self.primes.iter())
+                .map(|((lhs, rhs), p)| op(lhs, rhs, *p))
+                .collect()
+        };
+        RnsCiphertext {
+            c_0: apply(self.c_0, other.c_0),
+            c_1: apply(self.c_1, other.c_1),
+            primes: self.primes,
+            t: self.t,
+        }
+    }
+}
+
+/// Add two RNS ciphertexts over the same primes, limb by limb.
+impl Add<RnsCiphertext> for RnsCiphertext {
+    type Output = Self;
+    fn add(self, other: RnsCiphertext) -> Self::Output {
+        self.limb_wise(other, |lhs, rhs, p| {
+            let degree = lhs.degree();
+            (lhs + rhs) % (p, degree)
+        })
+    }
+}
+
+/// Subtract two RNS ciphertexts over the same primes, limb by limb.
+impl Sub<RnsCiphertext> for RnsCiphertext {
+    type Output = Self;
+    fn sub(self, other: RnsCiphertext) -> Self::Output {
+        self.limb_wise(other, |lhs, rhs, p| {
+            let degree = lhs.degree();
+            (lhs - rhs) % (p, degree)
+        })
+    }
+}
+
+/// Take the negation of an RNS ciphertext, limb by limb.
+impl Neg for RnsCiphertext {
+    type Output = Self;
+    fn neg(mut self) -> Self::Output {
+        let limb_neg = |c: Vec<Poly>, primes: &[i64]| {
+            c.into_iter()
+                .zip(primes)
+                .map(|(limb, p)| {
+                    let degree = limb.degree();
+                    -limb % (*p, degree)
+                })
+                .collect()
+        };
+        self.c_0 = limb_neg(self.c_0, &self.primes);
+        self.c_1 = limb_neg(self.c_1, &self.primes);
+        self
+    }
+}
+
+/// Returns the residues of `poly` modulo each of `primes`.
+pub(crate) fn to_residues(poly: &Poly, primes: &[i64]) -> Vec<Poly> {
+    primes
+        .iter()
+        .map(|p| poly.clone() % (*p, poly.degree()))
+        .collect()
+}
+
+/// Recombines the residues of a polynomial modulo `primes` into its representative in `[0, q)`,
+/// where `q` is the product of `primes`, with the Chinese remainder theorem.
+pub(crate) fn from_residues(residues: &[Poly], primes: &[i64]) -> Poly {
+    assert_eq!(residues.len(), primes.len());
+    let q = primes.iter().map(|p| *p as i128).product::<i128>();
+    let degree = residues[0].degree();
+    let val = (0..degree)
+        .map(|j| {
+            residues
+                .iter()
+                .zip(primes)
+                .map(|(residue, p)| {
+                    let p = *p as i128;
+                    let q_over_p = q / p;
+                    // `q/p * [(q/p)^{-1}]_p` is `1` modulo `p` and `0` modulo the other primes
+                    let inv = inv_mod(q_over_p, p);
+                    (residue.val()[j] as i128 * inv).rem_euclid(p) * q_over_p
+                })
+                .sum::<i128>()
+                .rem_euclid(q) as i64
+        })
+        .collect();
+    Poly::new(val)
+}
+
+/// Returns the inverse of `x` modulo `p`, given that they are coprime.
+pub(crate) fn inv_mod(x: i128, p: i128) -> i128 {
+    let (mut old_r, mut r) = (x.rem_euclid(p), p);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    assert_eq!(old_r, 1, "{x} is not invertible modulo {p}");
+    old_s.rem_euclid(p)
+}
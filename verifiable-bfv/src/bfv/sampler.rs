@@ -0,0 +1,55 @@
+use super::poly::Poly;
+use super::random_source;
+use rand::distributions::{Distribution, Uniform};
+use rand::{CryptoRng, RngCore};
+
+/// A distribution of the error polynomials added during encryption
+///
+/// `Plaintext::encrypt_with_sampler` and `Plaintext::encrypt_symmetric_with_sampler` take any
+/// sampler, while `encrypt` and `encrypt_symmetric` use a `GaussianSampler`.
+pub trait ErrorSampler {
+    /// Sample an error polynomial of degree `degree`
+    fn sample<T: RngCore + CryptoRng>(&self, degree: usize, rng: &mut T) -> Poly;
+}
+
+/// The default sampler, drawing the absolute values of samples of a Gaussian of standard
+/// deviation `std_dev` centered over 0, truncated to integers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GaussianSampler {
+    std_dev: f64,
+}
+
+impl GaussianSampler {
+    /// Instantiate a Gaussian sampler of standard deviation `std_dev`
+    pub fn new(std_dev: f64) -> GaussianSampler {
+        GaussianSampler { std_dev }
+    }
+}
+
+impl ErrorSampler for GaussianSampler {
+    fn sample<T: RngCore + CryptoRng>(&self, degree: usize, rng: &mut T) -> Poly {
+        random_source::get_gaussian(self.std_dev, degree, rng)
+    }
+}
+
+/// A sampler drawing each coefficient uniformly from `[-bound, bound]`, so that the error norm
+/// never exceeds `bound`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UniformSampler {
+    bound: i64,
+}
+
+impl UniformSampler {
+    /// Instantiate a uniform sampler over `[-bound, bound]`
+    pub fn new(bound: i64) -> UniformSampler {
+        assert!(bound >= 0);
+        UniformSampler { bound }
+    }
+}
+
+impl ErrorSampler for UniformSampler {
+    fn sample<T: RngCore + CryptoRng>(&self, degree: usize, rng: &mut T) -> Poly {
+        let between = Uniform::new_inclusive(-self.bound, self.bound);
+        Poly::new((0..degree).map(|_| between.sample(rng)).collect())
+    }
+}
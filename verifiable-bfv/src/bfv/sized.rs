@@ -0,0 +1,232 @@
+use super::ciphertext::Ciphertext;
+use super::error::BfvError;
+use super::keys::{RelinearizationKey1, RelinearizationKeyChain, SecretKey};
+use super::plaintext::Plaintext;
+use super::poly::{Poly, WidePoly};
+use std::ops::Mul;
+
+/// A BFV12 Ciphertext that tracks its number of polynomials, so that it can be multiplied with `*`
+/// without handling the degree 2 output of `Ciphertext::basic_mul` by hand
+///
+/// * `c` = `[c_0, c_1]` or, after a multiplication without relinearization, `[c_0, c_1, c_2]`,
+///   which decrypts with `(1, s, s^2)`. `tensor` builds larger ciphertexts `[c_0, ..., c_k]`,
+///   which decrypt with `(1, s, ..., s^k)` and are relinearized by a `RelinearizationKeyChain`
+/// * `q` = the ciphertext modulus
+/// * `t` = the plaintext modulus
+/// * `rlk` = the Version 1 relinearization key applied after every multiplication, if attached
+///
+/// Without a key, a product keeps its 3 polynomials. It can still be decrypted or relinearized
+/// later, but multiplying it again is an error, as the result would need `s^3` and `s^4`.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 4;
+/// # let q = 65536;
+/// # let t = 4;
+/// # let base = 16;
+/// #
+/// use verifiable_bfv::bfv::{BfvError, Plaintext, SecretKey, SizedCiphertext};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+/// let rlk = secret_key.relin_key_gen_1(q, std_dev, &mut rng, base).unwrap();
+///
+/// let pt = Plaintext::new(vec![0, 1, 0, 0], t);
+/// let ct = SizedCiphertext::new(pt.encrypt(&public_key, std_dev, &mut rng));
+///
+/// // Without a key the product has 3 polynomials, and cannot be multiplied again
+/// let product = (ct.clone() * ct.clone()).unwrap();
+/// assert_eq!(product.size(), 3);
+/// assert_eq!(
+///     (product.clone() * product).unwrap_err(),
+///     BfvError::MissingRelinearizationKey { size: 3 }
+/// );
+///
+/// // With a key attached, every product is relinearized
+/// let ct = ct.with_relin_key(&rlk);
+/// let product = (ct.clone() * ct).unwrap();
+/// assert_eq!(product.size(), 2);
+/// assert_eq!(product.decrypt(&secret_key), Plaintext::new(vec![0, 0, 1, 0], t));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SizedCiphertext<'a> {
+    c: Vec<Poly>,
+    q: i64,
+    t: i64,
+    level: usize,
+    rlk: Option<&'a RelinearizationKey1>,
+}
+
+impl<'a> SizedCiphertext<'a> {
+    /// Wrap a ciphertext of 2 polynomials, without a relinearization key
+    pub fn new(ct: Ciphertext) -> SizedCiphertext<'a> {
+        SizedCiphertext {
+            c: vec![ct.c_0, ct.c_1],
+            q: ct.q,
+            t: ct.t,
+            level: ct.level,
+            rlk: None,
+        }
+    }
+
+    /// Attach a relinearization key, which is then applied after every multiplication
+    pub fn with_relin_key(mut self, rlk: &'a RelinearizationKey1) -> SizedCiphertext<'a> {
+        self.rlk = Some(rlk);
+        self
+    }
+
+    /// Return the number of polynomials, 2 for a fresh ciphertext
+    pub fn size(&self) -> usize {
+        self.c.len()
+    }
+
+    /// Decrypt with `(1, s, s^2, ...)` truncated to the size of the ciphertext
+    pub fn decrypt(&self, secret_key: &SecretKey) -> Plaintext {
+        let s = &secret_key.poly;
+        let degree = s.degree();
+
+        // Horner's rule: `c_0 + s * (c_1 + s * (c_2 + ...))`
+        let raw = self
+            .c
+            .iter()
+            .rev()
+            .fold(Poly::new(vec![0; degree]), |acc, c| {
+                (acc.mul_ntt(s, self.q) + c.clone()) % (self.q, degree)
+            });
+        let poly = raw.scale_round(self.t, self.q) % (self.t, degree);
+
+        Plaintext::new_from_poly(poly, self.t)
+    }
+
+    /// Return the ciphertext of 2 polynomials, relinearizing it with the attached key if it has 3.
+    /// Larger ciphertexts need `relinearize_with_chain`.
+    pub fn relinearize(self) -> Result<Ciphertext, BfvError> {
+        let size = self.size();
+        if size > 3 {
+            return Err(BfvError::MissingRelinearizationKey { size });
+        }
+        let mut c = self.c.into_iter();
+        let ct = Ciphertext {
+            c_0: c.next().unwrap(),
+            c_1: c.next().unwrap(),
+            q: self.q,
+            t: self.t,
+            level: self.level,
+        };
+        match (c.next(), self.rlk) {
+            (None, _) => Ok(ct),
+            (Some(c_2), Some(rlk)) => {
+                let (c_0, c_1) = (ct.c_0.clone(), ct.c_1.clone());
+                Ok(ct.relinearization_1(c_0, c_1, c_2, rlk))
+            }
+            (Some(_), None) => Err(BfvError::MissingRelinearizationKey { size }),
+        }
+    }
+
+    /// Multiply two ciphertexts of any size without relinearizing, returning the ciphertext of
+    /// `size() + other.size() - 1` polynomials `d_k = [round(t/q * sum_{i + j = k} c_i * c'_j)]_q`,
+    /// which decrypts with the powers of `s` up to `s^k`. The product keeps no relinearization key.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 1 << 24;
+    /// # let t = 4;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey, SizedCiphertext};
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+    /// let chain = secret_key.relin_key_chain_gen(q, std_dev, &mut rng, 16, 3).unwrap();
+    ///
+    /// let pt = Plaintext::new(vec![0, 1, 0, 0], t);
+    /// let ct = SizedCiphertext::new(pt.encrypt(&public_key, std_dev, &mut rng));
+    ///
+    /// // `X^3` as a ciphertext of 4 polynomials, relinearized in one go
+    /// let cube = ct.tensor(&ct).tensor(&ct);
+    /// assert_eq!(cube.size(), 4);
+    /// let cube = cube.relinearize_with_chain(&chain).unwrap();
+    /// assert_eq!(cube.decrypt(&secret_key), Plaintext::new(vec![0, 0, 0, 1], t));
+    /// ```
+    pub fn tensor(&self, other: &SizedCiphertext) -> SizedCiphertext<'a> {
+        assert_eq!((self.q, self.t), (other.q, other.t));
+        let degree = self.c[0].degree();
+        let c = (0..self.size() + other.size() - 1)
+            .map(|k| {
+                let raw = self
+                    .c
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i <= k && k - i < other.size())
+                    .fold(WidePoly::default(), |acc, (i, c_i)| {
+                        acc + c_i.mul_wide(&other.c[k - i])
+                    });
+                // Scale by `t/q` exactly, the raw products have more bits than an `f64` mantissa.
+                raw.scale_round(self.t, self.q).reduce(self.q, degree)
+            })
+            .collect();
+        SizedCiphertext {
+            c,
+            q: self.q,
+            t: self.t,
+            level: self.level,
+            rlk: None,
+        }
+    }
+
+    /// Return the ciphertext of 2 polynomials, key switching every `c_i` with `i >= 2` from `s^i`
+    /// to `s` with the keys of `chain`. Returns `MissingRelinearizationKey` if the ciphertext is
+    /// larger than `chain.max_degree() + 1` polynomials.
+    pub fn relinearize_with_chain(
+        self,
+        chain: &RelinearizationKeyChain,
+    ) -> Result<Ciphertext, BfvError> {
+        let size = self.size();
+        if size > chain.max_degree() + 1 {
+            return Err(BfvError::MissingRelinearizationKey { size });
+        }
+        let degree = self.c[0].degree();
+        let mut c = self.c.into_iter();
+        let mut ct = Ciphertext {
+            c_0: c.next().unwrap(),
+            c_1: c.next().unwrap(),
+            q: self.q,
+            t: self.t,
+            level: self.level,
+        };
+        for (c_i, ksk) in c.zip(&chain.keys) {
+            let (ks_0, ks_1) = ct.key_switch_poly(c_i, ksk);
+            ct.c_0 = (ct.c_0 + ks_0) % (ct.q, degree);
+            ct.c_1 = (ct.c_1 + ks_1) % (ct.q, degree);
+        }
+        Ok(ct)
+    }
+}
+
+/// Multiply two ciphertexts under the same moduli, relinearizing the product if either operand
+/// has a key attached, which is then attached to the product as well. Operands of 3 polynomials
+/// are relinearized first, and without a key the multiplication fails.
+impl<'a> Mul<SizedCiphertext<'a>> for SizedCiphertext<'a> {
+    type Output = Result<SizedCiphertext<'a>, BfvError>;
+    fn mul(self, other: SizedCiphertext<'a>) -> Self::Output {
+        assert_eq!((self.q, self.t), (other.q, other.t));
+        let rlk = self.rlk.or(other.rlk);
+        let lhs = SizedCiphertext { rlk, ..self }.relinearize()?;
+        let rhs = SizedCiphertext { rlk, ..other }.relinearize()?;
+
+        let (c_0, c_1, c_2) = lhs.basic_mul(rhs);
+        let product = SizedCiphertext {
+            c: vec![c_0, c_1, c_2],
+            q: lhs.q,
+            t: lhs.t,
+            level: lhs.level,
+            rlk,
+        };
+        match rlk {
+            Some(rlk) => Ok(SizedCiphertext::new(product.relinearize()?).with_relin_key(rlk)),
+            None => Ok(product),
+        }
+    }
+}
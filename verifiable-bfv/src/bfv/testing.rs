@@ -0,0 +1,46 @@
+//! Assertions for tests of code built on BFV12 ciphertexts, available with the `testing` feature
+
+use super::ciphertext::Ciphertext;
+use super::keys::SecretKey;
+use super::plaintext::Plaintext;
+use super::poly::Poly;
+
+/// Assert that a ciphertext decrypts to `expected_msg` under the plaintext modulus `t`
+///
+/// The expected message is reduced the same way as the decryption, into `R_t` for the ring of the
+/// secret key: coefficients beyond the degree wrap around the cyclotomic polynomial and each
+/// coefficient is taken modulo `t`, so `-1` and `t - 1` are the same message. On failure, both
+/// messages are printed as centered representatives in `(-t/2, t/2]`.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// # let std_dev = 3.2;
+/// # let degree = 4;
+/// # let q = 65536;
+/// # let t = 8;
+/// #
+/// use verifiable_bfv::bfv::testing::assert_decrypts_to;
+/// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+/// let secret_key = SecretKey::generate(degree, &mut rng);
+/// let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+///
+/// let ct = Plaintext::new(vec![7, 1, 0, 0], t).encrypt(&public_key, std_dev, &mut rng);
+/// assert_decrypts_to(&ct, &secret_key, &[-1, 1], t);
+/// ```
+#[track_caller]
+pub fn assert_decrypts_to(ct: &Ciphertext, sk: &SecretKey, expected_msg: &[i64], t: i64) {
+    assert_eq!(
+        ct.t, t,
+        "the ciphertext has plaintext modulus {}, not {t}",
+        ct.t
+    );
+    let decrypted = ct.decrypt(sk);
+    let expected = Poly::new(expected_msg.to_vec()) % (t, sk.ring);
+    assert!(
+        decrypted.poly() == expected,
+        "the ciphertext decrypts to {:?}, expected {:?}",
+        decrypted.to_vec(),
+        Plaintext::new_from_poly(expected, t).to_vec()
+    );
+}
@@ -1,7 +1,23 @@
 #[cfg(test)]
 mod tests {
-    use crate::bfv::keys::SecretKey;
+    use crate::bfv::bootstrap::{blind_rotate, BootstrappingKey};
+    use crate::bfv::ciphertext::{product_with, Ciphertext};
+    use crate::bfv::ciphertext_vec::CiphertextVec;
+    use crate::bfv::encoder::CkksEncoder;
+    use crate::bfv::error::BfvError;
+    use crate::bfv::evaluator::{Decryptor, Evaluator};
+    use crate::bfv::integer::EncryptedInteger;
+    use crate::bfv::keygen::KeyGenerator;
+    use crate::bfv::keys::{KeySwitchKey, PublicKey, SecretKey};
+    use crate::bfv::modulus_chain::ModulusChain;
+    use crate::bfv::noise::{NoiseOp, NoiseSimulator};
+    use crate::bfv::params::BfvParameters;
     use crate::bfv::plaintext::Plaintext;
+    use crate::bfv::poly::{centered_coeff, Cyclotomic, Poly};
+    use crate::bfv::rns::RnsCiphertext;
+    use crate::bfv::sampler::{ErrorSampler, GaussianSampler, UniformSampler};
+    use crate::bfv::sized::SizedCiphertext;
+    use crate::bfv::testing::assert_decrypts_to;
     use rand::SeedableRng;
 
     fn encrypt_decrypt_helper(msg: Vec<i64>, t: i64, q: i64, std_dev: f64) {
@@ -28,6 +44,164 @@ mod tests {
         }
     }
 
+    fn decrypt_to_vec_helper(msg: Vec<i64>, t: i64, q: i64, std_dev: f64) {
+        let degree = msg.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintext = Plaintext::new(msg.clone(), t);
+        assert_eq!(plaintext.to_vec(), msg);
+
+        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+        assert_eq!(ciphertext.decrypt_to_vec(&secret_key), msg);
+    }
+
+    #[test]
+    fn decrypt_to_vec() {
+        // messages within `(-t/2, t/2]`, including `t/2` itself
+        decrypt_to_vec_helper(vec![1, 0], 2, 65536, 3.2);
+        decrypt_to_vec_helper(vec![2, -1, 0, 1], 4, 65536, 3.2);
+        decrypt_to_vec_helper(vec![0, 1, -1, 2, -2, 3, -3, 4], 8, 65536, 3.2);
+        decrypt_to_vec_helper(vec![0, 1, -1, 2, -2, 3, -3, 1], 7, 65536, 3.2);
+        decrypt_to_vec_helper(vec![16, -15, 7, 0], 32, 65536, 3.2);
+    }
+
+    fn encrypt_chunks_helper(msg: Vec<i64>, t: i64, degree: usize, q: i64, std_dev: f64) {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintexts = Plaintext::encode_chunks(&msg, t, degree);
+        assert_eq!(plaintexts.len(), msg.len().div_ceil(degree));
+        let last = plaintexts.last().unwrap().poly();
+        assert!(last.val()[msg.len() - (plaintexts.len() - 1) * degree..]
+            .iter()
+            .all(|coeff| *coeff == 0));
+
+        let ciphertexts: Vec<Ciphertext> = plaintexts
+            .iter()
+            .map(|pt| pt.encrypt(&public_key, std_dev, &mut rng))
+            .collect();
+        let decrypted: Vec<Plaintext> = ciphertexts
+            .iter()
+            .map(|ct| ct.decrypt(&secret_key))
+            .collect();
+        assert_eq!(Plaintext::decode_chunks(&decrypted, msg.len()), msg);
+    }
+
+    #[test]
+    fn encrypt_chunks() {
+        // message lengths that are not a multiple of `degree`
+        encrypt_chunks_helper(vec![1, 0, 1], 2, 2, 65536, 3.2);
+        encrypt_chunks_helper((0..10).collect(), 16, 4, 65536, 3.2);
+        encrypt_chunks_helper((0..21).map(|i| i % 32).collect(), 32, 8, 65536, 3.2);
+        // a message shorter than `degree`
+        encrypt_chunks_helper(vec![3, 2, 1], 4, 8, 65536, 3.2);
+    }
+
+    fn rand_distribution_helper(degree: usize, t: i64) {
+        let samples: Vec<i64> = (0..100)
+            .flat_map(|seed| {
+                Plaintext::rand_with_seed(degree, t, seed)
+                    .poly()
+                    .val()
+                    .clone()
+            })
+            .collect();
+
+        // residues are uniform over `[0, t)`, not centered
+        assert!(samples.iter().all(|coeff| (0..t).contains(coeff)));
+        for value in 0..t {
+            assert!(samples.contains(&value));
+        }
+        let mean = samples.iter().sum::<i64>() as f64 / samples.len() as f64;
+        assert!((mean - (t - 1) as f64 / 2.0).abs() < t as f64 / 10.0);
+
+        assert_eq!(
+            Plaintext::rand_with_seed(degree, t, 7),
+            Plaintext::rand_with_seed(degree, t, 7)
+        );
+    }
+
+    #[test]
+    fn rand_distribution() {
+        for t in [2, 3, 4, 16, 32].iter() {
+            rand_distribution_helper(64, *t);
+        }
+    }
+
+    fn format_helper(degree: usize, t: i64, q: i64, std_dev: f64) {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintext = Plaintext::rand(degree, t, &mut rng);
+        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+
+        let displayed = plaintext.to_string();
+        assert!(displayed.ends_with(&format!("t = {})", t)));
+        assert!(displayed.len() < 200);
+        let debugged = format!("{:?}", ciphertext);
+        assert!(debugged.starts_with(&format!("Ciphertext {{ degree: {}, q: {}", degree, q)));
+        assert!(debugged.len() < 200);
+    }
+
+    #[test]
+    fn format() {
+        for degree in [1, 4, 16, 17, 1024].iter() {
+            format_helper(*degree, 16, 65536, 3.2);
+        }
+
+        // empty polynomials
+        assert_eq!(Plaintext::new(vec![], 4).to_string(), "[] (t = 4)");
+        let empty = Ciphertext {
+            c_0: Poly::new(vec![]),
+            c_1: Poly::new(vec![]),
+            q: 65536,
+            t: 4,
+            level: 0,
+        };
+        assert_eq!(
+            format!("{:?}", empty),
+            "Ciphertext { degree: 0, q: 65536, t: 4, c_0_norm: 0, c_1_norm: 0 }"
+        );
+    }
+
+    fn encrypt_symmetric_helper(msg: Vec<i64>, t: i64, q: i64, std_dev: f64) {
+        let degree = msg.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintext = Plaintext::new(msg, t);
+        let symmetric_ciphertext = plaintext.encrypt_symmetric(&secret_key, q, std_dev, &mut rng);
+        let public_ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+
+        let decrypted = symmetric_ciphertext.decrypt(&secret_key);
+        assert_eq!(decrypted.poly(), plaintext.poly() % (t, degree));
+
+        // Symmetric encryption only adds a single error term, whereas public-key encryption
+        // also carries `e * u + e_2 * s` from the public key.
+        let symmetric_budget = symmetric_ciphertext.noise_budget(&secret_key);
+        let public_budget = public_ciphertext.noise_budget(&secret_key);
+        assert!(symmetric_budget > 0);
+        assert!(symmetric_budget >= public_budget);
+    }
+
+    #[test]
+    fn encrypt_symmetric_decrypt() {
+        for t in [2, 4, 8, 16, 32].iter() {
+            encrypt_symmetric_helper(vec![1, 0], *t, 65536, 3.2);
+            encrypt_symmetric_helper(vec![3, 2, 1, 0], *t, 65536, 3.2);
+            encrypt_symmetric_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *t, 65536, 3.2);
+        }
+    }
+
     fn encrypt_add_sub_decrypt_helper(
         msg_1: Vec<i64>,
         msg_2: Vec<i64>,
@@ -83,6 +257,235 @@ mod tests {
         }
     }
 
+    fn ref_operators_helper(msg_1: Vec<i64>, msg_2: Vec<i64>, t: i64, q: i64, std_dev: f64) {
+        let degree = msg_1.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(45);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let ciphertext_1 = Plaintext::new(msg_1, t).encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_2 = Plaintext::new(msg_2, t).encrypt(&public_key, std_dev, &mut rng);
+
+        // the operands stay available after every reference operator
+        let add_ciphertext = &ciphertext_1 + &ciphertext_2;
+        let sub_ciphertext = &ciphertext_1 - &ciphertext_2;
+        let neg_ciphertext = -&ciphertext_1;
+        let chained = &(&ciphertext_1 + &ciphertext_2) - &ciphertext_1;
+
+        assert_eq!(add_ciphertext, ciphertext_1.clone() + ciphertext_2.clone());
+        assert_eq!(sub_ciphertext, ciphertext_1.clone() - ciphertext_2.clone());
+        assert_eq!(neg_ciphertext, -ciphertext_1.clone());
+        assert_eq!(
+            chained.decrypt(&secret_key),
+            ciphertext_2.decrypt(&secret_key)
+        );
+    }
+
+    #[test]
+    fn ref_operators() {
+        for t in [2, 4, 8, 16, 32].iter() {
+            ref_operators_helper(vec![0, 6], vec![7, 2], *t, 65536, 3.2);
+            ref_operators_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, 65536, 3.2);
+        }
+    }
+
+    fn accumulate_helper(count: usize, degree: usize, t: i64, q: i64, std_dev: f64) {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintexts: Vec<Plaintext> = (0..count)
+            .map(|_| Plaintext::rand(degree, t, &mut rng))
+            .collect();
+        let ciphertexts: Vec<Ciphertext> = plaintexts
+            .iter()
+            .map(|pt| pt.encrypt(&public_key, std_dev, &mut rng))
+            .collect();
+
+        let mut acc = ciphertexts[0].clone();
+        for ct in &ciphertexts[1..] {
+            acc += ct;
+        }
+        let folded = ciphertexts[1..]
+            .iter()
+            .fold(ciphertexts[0].clone(), |acc, ct| acc + ct.clone());
+        assert_eq!(acc.c_0, folded.c_0 % (q, degree));
+        assert_eq!(acc.c_1, folded.c_1 % (q, degree));
+
+        let expected = plaintexts
+            .iter()
+            .fold(Poly::new(vec![0; degree]), |acc, pt| acc + pt.poly())
+            % (t, degree);
+        assert_eq!(acc.decrypt(&secret_key).poly(), expected);
+
+        // subtracting everything but the first ciphertext back in place recovers it
+        for ct in &ciphertexts[1..] {
+            acc -= ct;
+        }
+        assert_eq!(acc.c_0, ciphertexts[0].c_0.clone() % (q, degree));
+        assert_eq!(acc.c_1, ciphertexts[0].c_1.clone() % (q, degree));
+    }
+
+    #[test]
+    fn accumulate_in_place() {
+        for t in [2, 4, 8, 16, 32].iter() {
+            accumulate_helper(10, 8, *t, 65536, 3.2);
+        }
+    }
+
+    fn rescale_plaintext_modulus_helper(msg: Vec<i64>, t: i64, new_t: i64, q: i64, std_dev: f64) {
+        let degree = msg.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(47);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintext = Plaintext::new(msg.clone(), t);
+        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+        let switched = ciphertext.rescale_plaintext_modulus(new_t);
+
+        // only `[m]_g` survives the switch, embedded as `new_t/g * [m]_g`
+        let g = (1..=t.min(new_t))
+            .rev()
+            .find(|g| t % g == 0 && new_t % g == 0)
+            .unwrap();
+        let expected = msg
+            .iter()
+            .map(|m| (new_t / g * m.rem_euclid(g)).rem_euclid(new_t))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            switched.decrypt(&secret_key),
+            Plaintext::new(expected, new_t)
+        );
+    }
+
+    #[test]
+    fn rescale_plaintext_modulus() {
+        let msg = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+        // `new_t` divides `t`: the message is reduced modulo `new_t`
+        rescale_plaintext_modulus_helper(msg.clone(), 16, 8, 65536, 3.2);
+        rescale_plaintext_modulus_helper(msg.clone(), 16, 2, 65536, 3.2);
+        rescale_plaintext_modulus_helper(msg[..6].to_vec(), 6, 3, 65536, 3.2);
+        // `new_t` does not divide `t`
+        rescale_plaintext_modulus_helper(msg[..12].to_vec(), 12, 8, 65536, 3.2);
+        rescale_plaintext_modulus_helper(msg[..8].to_vec(), 8, 12, 65536, 3.2);
+        rescale_plaintext_modulus_helper(msg[..4].to_vec(), 10, 4, 65536, 3.2);
+    }
+
+    fn transparent_helper(msg: Vec<i64>, t: i64, q: i64, std_dev: f64) {
+        let degree = msg.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(49);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let ciphertext = Plaintext::new(msg, t).encrypt(&public_key, std_dev, &mut rng);
+        assert!(!ciphertext.is_transparent());
+        assert!(!(&ciphertext + &ciphertext).is_transparent());
+
+        let copy = ciphertext.clone();
+        assert!((&ciphertext - &copy).is_transparent());
+        let mut acc = ciphertext.clone();
+        acc -= &copy;
+        assert!(acc.is_transparent());
+        // `c_1` is only zero modulo `q`
+        let wrapped = Ciphertext {
+            c_1: Poly::new(vec![q; degree]),
+            ..ciphertext
+        };
+        assert!(wrapped.is_transparent());
+    }
+
+    #[test]
+    fn transparent() {
+        for t in [2, 4, 8, 16, 32].iter() {
+            transparent_helper(vec![0, 6], *t, 65536, 3.2);
+            transparent_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *t, 65536, 3.2);
+        }
+    }
+
+    fn rns_arithmetic_helper(msg_1: Vec<i64>, msg_2: Vec<i64>, t: i64, primes: &[i64]) {
+        let q = primes.iter().product::<i64>();
+        let std_dev = 3.2;
+        let degree = msg_1.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(21);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintext_1 = Plaintext::new(msg_1, t);
+        let plaintext_2 = Plaintext::new(msg_2, t);
+        let ciphertext_1 = plaintext_1.encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext_2 = plaintext_2.encrypt(&public_key, std_dev, &mut rng);
+        let rns_1 = ciphertext_1.to_rns(primes);
+        let rns_2 = ciphertext_2.to_rns(primes);
+
+        let assert_matches = |rns: RnsCiphertext, expected: Ciphertext| {
+            let recombined = Ciphertext::from_rns(&rns);
+            assert_eq!(recombined.c_0, expected.c_0 % (q, degree));
+            assert_eq!(recombined.c_1, expected.c_1 % (q, degree));
+        };
+
+        assert_matches(rns_1.clone(), ciphertext_1.clone());
+        assert_matches(
+            rns_1.clone() + rns_2.clone(),
+            ciphertext_1.clone() + ciphertext_2.clone(),
+        );
+        assert_matches(
+            rns_1.clone() - rns_2.clone(),
+            ciphertext_1.clone() - ciphertext_2.clone(),
+        );
+        assert_matches(-rns_1.clone(), -ciphertext_1.clone());
+
+        let m = plaintext_2.poly();
+        let mul_plaintext = Ciphertext {
+            c_0: ciphertext_1.c_0.clone() * m.clone(),
+            c_1: ciphertext_1.c_1.clone() * m,
+            q,
+            t,
+            level: 0,
+        };
+        assert_matches(rns_1.mul_plaintext(&plaintext_2), mul_plaintext);
+
+        let decrypted_add = Ciphertext::from_rns(&(rns_1 + rns_2)).decrypt(&secret_key);
+        let expected_add = (plaintext_1.poly() + plaintext_2.poly()) % (t, degree);
+        assert_eq!(decrypted_add.poly(), expected_add);
+    }
+
+    #[test]
+    fn rns_arithmetic() {
+        // primes `p` with `2N | p - 1`, so that each limb supports NTT
+        for t in [2, 4, 8].iter() {
+            rns_arithmetic_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], *t, &[17, 97]);
+            rns_arithmetic_helper(
+                vec![0, 1, 2, 3, 4, 5, 6, 7],
+                vec![7, 6, 5, 4, 3, 2, 1, 0],
+                *t,
+                &[97, 193, 257],
+            );
+        }
+    }
+
+    #[test]
+    fn rns_roundtrip_large_modulus() {
+        // `q` is close to `2^62`, so the recombination needs `i128`
+        let primes = [2147483647, 2147483629];
+        let q = primes[0] * primes[1];
+        let ciphertext = Ciphertext {
+            c_0: Poly::new(vec![0, 1, q - 2, q - 1]),
+            c_1: Poly::new(vec![q / 2, primes[0], primes[1], q / 3]),
+            q,
+            t: 4,
+            level: 0,
+        };
+        assert_eq!(
+            Ciphertext::from_rns(&ciphertext.to_rns(&primes)),
+            ciphertext
+        );
+    }
+
     fn basic_mul_helper(msg_1: Vec<i64>, msg_2: Vec<i64>, t: i64, q: i64, std_dev: f64) {
         let degree = msg_1.len();
         let mut rng = rand::rngs::StdRng::seed_from_u64(20);
@@ -98,11 +501,12 @@ mod tests {
         // Multiply without relinearizing
         let (c_0, c_1, c_2) = ciphertext_1.clone().basic_mul(ciphertext_2.clone());
 
-        // Decrypt non-relinearized multilication output
+        // Decrypt non-relinearized multilication output, reducing into `R_q` before rounding so
+        // that the rounding errors of the wrapped coefficients do not add up
         let s = secret_key.poly;
-        let delta_inv = t as f64 / q as f64;
-        let raw = c_0.clone() + c_1.clone() * s.clone() + c_2.clone() * s.clone() * s.clone();
-        let decrypted_mul = (raw * delta_inv) % (t, degree);
+        let raw = (c_0.clone() + c_1.clone() * s.clone() + c_2.clone() * s.clone() * s.clone())
+            % (q, degree);
+        let decrypted_mul = raw.scale_round(t, q) % (t, degree);
 
         assert_eq!(
             decrypted_mul,
@@ -119,6 +523,29 @@ mod tests {
         }
     }
 
+    // Test multiplication at the usual error, which the exact scaling by `t/q` keeps correct
+    #[test]
+    fn basic_mul_test_std_dev_3_2() {
+        for t in [2, 4, 8, 16] {
+            basic_mul_helper(vec![0, 6], vec![7, 2], t, 1 << 24, 3.2);
+            basic_mul_helper(vec![3, 2, 1, 0], vec![1, 2, 3, 4], t, 1 << 24, 3.2);
+            basic_mul_helper(vec![1, 0, 3, 0, 5, 0, 7, 0], vec![2; 8], t, 1 << 24, 3.2);
+        }
+    }
+
+    // Test that `delta = floor(q/t)` is exact for a `q` that an `f64` rounds up to `2^62`
+    #[test]
+    fn delta_is_exact_for_large_q() {
+        let (q, t) = ((1 << 62) - 1, 2);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(4, &mut rng);
+        let pt = Plaintext::new(vec![1, 0, 1, 1], t);
+
+        let ct = Ciphertext::trivial(&pt, q);
+        assert_eq!(ct.c_0, Poly::new(vec![q / t, 0, q / t, q / t]));
+        assert_eq!(ct.decrypt(&secret_key), pt);
+    }
+
     fn relin_1_mul_helper(
         msg_1: Vec<i64>,
         msg_2: Vec<i64>,
@@ -139,7 +566,9 @@ mod tests {
         let ciphertext_2 = plaintext_2.encrypt(&public_key, std_dev, &mut rng);
 
         // Homomorphic multiplication with relinearization
-        let rlk_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, base);
+        let rlk_1 = secret_key
+            .relin_key_gen_1(q, std_dev, &mut rng, base)
+            .unwrap();
         let mul_ciphertext = ciphertext_1.clone() * (ciphertext_2.clone(), &rlk_1);
         let decrypted_mul = mul_ciphertext.decrypt(&secret_key);
         assert_eq!(
@@ -217,7 +646,7 @@ mod tests {
         let ciphertext_2 = plaintext_2.encrypt(&public_key, std_dev, &mut rng);
 
         // Homomorphic multiplication with relinearization
-        let rlk_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p);
+        let rlk_2 = secret_key.relin_key_gen_2(q, std_dev, &mut rng, p).unwrap();
         let mul_ciphertext = ciphertext_1.clone() * (ciphertext_2.clone(), &rlk_2);
         let decrypted_mul = mul_ciphertext.decrypt(&secret_key);
         assert_eq!(
@@ -261,7 +690,9 @@ mod tests {
 
             let secret_key = SecretKey::generate(degree, &mut rng);
             let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
-            let rlk_1 = secret_key.relin_key_gen_1(q, std_dev, &mut rng, rlk_base);
+            let rlk_1 = secret_key
+                .relin_key_gen_1(q, std_dev, &mut rng, rlk_base)
+                .unwrap();
 
             let pt_1 = Plaintext::rand(degree, t, &mut rng);
             let pt_2 = Plaintext::rand(degree, t, &mut rng);
@@ -280,4 +711,1604 @@ mod tests {
             assert_eq!(expr_pt.poly(), expected_pt);
         }
     }
+
+    // Test that an `Evaluator` computes the ciphertext of `end_to_end_test` from the same keys
+    #[test]
+    fn evaluator_end_to_end() {
+        let (degree, q, t, std_dev) = (4, 65536, 16, 3.2);
+        let rlk_base = (q as f64).log2() as i64;
+        let params = BfvParameters::new(degree, q, t, std_dev);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(23);
+        let mut keygen = KeyGenerator::new(params, &mut rng);
+        let ev = Evaluator::new(
+            params,
+            keygen.public_key(),
+            keygen.relinearization_key(rlk_base).unwrap(),
+        );
+        let decryptor = Decryptor::new(keygen.secret_key().clone());
+        let encryptor = ev.encryptor();
+
+        let pts = [0; 4].map(|_| Plaintext::rand(degree, t, &mut rng));
+        let cts = pts.each_ref().map(|pt| encryptor.encrypt(pt, &mut rng));
+        let expr_ct = ev.add(&ev.mul(&cts[0], &cts[1]), &ev.mul(&cts[2], &cts[3]));
+
+        let expected_pt =
+            (pts[0].poly() * pts[1].poly() + pts[2].poly() * pts[3].poly()) % (t, degree);
+        assert_eq!(decryptor.decrypt(&expr_ct).poly(), expected_pt);
+
+        // The same RNG draws reproduce the ciphertext with explicit keys
+        let mut rng = rand::rngs::StdRng::seed_from_u64(23);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let rlk_1 = secret_key
+            .relin_key_gen_1(q, std_dev, &mut rng, rlk_base)
+            .unwrap();
+        let pts = [0; 4].map(|_| Plaintext::rand(degree, t, &mut rng));
+        let [ct_1, ct_2, ct_3, ct_4] = pts
+            .each_ref()
+            .map(|pt| pt.encrypt(&public_key, std_dev, &mut rng));
+        assert_eq!(expr_ct, ct_1 * (ct_2, &rlk_1) + ct_3 * (ct_4, &rlk_1));
+
+        // `sub` and `mul_plain` agree with the `Ciphertext` operations
+        let diff = ev.sub(&expr_ct, &cts[0]);
+        assert_eq!(
+            decryptor.decrypt(&diff).poly(),
+            (expected_pt.clone() - pts[0].poly()) % (t, degree)
+        );
+        assert_eq!(ev.mul_plain(&cts[0], &pts[1]), cts[0].mul_plain(&pts[1]));
+    }
+
+    #[test]
+    fn key_generator() {
+        let q = 65536;
+        let t = 16;
+        let std_dev = 3.2;
+        let degree = 4;
+        let rlk_base = (q as f64).log2() as i64;
+        let params = BfvParameters::new(degree, q, t, std_dev);
+
+        // From the same RNG, the generator samples exactly the keys of `end_to_end_test`.
+        let mut rng = rand::rngs::StdRng::seed_from_u64(23);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let rlk_1 = secret_key
+            .relin_key_gen_1(q, std_dev, &mut rng, rlk_base)
+            .unwrap();
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(23);
+        let keys = KeyGenerator::new(params, &mut rng)
+            .key_set(rlk_base, &[1, 2])
+            .unwrap();
+        assert_eq!(keys.secret_key.poly, secret_key.poly);
+        assert_eq!(keys.public_key.p_0, public_key.p_0);
+        assert_eq!(keys.public_key.p_1, public_key.p_1);
+        assert_eq!(keys.relinearization_key.ksk.val, rlk_1.ksk.val);
+        assert_eq!(keys.galois_keys.steps().collect::<Vec<_>>(), vec![1, 2]);
+
+        // The bundled keys then evaluate the expression of `end_to_end_test`.
+        let pt_1 = Plaintext::rand(degree, t, &mut rng);
+        let pt_2 = Plaintext::rand(degree, t, &mut rng);
+        let pt_3 = Plaintext::rand(degree, t, &mut rng);
+        let pt_4 = Plaintext::rand(degree, t, &mut rng);
+
+        let ct_1 = pt_1.encrypt(&keys.public_key, std_dev, &mut rng);
+        let ct_2 = pt_2.encrypt(&keys.public_key, std_dev, &mut rng);
+        let ct_3 = pt_3.encrypt(&keys.public_key, std_dev, &mut rng);
+        let ct_4 = pt_4.encrypt(&keys.public_key, std_dev, &mut rng);
+
+        for step in keys.galois_keys.steps() {
+            let gk = keys.galois_keys.get(step).unwrap();
+            let rotated = ct_1.rotate(gk).decrypt(&keys.secret_key);
+            assert_eq!(rotated.poly(), pt_1.poly().automorphism(gk.k) % (t, degree));
+        }
+
+        let rlk = &keys.relinearization_key;
+        let expr_ct = ct_1 * (ct_2, rlk) + ct_3 * (ct_4, rlk);
+        let expr_pt = expr_ct.decrypt(&keys.secret_key);
+
+        let expected_pt = (pt_1.poly() * pt_2.poly() + pt_3.poly() * pt_4.poly()) % (t, degree);
+        assert_eq!(expr_pt.poly(), expected_pt);
+    }
+
+    #[test]
+    fn constant_time_centering() {
+        for q in [2, 3, 4, 16, 17, 65536].iter() {
+            for coeff in 0..*q {
+                let expected = if coeff > q / 2 { coeff - q } else { coeff };
+                assert_eq!(centered_coeff(coeff, *q), expected);
+            }
+        }
+
+        let q = 65536;
+        let std_dev = 3.2;
+        let degree = 8;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        for t in [2, 3, 4, 16, 17].iter() {
+            let pt = Plaintext::rand(degree, *t, &mut rng);
+            let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+
+            let expected: Vec<i64> = (pt.poly() % (*t, degree))
+                .val()
+                .iter()
+                .map(|coeff| if *coeff > t / 2 { coeff - t } else { *coeff })
+                .collect();
+            assert_eq!(ct.decrypt_to_vec(&secret_key), expected);
+        }
+    }
+
+    #[test]
+    fn encrypt_seeded() {
+        let q = 65536;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        for degree in [8, 16, 32].iter() {
+            for t in [2, 4, 16].iter() {
+                let secret_key = SecretKey::generate(*degree, &mut rng);
+                let pt = Plaintext::rand(*degree, *t, &mut rng);
+                let full = pt.encrypt_symmetric(&secret_key, q, std_dev, &mut rng);
+                let (ct, seed) = pt.encrypt_seeded(&secret_key, q, std_dev, &mut rng);
+
+                let expanded = Ciphertext::from_seeded(ct.c_0(), seed, q, *t);
+                assert_eq!(expanded, ct);
+                assert_eq!(expanded.decrypt(&secret_key), full.decrypt(&secret_key));
+
+                // The seeded form stores `c_0` and the seed, the full form `c_0` and `c_1`, which
+                // is larger as soon as `c_1` takes more than the 32 bytes of the seed.
+                let seeded_size = std::mem::size_of_val(ct.c_0()) + seed.len();
+                let full_size = 2 * std::mem::size_of_val(full.c_0());
+                assert!(seeded_size < full_size);
+            }
+        }
+    }
+
+    fn mul_int_helper(msg: Vec<i64>, k: i64, t: i64, q: i64, std_dev: f64) {
+        let degree = msg.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintext = Plaintext::new(msg, t);
+        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+
+        let product = ciphertext.mul_int(k);
+        assert!(product.c_0.val().iter().all(|coeff| (0..q).contains(coeff)));
+        assert!(product.c_1.val().iter().all(|coeff| (0..q).contains(coeff)));
+
+        let expected = (plaintext.poly() * (k % t)) % (t, degree);
+        assert_eq!(product.decrypt(&secret_key).poly(), expected);
+    }
+
+    #[test]
+    fn mul_int() {
+        for t in [2, 3, 8, 16].iter() {
+            for k in [0, 1, 2, 5, -1, -7, i64::MAX, i64::MIN, 1 << 62].iter() {
+                mul_int_helper(vec![3, 2, 1, 0], *k, *t, 65536, 3.2);
+                mul_int_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *k, *t, 65536, 3.2);
+            }
+        }
+
+        // Multiplying by -1 is the negation.
+        let q = 65536;
+        let degree = 4;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, 3.2, &mut rng);
+        let ciphertext = Plaintext::new(vec![0, 1, 2, 3], 16).encrypt(&public_key, 3.2, &mut rng);
+        let negated = -&ciphertext;
+        let product = ciphertext.mul_int(-1);
+        assert_eq!(product.c_0, negated.c_0 % (q, degree));
+        assert_eq!(product.c_1, negated.c_1 % (q, degree));
+    }
+
+    fn ckks_helper(lhs: Vec<f64>, rhs: Vec<f64>, c: f64, scale: f64) {
+        let (t, q, std_dev) = (1 << 14, 1 << 28, 3.2);
+        let degree = lhs.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let encoder = CkksEncoder::new(degree, t, scale);
+
+        // Each encoding rounds within `1 / (2 * scale)`, so a sum of two is off by `1 / scale`.
+        let ct_lhs = encoder.encode(&lhs).encrypt(&public_key, std_dev, &mut rng);
+        let ct_rhs = encoder.encode(&rhs).encrypt(&public_key, std_dev, &mut rng);
+        let sum = encoder.decode(&(ct_lhs.clone() + ct_rhs).decrypt(&secret_key));
+        for (i, value) in sum.iter().enumerate() {
+            assert!((value - (lhs[i] + rhs[i])).abs() <= 1.0 / scale);
+        }
+
+        // A constant scales every value, and the product is encoded at `scale^2`.
+        let product = ct_lhs.mul_plain(&encoder.encode(&[c]));
+        let product = encoder
+            .product_encoder()
+            .decode(&product.decrypt(&secret_key));
+        let tolerance = (c.abs() + lhs.iter().fold(0.0f64, |acc, x| acc.max(x.abs()))) / scale;
+        for (value, expected) in product.iter().zip(&lhs) {
+            assert!((value - c * expected).abs() <= tolerance);
+        }
+    }
+
+    #[test]
+    fn ckks_encoding() {
+        for scale in [8.0, 16.0, 32.0].iter() {
+            ckks_helper(
+                vec![0.5, -1.25, 3.1, 0.0],
+                vec![1.7, 2.0, -0.3, -1.1],
+                1.5,
+                *scale,
+            );
+            ckks_helper(
+                vec![0.1, 0.2, -0.3, 0.4, 1.0, -1.0, 2.5, 0.0],
+                vec![-0.9, 0.8, 0.7, 0.6, 1.4, 0.0, -2.2, 0.3],
+                -0.75,
+                *scale,
+            );
+        }
+    }
+
+    fn bytes_helper(msg: Vec<i64>, t: i64, q: i64, std_dev: f64) {
+        let degree = msg.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintext = Plaintext::new(msg, t);
+        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+        let bytes = ciphertext.to_bytes();
+        assert_eq!(bytes.len(), 8 * (3 + 2 * degree));
+        assert_eq!(Ciphertext::from_bytes(&bytes), Ok(ciphertext.clone()));
+        assert_eq!(
+            Ciphertext::try_from(bytes.as_slice()),
+            Ok(ciphertext.clone())
+        );
+
+        // `+` leaves the coefficients unreduced, which `to_bytes` reduces into `[0, q)`.
+        let sum = ciphertext.clone() + ciphertext;
+        let decoded = Ciphertext::from_bytes(&sum.to_bytes()).unwrap();
+        assert_eq!(decoded.decrypt(&secret_key), sum.decrypt(&secret_key));
+
+        // Every truncation, and any trailing byte, is rejected.
+        for len in 0..bytes.len() {
+            assert!(matches!(
+                Ciphertext::from_bytes(&bytes[..len]),
+                Err(BfvError::BufferLength { actual, .. }) if actual == len
+            ));
+        }
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(Ciphertext::from_bytes(&extended).is_err());
+
+        let mut bad_modulus = bytes.clone();
+        bad_modulus[16..24].copy_from_slice(&q.to_le_bytes());
+        assert_eq!(
+            Ciphertext::from_bytes(&bad_modulus),
+            Err(BfvError::InvalidModulus { q, t: q })
+        );
+
+        let mut bad_coeff = bytes.clone();
+        bad_coeff[24..32].copy_from_slice(&(-1i64).to_le_bytes());
+        assert_eq!(
+            Ciphertext::from_bytes(&bad_coeff),
+            Err(BfvError::CoefficientOutOfRange { value: -1, q })
+        );
+
+        let mut huge_degree = bytes;
+        huge_degree[0..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(Ciphertext::from_bytes(&huge_degree).is_err());
+
+        // A degree of `0` passes the length check with the bare header
+        let zero_degree = [0u64.to_le_bytes(), q.to_le_bytes(), t.to_le_bytes()].concat();
+        assert_eq!(
+            Ciphertext::from_bytes(&zero_degree),
+            Err(BfvError::InvalidDegree { degree: 0 })
+        );
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        for t in [2, 4, 8, 16, 32].iter() {
+            bytes_helper(vec![1, 0], *t, 65536, 3.2);
+            bytes_helper(vec![3, 2, 1, 0], *t, 65536, 3.2);
+            bytes_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *t, 65536, 3.2);
+        }
+    }
+
+    fn add_many_helper(msgs: Vec<Vec<i64>>, t: i64, q: i64, std_dev: f64) {
+        let degree = msgs[0].len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintexts: Vec<Plaintext> =
+            msgs.into_iter().map(|msg| Plaintext::new(msg, t)).collect();
+        let ciphertexts: Vec<Ciphertext> = plaintexts
+            .iter()
+            .map(|pt| pt.encrypt(&public_key, std_dev, &mut rng))
+            .collect();
+
+        let sum = Ciphertext::add_many(&ciphertexts);
+        assert!(sum.c_0.val().iter().all(|coeff| (0..q).contains(coeff)));
+        assert!(sum.c_1.val().iter().all(|coeff| (0..q).contains(coeff)));
+
+        let folded = ciphertexts[1..]
+            .iter()
+            .fold(ciphertexts[0].clone(), |acc, ct| acc + ct.clone());
+        assert_eq!(sum.c_0, folded.c_0 % (q, degree));
+        assert_eq!(sum.c_1, folded.c_1 % (q, degree));
+
+        let expected = plaintexts
+            .iter()
+            .fold(Poly::new(vec![0; degree]), |acc, pt| acc + pt.poly())
+            % (t, degree);
+        assert_eq!(sum.decrypt(&secret_key).poly(), expected);
+    }
+
+    #[test]
+    fn add_many() {
+        for t in [2, 4, 8, 16, 32].iter() {
+            add_many_helper(vec![vec![1, 0]], *t, 65536, 3.2);
+            add_many_helper(
+                vec![vec![3, 2, 1, 0], vec![1, 1, 3, 2], vec![0, 2, 2, 1]],
+                *t,
+                65536,
+                3.2,
+            );
+            add_many_helper(
+                (0..16)
+                    .map(|i| (0..8).map(|j| (i * j) % 32).collect())
+                    .collect(),
+                *t,
+                1 << 40,
+                3.2,
+            );
+        }
+    }
+
+    fn error_sampler_helper(msg: Vec<i64>, t: i64, q: i64, bound: i64) {
+        let degree = msg.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let sampler = UniformSampler::new(bound);
+
+        let samples = sampler.sample(degree * 64, &mut rng);
+        assert!(samples.norm_infinity() <= bound);
+        assert_eq!(samples.val().iter().any(|coeff| *coeff < 0), bound > 0);
+
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, 3.2, &mut rng);
+        let plaintext = Plaintext::new(msg, t);
+
+        let ciphertext = plaintext.encrypt_with_sampler(&public_key, &sampler, &mut rng);
+        assert_eq!(
+            ciphertext.decrypt(&secret_key).poly(),
+            plaintext.poly() % (t, degree)
+        );
+        assert!(ciphertext.noise_budget(&secret_key) > 0);
+
+        // The symmetric noise `[c_0 + c_1 * s - delta * m]_q` is exactly `-e`.
+        let ciphertext =
+            plaintext.encrypt_symmetric_with_sampler(&secret_key, q, &sampler, &mut rng);
+        assert_eq!(
+            ciphertext.decrypt(&secret_key).poly(),
+            plaintext.poly() % (t, degree)
+        );
+        let delta = q / t;
+        let noise = ciphertext.c_0.clone() + ciphertext.c_1.mul_ntt(&secret_key.poly, q)
+            - plaintext.poly() * delta;
+        assert!(noise.norm_canonical(q) <= bound);
+    }
+
+    #[test]
+    fn error_sampler() {
+        for t in [2, 4, 8, 16, 32].iter() {
+            for bound in [0, 1, 4, 16].iter() {
+                error_sampler_helper(vec![1, 0], *t, 65536, *bound);
+                error_sampler_helper(vec![3, 2, 1, 0], *t, 65536, *bound);
+                error_sampler_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *t, 65536, *bound);
+            }
+        }
+
+        // The default sampler keeps the ciphertexts of `encrypt` unchanged.
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(4, &mut rng);
+        let public_key = secret_key.public_key_gen(65536, 3.2, &mut rng);
+        let plaintext = Plaintext::new(vec![0, 1, 2, 3], 4);
+        let mut rng_1 = rand::rngs::StdRng::seed_from_u64(19);
+        let mut rng_2 = rand::rngs::StdRng::seed_from_u64(19);
+        assert_eq!(
+            plaintext.encrypt(&public_key, 3.2, &mut rng_1),
+            plaintext.encrypt_with_sampler(&public_key, &GaussianSampler::new(3.2), &mut rng_2)
+        );
+    }
+
+    // Test that the simulated noise budget is a lower bound on the measured one, and not a loose one
+    #[test]
+    fn noise_simulator() {
+        let (degree, q, t, std_dev, base) = (8, 1 << 24, 4, 3.2, 16);
+        let params = BfvParameters::new(degree, q, t, std_dev);
+        let simulator = NoiseSimulator::new(params);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let rlk_1 = secret_key
+            .relin_key_gen_1(q, std_dev, &mut rng, base)
+            .unwrap();
+
+        let pt = Plaintext::new(vec![3, 2, 1, 0, 1, 2, 3, 0], t);
+        let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+        let sum = ct.clone() + ct.clone();
+        let scaled = ct.mul_plain(&pt);
+        let product = ct.clone() * (ct.clone(), &rlk_1);
+
+        let circuits = vec![
+            (vec![], ct),
+            (vec![NoiseOp::Add], sum.clone()),
+            (vec![NoiseOp::MulPlain], scaled.clone()),
+            (
+                vec![NoiseOp::MulPlain, NoiseOp::Add],
+                scaled.clone() + scaled,
+            ),
+            (vec![NoiseOp::Add, NoiseOp::Add], sum.clone() + sum),
+            (vec![NoiseOp::Mul, NoiseOp::Relinearize { base }], product),
+        ];
+        for (ops, ct) in circuits {
+            let estimate = simulator.simulate(&ops);
+            let measured = ct.noise_budget(&secret_key);
+            assert!(estimate.decrypts());
+            assert!(estimate.budget() <= measured);
+            assert!(measured - estimate.budget() <= 10);
+        }
+
+        // Switching to a smaller modulus scales the noise down, but the rounding error eventually
+        // costs budget, and the noise keeps growing with depth.
+        let depth_1 = [NoiseOp::Mul, NoiseOp::Relinearize { base }];
+        let switched = simulator.simulate(&[&depth_1[..], &[NoiseOp::ModSwitch(1 << 20)]].concat());
+        assert!(switched.noise() < simulator.simulate(&depth_1).noise());
+        let switched = simulator.simulate(&[NoiseOp::ModSwitch(1 << 12)]);
+        assert!(switched.budget() < simulator.fresh().budget());
+        assert!(!simulator.simulate(&[depth_1, depth_1].concat()).decrypts());
+    }
+
+    #[test]
+    fn rerandomize() {
+        let (degree, q, t, std_dev) = (8, 65536, 8, 3.2);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let plaintext = Plaintext::new(vec![0, 1, 2, 3, 4, 5, 6, 7], t);
+        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+        let rerandomized_1 = ciphertext.rerandomize(&public_key, std_dev, &mut rng);
+        let rerandomized_2 = ciphertext.rerandomize(&public_key, std_dev, &mut rng);
+
+        assert_ne!(rerandomized_1.to_bytes(), ciphertext.to_bytes());
+        assert_ne!(rerandomized_1.to_bytes(), rerandomized_2.to_bytes());
+        assert_eq!(rerandomized_1.decrypt(&secret_key), plaintext);
+        assert_eq!(rerandomized_2.decrypt(&secret_key), plaintext);
+        assert!(rerandomized_1.noise_budget(&secret_key) > 0);
+
+        // Preserves the message of a ciphertext whose noise has already grown
+        let product = ciphertext.mul_plain(&Plaintext::new(vec![1, 1, 0, 0, 0, 0, 0, 0], t));
+        let rerandomized = product.rerandomize(&public_key, std_dev, &mut rng);
+        assert_eq!(
+            rerandomized.decrypt(&secret_key),
+            product.decrypt(&secret_key)
+        );
+    }
+
+    #[test]
+    fn public_key_from_parts() {
+        let (degree, q, t, std_dev) = (8, 65536, 8, 3.2);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let p_0 = public_key.p0().clone();
+        let p_1 = public_key.p1().clone();
+        let rebuilt = PublicKey::from_parts(p_0, p_1, public_key.q());
+        assert_eq!(rebuilt.p0(), public_key.p0());
+        assert_eq!(rebuilt.p1(), public_key.p1());
+        assert_eq!(rebuilt.q(), q);
+
+        let plaintext = Plaintext::new(vec![0, 1, 2, 3, 4, 5, 6, 7], t);
+        let ciphertext = plaintext.encrypt(&rebuilt, std_dev, &mut rng);
+        assert_eq!(ciphertext.decrypt(&secret_key), plaintext);
+    }
+
+    #[test]
+    #[should_panic]
+    fn public_key_from_parts_degree_mismatch() {
+        PublicKey::from_parts(Poly::new(vec![0; 8]), Poly::new(vec![0; 4]), 65536);
+    }
+
+    // Test that `*` on sized ciphertexts relinearizes with an attached key, and fails without one
+    #[test]
+    fn sized_ciphertext_mul() {
+        let (degree, q, t, std_dev, base) = (8, 1 << 24, 4, 3.2, 16);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let rlk_1 = secret_key
+            .relin_key_gen_1(q, std_dev, &mut rng, base)
+            .unwrap();
+
+        let pt_1 = Plaintext::new(vec![1, 1, 0, 0, 0, 0, 0, 0], t);
+        let pt_2 = Plaintext::new(vec![0, 1, 2, 3, 0, 1, 2, 3], t);
+        let ct_1 = SizedCiphertext::new(pt_1.encrypt(&public_key, std_dev, &mut rng));
+        let ct_2 = SizedCiphertext::new(pt_2.encrypt(&public_key, std_dev, &mut rng));
+        let expected = (pt_1.poly() * pt_2.poly()) % (t, degree);
+        let expected_cube = (pt_1.poly() * pt_1.poly() * pt_2.poly()) % (t, degree);
+
+        // Without a key, the product keeps 3 polynomials and still decrypts
+        let product = (ct_1.clone() * ct_2.clone()).unwrap();
+        assert_eq!(product.size(), 3);
+        assert_eq!(product.decrypt(&secret_key).poly(), expected);
+
+        // but it can neither be multiplied nor relinearized
+        let missing_key = BfvError::MissingRelinearizationKey { size: 3 };
+        assert_eq!(
+            (product.clone() * product.clone()).unwrap_err(),
+            missing_key
+        );
+        assert_eq!((ct_1.clone() * product.clone()).unwrap_err(), missing_key);
+        assert_eq!(product.clone().relinearize().unwrap_err(), missing_key);
+
+        // Attaching a key later relinearizes it as for `Ciphertext * (Ciphertext, &rlk)`
+        let relinearized = product.with_relin_key(&rlk_1).relinearize().unwrap();
+        let reference =
+            ct_1.clone().relinearize().unwrap() * (ct_2.clone().relinearize().unwrap(), &rlk_1);
+        assert_eq!(relinearized, reference);
+
+        // A key on either operand relinearizes the product, and stays attached to it
+        let ct_1 = ct_1.with_relin_key(&rlk_1);
+        let product = (ct_2.clone() * ct_1.clone()).unwrap();
+        assert_eq!(product.size(), 2);
+        assert_eq!(product.decrypt(&secret_key).poly(), expected);
+        let cube = (product * ct_1).unwrap();
+        assert_eq!(cube.size(), 2);
+        assert_eq!(cube.decrypt(&secret_key).poly(), expected_cube);
+
+        // A size 3 operand is relinearized with the key of the other one
+        let product = (ct_2.clone() * ct_2.clone()).unwrap();
+        let key_holder = ct_2.with_relin_key(&rlk_1);
+        let product = (product * key_holder).unwrap();
+        assert_eq!(product.size(), 2);
+        assert_eq!(
+            product.decrypt(&secret_key).poly(),
+            (pt_2.poly() * pt_2.poly() * pt_2.poly()) % (t, degree)
+        );
+    }
+
+    // Evaluate `m` at the primitive `2N`-th roots of unity `zeta^k` modulo `t`, for odd `k`
+    fn slots(m: &Poly, zeta: i64, t: i64) -> Vec<i64> {
+        let degree = m.degree();
+        (1..2 * degree)
+            .step_by(2)
+            .map(|k| {
+                let x = (0..k).fold(1, |x, _| x * zeta % t);
+                m.val().iter().rev().fold(0, |acc, c| (acc * x + c) % t)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn slot_sum() {
+        // `t = 17 = 1 mod 16` and `3` has order 16 modulo 17
+        let (degree, q, t, std_dev, zeta) = (8, 1 << 24, 17, 3.2, 3);
+        let params = BfvParameters::new(degree, q, t, std_dev);
+        let mut keygen = KeyGenerator::new(params, rand::rngs::StdRng::seed_from_u64(18));
+        let public_key = keygen.public_key();
+        let galois_keys = keygen.slot_sum_keys().unwrap();
+        assert_eq!(galois_keys.steps().collect::<Vec<_>>(), vec![1, 2]);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+        let plaintext = Plaintext::new(vec![3, 16, 0, 5, 9, 1, 12, 7], t);
+        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+        let sum = ciphertext.slot_sum(&galois_keys).unwrap();
+
+        let expected = slots(&plaintext.poly(), zeta, t).iter().sum::<i64>() % t;
+        let decrypted = sum.decrypt(keygen.secret_key());
+        assert_eq!(slots(&decrypted.poly(), zeta, t), vec![expected; degree]);
+
+        // Every rotation step and the conjugation are needed
+        let missing_step = keygen.galois_keys(&[1]).unwrap();
+        let conjugation = galois_keys.conjugation().unwrap().clone();
+        assert_eq!(
+            ciphertext
+                .slot_sum(&missing_step.with_conjugation(conjugation))
+                .unwrap_err(),
+            BfvError::MissingGaloisKey { k: 9 }
+        );
+        assert_eq!(
+            ciphertext
+                .slot_sum(&keygen.galois_keys(&[1, 2]).unwrap())
+                .unwrap_err(),
+            BfvError::MissingGaloisKey { k: 15 }
+        );
+    }
+
+    #[test]
+    fn encrypt_with_noise() {
+        let (degree, q, t, std_dev) = (8, 65536, 8, 3.2);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let plaintext = Plaintext::new(vec![0, 1, 2, 3, 4, 5, 6, 7], t);
+
+        // Same ciphertext as `encrypt` for the same randomness
+        let mut rng_1 = rand::rngs::StdRng::seed_from_u64(19);
+        let mut rng_2 = rand::rngs::StdRng::seed_from_u64(19);
+        let (ciphertext, noise) = plaintext.encrypt_with_noise(&public_key, std_dev, &mut rng_1);
+        assert_eq!(
+            ciphertext,
+            plaintext.encrypt(&public_key, std_dev, &mut rng_2)
+        );
+
+        // The error is the difference between the raw decryption and `delta * m`
+        let delta = q / t;
+        let raw = ciphertext.c_0.clone() + ciphertext.c_1.mul_ntt(&secret_key.poly, q);
+        let diff = (raw - plaintext.poly() * delta) % (q, degree);
+        let error = noise.error(&secret_key, &public_key);
+        assert_eq!(error.clone() % (q, degree), diff);
+        assert!(error.norm_infinity() <= q / 2);
+
+        // and sets the noise budget
+        let budget = ((delta as f64 / 2.0) / error.norm_infinity() as f64)
+            .log2()
+            .floor() as i64;
+        assert_eq!(ciphertext.noise_budget(&secret_key), budget);
+        assert!(noise.u().norm_infinity() <= 1);
+        assert!(noise.e_1().norm_infinity() > 0 && noise.e_2().norm_infinity() > 0);
+    }
+
+    #[test]
+    fn prime_cyclotomic_encrypt_decrypt() {
+        let (q, t, std_dev) = (65536, 7, 3.2);
+        for p in [5, 7, 11, 13] {
+            let params = BfvParameters::new_prime_cyclotomic(p, q, t, std_dev);
+            let rng = rand::rngs::StdRng::seed_from_u64(p as u64);
+            let mut keygen = KeyGenerator::new(params, rng);
+            let secret_key = keygen.secret_key().clone();
+            let public_key = keygen.public_key();
+            assert_eq!(secret_key.ring, Cyclotomic::Prime(p));
+            assert_eq!(public_key.ring, Cyclotomic::Prime(p));
+
+            let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+            let pt_1 = Plaintext::rand(p - 1, t, &mut rng);
+            let pt_2 = Plaintext::rand(p - 1, t, &mut rng);
+
+            let ct_1 = pt_1.encrypt(&public_key, std_dev, &mut rng);
+            let ct_2 = pt_2.encrypt_symmetric(&secret_key, q, std_dev, &mut rng);
+            assert_eq!(ct_1.decrypt(&secret_key), pt_1);
+            assert_eq!(ct_2.decrypt(&secret_key), pt_2);
+            assert!(ct_1.noise_budget(&secret_key) > 0);
+
+            let expected = (pt_1.poly() + pt_2.poly()) % (t, Cyclotomic::Prime(p));
+            assert_eq!((ct_1 + ct_2).decrypt(&secret_key).poly(), expected);
+        }
+    }
+
+    #[test]
+    fn prime_cyclotomic_mul_and_rotate_unsupported() {
+        let (p, q, t, std_dev) = (7, 65536, 7, 3.2);
+        let params = BfvParameters::new_prime_cyclotomic(p, q, t, std_dev);
+        let rng = rand::rngs::StdRng::seed_from_u64(18);
+        let mut keygen = KeyGenerator::new(params, rng);
+        let expected = BfvError::UnsupportedRing {
+            ring: Cyclotomic::Prime(p),
+        };
+
+        // Multiplying needs a relinearization key, which the prime ring does not get
+        assert_eq!(keygen.relinearization_key(16).unwrap_err(), expected);
+        assert_eq!(
+            keygen.relinearization_key_chain(16, 3).unwrap_err(),
+            expected
+        );
+        assert_eq!(keygen.galois_keys(&[1]).unwrap_err(), expected);
+        assert_eq!(keygen.slot_sum_keys().unwrap_err(), expected);
+        assert_eq!(keygen.key_set(16, &[]).unwrap_err(), expected);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+        let secret_key = keygen.secret_key();
+        assert_eq!(
+            secret_key
+                .relin_key_gen_2(q, std_dev, &mut rng, 1 << 13)
+                .unwrap_err(),
+            expected
+        );
+        assert_eq!(
+            secret_key
+                .conjugation_key_gen(q, std_dev, &mut rng, 16)
+                .unwrap_err(),
+            expected
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn prime_cyclotomic_rejects_composite() {
+        BfvParameters::new_prime_cyclotomic(9, 65536, 7, 3.2);
+    }
+
+    #[test]
+    fn key_switch() {
+        let (degree, q, t, std_dev) = (8, 1 << 24, 16, 3.2);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(29);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let new_secret_key = SecretKey::generate(degree, &mut rng);
+
+        for base in [4, 16, 256] {
+            let ksk =
+                KeySwitchKey::generate(&secret_key, &new_secret_key, base, q, std_dev, &mut rng);
+            let pt = Plaintext::rand(degree, t, &mut rng);
+            let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+
+            let switched = ct.key_switch(&ksk);
+            assert_eq!(switched.decrypt(&new_secret_key), pt);
+            assert!(switched.noise_budget(&new_secret_key) > 0);
+            assert_ne!(switched.decrypt(&secret_key), pt);
+
+            // Switching back recovers a ciphertext under the original key.
+            let back =
+                KeySwitchKey::generate(&new_secret_key, &secret_key, base, q, std_dev, &mut rng);
+            assert_eq!(switched.key_switch(&back).decrypt(&secret_key), pt);
+        }
+    }
+
+    #[test]
+    fn ciphertext_vec() {
+        let (degree, q, t, std_dev) = (8, 1 << 20, 16, 3.2);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(31);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let pts_1 = (0..5)
+            .map(|_| Plaintext::rand(degree, t, &mut rng))
+            .collect::<Vec<_>>();
+        let pts_2 = (0..5)
+            .map(|_| Plaintext::rand(degree, t, &mut rng))
+            .collect::<Vec<_>>();
+        let cts_1 = CiphertextVec::encrypt(&pts_1, &public_key, std_dev, &mut rng);
+        let cts_2 = pts_2
+            .iter()
+            .map(|pt| pt.encrypt(&public_key, std_dev, &mut rng))
+            .collect::<Result<CiphertextVec, BfvError>>()
+            .unwrap();
+        assert_eq!(cts_1.decrypt_all(&secret_key), pts_1);
+
+        let expected = |op: fn(Poly, Poly) -> Poly| {
+            pts_1
+                .iter()
+                .zip(&pts_2)
+                .map(|(pt_1, pt_2)| op(pt_1.poly(), pt_2.poly()) % (t, degree))
+                .collect::<Vec<_>>()
+        };
+        let polys = |cts: CiphertextVec| {
+            cts.decrypt_all(&secret_key)
+                .iter()
+                .map(Plaintext::poly)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(polys((&cts_1 + &cts_2).unwrap()), expected(|a, b| a + b));
+        assert_eq!(polys((&cts_1 - &cts_2).unwrap()), expected(|a, b| a - b));
+        assert_eq!(
+            polys(cts_1.mul_plain(&pts_2).unwrap()),
+            expected(|a, b| a * b)
+        );
+
+        // Operands of different lengths or parameters are rejected.
+        let short = CiphertextVec::new(cts_2.as_slice()[..4].to_vec()).unwrap();
+        assert_eq!(
+            (&cts_1 + &short).unwrap_err(),
+            BfvError::LengthMismatch {
+                expected: 5,
+                actual: 4
+            }
+        );
+        assert_eq!(
+            cts_1.mul_plain(&pts_2[..4]).unwrap_err(),
+            BfvError::LengthMismatch {
+                expected: 5,
+                actual: 4
+            }
+        );
+        let other_key =
+            SecretKey::generate(degree, &mut rng).public_key_gen(1 << 21, std_dev, &mut rng);
+        let mut mixed = cts_1.clone().into_vec();
+        mixed.push(pts_1[0].encrypt(&other_key, std_dev, &mut rng));
+        assert_eq!(
+            CiphertextVec::new(mixed).unwrap_err(),
+            BfvError::ParameterMismatch { index: 5 }
+        );
+        let other = CiphertextVec::encrypt(&pts_2, &other_key, std_dev, &mut rng);
+        assert_eq!(
+            (&cts_1 - &other).unwrap_err(),
+            BfvError::ParameterMismatch { index: 0 }
+        );
+    }
+
+    #[test]
+    fn try_mul_noise_overflow() {
+        let (degree, q, t, std_dev, base) = (8, 65536, 16, 3.2, 16);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(37);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let rlk = secret_key
+            .relin_key_gen_1(q, std_dev, &mut rng, base)
+            .unwrap();
+
+        let pt = Plaintext::rand(degree, t, &mut rng);
+        let mut ct = pt.encrypt(&public_key, std_dev, &mut rng);
+        let mut expected = pt.poly();
+        // Square until the noise overflows: every product returned is correct, and the one that
+        // would not be is reported instead.
+        for _ in 0..4 {
+            match ct.clone().try_mul(ct.clone(), &rlk, Some(&secret_key)) {
+                Ok(product) => {
+                    expected = (expected.clone() * expected) % (t, degree);
+                    assert_eq!(product.decrypt(&secret_key).poly(), expected);
+                    ct = product;
+                }
+                Err(BfvError::NoiseBudgetExhausted { budget }) => {
+                    let unchecked = ct.clone() * (ct.clone(), &rlk);
+                    assert_eq!(unchecked.noise_budget(&secret_key), budget);
+                    assert!(budget <= 0);
+                    // Without a key, the same product is returned unchecked.
+                    assert!(ct.clone().try_mul(ct, &rlk, None).is_ok());
+                    return;
+                }
+                Err(err) => panic!("unexpected error {err}"),
+            }
+        }
+        panic!("the noise never overflowed");
+    }
+
+    #[test]
+    fn relinearize_with_chain() {
+        let (degree, q, t, std_dev, base) = (8, 1 << 24, 4, 3.2, 16);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(41);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let chain = secret_key
+            .relin_key_chain_gen(q, std_dev, &mut rng, base, 3)
+            .unwrap();
+        assert_eq!(chain.max_degree(), 3);
+
+        let pts = [
+            Plaintext::new(vec![1, 1, 0, 0, 0, 0, 0, 0], t),
+            Plaintext::new(vec![0, 1, 2, 3, 0, 1, 2, 3], t),
+            Plaintext::new(vec![3, 0, 0, 1, 0, 0, 0, 2], t),
+        ];
+        let [ct_1, ct_2, ct_3] =
+            [0, 1, 2].map(|i| SizedCiphertext::new(pts[i].encrypt(&public_key, std_dev, &mut rng)));
+        let expected = (pts[0].poly() * pts[1].poly() * pts[2].poly()) % (t, degree);
+
+        // Multiplying three without relinearizing gives a ciphertext of 4 polynomials
+        let product = ct_1.tensor(&ct_2).tensor(&ct_3);
+        assert_eq!(product.size(), 4);
+        assert_eq!(product.decrypt(&secret_key).poly(), expected);
+        assert_eq!(
+            product.clone().relinearize().unwrap_err(),
+            BfvError::MissingRelinearizationKey { size: 4 }
+        );
+
+        let relinearized = product.clone().relinearize_with_chain(&chain).unwrap();
+        assert_eq!(relinearized.decrypt(&secret_key).poly(), expected);
+        assert!(relinearized.noise_budget(&secret_key) > 0);
+
+        // The first key of the chain relinearizes products of two like a `RelinearizationKey1`
+        let square = ct_1.tensor(&ct_2).relinearize_with_chain(&chain).unwrap();
+        assert_eq!(
+            square.decrypt(&secret_key).poly(),
+            (pts[0].poly() * pts[1].poly()) % (t, degree)
+        );
+
+        // A chain up to `s^2` is too short for 4 polynomials
+        let short = secret_key
+            .relin_key_chain_gen(q, std_dev, &mut rng, base, 2)
+            .unwrap();
+        assert_eq!(
+            product.relinearize_with_chain(&short).unwrap_err(),
+            BfvError::MissingRelinearizationKey { size: 4 }
+        );
+    }
+
+    #[test]
+    fn ntt_form_ciphertext() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(40);
+        let (degree, q, t, std_dev) = (8, 12289, 4, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        let pt = Plaintext::new(vec![1, 2, 3, 0, 1, 0, 0, 2], t);
+        let factors = [
+            [0, 1, 0, 0, 0, 0, 0, 0],
+            [1, 0, 0, 0, 0, 0, 0, 3],
+            [0, 0, 2, 0, 0, 0, 0, 0],
+        ]
+        .map(|val| Plaintext::new(val.to_vec(), t));
+        let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+
+        // Plaintext multiplications in NTT form match the ones in coefficient form.
+        let mut expected = ct.clone();
+        let mut ct_ntt = ct.to_ntt().unwrap();
+        for factor in factors.iter() {
+            expected = expected.mul_plain(factor);
+            ct_ntt = ct_ntt.mul_plain(factor);
+        }
+        assert_eq!(Ciphertext::from_ntt(&ct_ntt), expected);
+        assert_eq!(ct_ntt.decrypt(&secret_key), expected.decrypt(&secret_key));
+
+        let sum = &ct_ntt + &ct.to_ntt().unwrap();
+        assert_eq!(
+            sum.decrypt(&secret_key),
+            (&expected + &ct).decrypt(&secret_key)
+        );
+        let diff = &ct_ntt - &ct.to_ntt().unwrap();
+        assert_eq!(
+            diff.decrypt(&secret_key),
+            (&expected - &ct).decrypt(&secret_key)
+        );
+
+        // 65536 has no NTT, so the ciphertext stays in coefficient form.
+        let public_key = secret_key.public_key_gen(65536, std_dev, &mut rng);
+        assert!(pt
+            .encrypt(&public_key, std_dev, &mut rng)
+            .to_ntt()
+            .is_none());
+    }
+
+    #[test]
+    fn assert_decrypts_to_reduces_expected() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(41);
+        let (degree, q, t, std_dev) = (4, 65536, 8, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let ct = Plaintext::new(vec![7, 1, 0, 3], t).encrypt(&public_key, std_dev, &mut rng);
+
+        assert_decrypts_to(&ct, &secret_key, &[7, 1, 0, 3], t);
+        // Negative and out-of-range coefficients are reduced modulo t.
+        assert_decrypts_to(&ct, &secret_key, &[-1, 9, 8, -5], t);
+        // Missing coefficients are zero and extra ones wrap around X^4 = -1.
+        assert_decrypts_to(&ct, &secret_key, &[7, 1, 0, 3, 0, 0, 0, 0, 8], t);
+        assert_decrypts_to(&ct, &secret_key, &[0, 1, 0, 3, 1], t);
+    }
+
+    #[test]
+    #[should_panic(expected = "decrypts to [-1, 1, 0, 3], expected [-1, 1, 0, 2]")]
+    fn assert_decrypts_to_rejects_wrong_message() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(41);
+        let (degree, q, t, std_dev) = (4, 65536, 8, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let ct = Plaintext::new(vec![7, 1, 0, 3], t).encrypt(&public_key, std_dev, &mut rng);
+
+        assert_decrypts_to(&ct, &secret_key, &[7, 1, 0, 2], t);
+    }
+
+    // Near `q = 2^31`, the products of two coefficients exceed `2^62`, so the raw tensor of
+    // `basic_mul` and the relinearization products only fit in `i128`.
+    #[test]
+    fn mul_near_2_31() {
+        let msg_1 = vec![3, 15, 0, 7, 1, 9, 12, 2];
+        let msg_2 = vec![11, 4, 8, 0, 13, 5, 1, 6];
+        // A prime with an NTT of degree 8, and one without.
+        for q in [2013265921, (1 << 31) - 1] {
+            relin_1_mul_helper(msg_1.clone(), msg_2.clone(), 16, q, 3.2, 1 << 8);
+            relin_2_mul_helper(msg_1.clone(), msg_2.clone(), 16, q, 3.2, 1 << 31);
+        }
+    }
+
+    #[test]
+    fn extract_lwe() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let (q, t, std_dev) = (1 << 24, 16, 3.2);
+        for degree in [1, 4, 16] {
+            let secret_key = SecretKey::generate(degree, &mut rng);
+            let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+            let pt = Plaintext::rand(degree, t, &mut rng);
+            let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+
+            for index in 0..degree {
+                let lwe = ct.extract_lwe(index);
+                assert_eq!(lwe.a().len(), degree);
+                assert_eq!(lwe.decrypt(&secret_key), pt.poly().val()[index]);
+            }
+        }
+
+        // After a rotation by X, the upper coefficient wraps around to index 0 negated.
+        let degree = 4;
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let ct = Plaintext::new(vec![1, 2, 3, 4], t).encrypt(&public_key, std_dev, &mut rng);
+        let shifted = ct.mul_plain(&Plaintext::new(vec![0, 1, 0, 0], t));
+        let coeffs = (0..degree)
+            .map(|index| shifted.extract_lwe(index).decrypt(&secret_key))
+            .collect::<Vec<_>>();
+        assert_eq!(coeffs, vec![12, 1, 2, 3]);
+    }
+
+    #[test]
+    fn blind_rotate_refreshes() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(43);
+        let (degree, q, big_q, t, std_dev) = (64, 1 << 24, 2013265921, 8, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let bsk =
+            BootstrappingKey::generate(&secret_key, &secret_key, 1 << 3, big_q, std_dev, &mut rng);
+
+        // Encrypt with a large error, so that the input has almost no noise budget left.
+        let noisy_key = secret_key.public_key_gen(q, 8192.0, &mut rng);
+        for m in 0..t / 2 {
+            let pt = Plaintext::new([m, 1, 2, 3].repeat(degree / 4), t);
+            let ct = pt.encrypt(&noisy_key, 8192.0, &mut rng);
+            assert_eq!(ct.decrypt(&secret_key), pt);
+            let budget = ct.noise_budget(&secret_key);
+            assert!(budget <= 3);
+
+            let identity = blind_rotate(&ct.extract_lwe(0), &bsk, |m| m);
+            assert_eq!(identity.decrypt(&secret_key).poly().val()[0], m);
+            assert!(identity.noise_budget(&secret_key) > budget + 4);
+
+            let programmed = blind_rotate(&ct.extract_lwe(0), &bsk, |m| 3 * m + 1);
+            assert_eq!(
+                programmed.decrypt(&secret_key).poly().val()[0],
+                (3 * m + 1) % t
+            );
+        }
+    }
+
+    #[test]
+    fn trivial_matches_plaintext_addition() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(44);
+        let (degree, q, std_dev) = (8, 1 << 20, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        for t in [2, 7, 16] {
+            let pt = Plaintext::rand(degree, t, &mut rng);
+            let ct = pt.encrypt(&public_key, std_dev, &mut rng);
+            // Coefficients outside `[0, t)` are reduced like the message of `Plaintext::encrypt`
+            let constant = Plaintext::new(vec![1, -1, t, 2 * t + 1, 0, 0, 0, 3], t);
+            let trivial = Ciphertext::trivial(&constant, q);
+            assert!(trivial.is_transparent());
+            assert_eq!(
+                trivial.decrypt(&secret_key).poly(),
+                constant.poly() % (t, degree)
+            );
+
+            let expected = (pt.poly() + constant.poly()) % (t, degree);
+            assert_eq!((&ct + &trivial).decrypt(&secret_key).poly(), expected);
+            assert_eq!((&trivial + &ct).decrypt(&secret_key).poly(), expected);
+            let expected = (pt.poly() - constant.poly()) % (t, degree);
+            assert_eq!((&ct - &trivial).decrypt(&secret_key).poly(), expected);
+            // A trivial ciphertext adds no noise, only the rounding of `delta` on wrap-around
+            assert!((&ct + &trivial).noise_budget(&secret_key) >= ct.noise_budget(&secret_key) - 1);
+        }
+    }
+
+    #[test]
+    fn sum_matches_add_many() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(45);
+        let (degree, q, t, std_dev) = (8, 65536, 16, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+
+        for n in [1, 2, 5] {
+            let cts = (0..n)
+                .map(|_| {
+                    Plaintext::rand(degree, t, &mut rng).encrypt(&public_key, std_dev, &mut rng)
+                })
+                .collect::<Vec<_>>();
+            let expected = Ciphertext::add_many(&cts);
+            assert_eq!(cts.iter().sum::<Ciphertext>(), expected);
+            assert_eq!(cts.clone().into_iter().sum::<Ciphertext>(), expected);
+            let filtered: Ciphertext = cts.iter().step_by(2).sum();
+            let expected =
+                Ciphertext::add_many(&cts.iter().step_by(2).cloned().collect::<Vec<_>>());
+            assert_eq!(filtered, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "no ciphertexts to add")]
+    fn sum_of_nothing_panics() {
+        let _: Ciphertext = Vec::<Ciphertext>::new().into_iter().sum();
+    }
+
+    #[test]
+    fn product_with_matches_sequential_mul() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let (degree, q, t, std_dev) = (4, 1 << 40, 8, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let rlk_1 = secret_key
+            .relin_key_gen_1(q, std_dev, &mut rng, 1 << 10)
+            .unwrap();
+
+        for n in 1..=5 {
+            let pts = (0..n)
+                .map(|_| Plaintext::rand(degree, t, &mut rng))
+                .collect::<Vec<_>>();
+            let cts = pts
+                .iter()
+                .map(|pt| pt.encrypt(&public_key, std_dev, &mut rng))
+                .collect::<Vec<_>>();
+            let expected = pts[1..]
+                .iter()
+                .fold(pts[0].poly(), |acc, pt| (acc * pt.poly()) % (t, degree));
+            let sequential = cts[1..]
+                .iter()
+                .fold(cts[0].clone(), |acc, ct| acc * (ct.clone(), &rlk_1));
+            let product = product_with(cts, &rlk_1);
+            assert_eq!(product.decrypt(&secret_key).poly(), expected);
+            assert_eq!(
+                product.decrypt(&secret_key),
+                sequential.decrypt(&secret_key)
+            );
+            // The tree has a depth of 3 for 5 ciphertexts, where the chain has 4
+            assert!(product.noise_budget(&secret_key) >= sequential.noise_budget(&secret_key));
+        }
+    }
+
+    #[test]
+    fn ciphertext_eq_canonicalizes() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(47);
+        let (degree, q, t, std_dev) = (8, 65536, 16, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let ct = Plaintext::rand(degree, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+
+        // Every representative of the coefficients modulo `q` is the same ciphertext
+        let shifted = Ciphertext {
+            c_0: Poly::new(ct.c_0.val().iter().map(|c| c - q).collect()),
+            c_1: Poly::new(ct.c_1.val().iter().map(|c| c + 3 * q).collect()),
+            ..ct.clone()
+        };
+        assert_ne!(shifted.c_0(), ct.c_0());
+        assert_eq!(shifted, ct);
+        let mut canonical = shifted.clone();
+        canonical.canonicalize();
+        assert_eq!(canonical, shifted);
+        assert_eq!(
+            &ct - &ct,
+            Ciphertext::trivial(&Plaintext::new(vec![0; degree], t), q)
+        );
+
+        // The moduli, the degree and every coefficient matter
+        assert_ne!(
+            Ciphertext {
+                q: 2 * q,
+                ..ct.clone()
+            },
+            ct
+        );
+        assert_ne!(
+            Ciphertext {
+                t: 2 * t,
+                ..ct.clone()
+            },
+            ct
+        );
+        let mut truncated = ct.clone();
+        truncated.c_1 = Poly::new(ct.c_1.val()[..degree - 1].to_vec());
+        assert_ne!(truncated, ct);
+        let mut perturbed = ct.clone();
+        perturbed.c_1 = Poly::new(ct.c_1.val().iter().map(|c| c + 1).collect());
+        assert_ne!(perturbed, ct);
+    }
+
+    #[test]
+    fn plaintext_eq_reduces_mod_t() {
+        let t = 8;
+        assert_eq!(
+            Plaintext::new(vec![-1, 9, -16, 3], t),
+            Plaintext::new(vec![7, 1, 0, 3], t)
+        );
+        assert_ne!(
+            Plaintext::new(vec![7, 1, 0, 3], t),
+            Plaintext::new(vec![7, 1, 0, 4], t)
+        );
+        assert_ne!(
+            Plaintext::new(vec![1, 2], t),
+            Plaintext::new(vec![1, 2], 2 * t)
+        );
+        assert_ne!(
+            Plaintext::new(vec![1, 2], t),
+            Plaintext::new(vec![1, 2, 0], t)
+        );
+    }
+
+    #[test]
+    fn mod_switch_down_chain() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(48);
+        let (degree, t, std_dev, base) = (8, 16, 3.2, 1 << 10);
+        let chain = ModulusChain::new(vec![1 << 50, (1 << 40) + 7, 1 << 30]).unwrap();
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(chain.moduli()[0], std_dev, &mut rng);
+        let rlks = chain
+            .moduli()
+            .iter()
+            .map(|q| {
+                secret_key
+                    .relin_key_gen_1(*q, std_dev, &mut rng, base)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let pt = Plaintext::new(vec![1, 2, 0, 0, 0, 0, 0, 1], t);
+        let x = pt.encrypt(&public_key, std_dev, &mut rng);
+        let mut expected = pt.poly();
+        let mut ct = x.clone();
+        let mut x_i = x;
+        for (level, rlk) in rlks.iter().enumerate() {
+            assert_eq!(ct.q(), chain.moduli()[level]);
+            assert_eq!(chain.level(ct.q()), Some(level));
+            assert_eq!(ct.decrypt(&secret_key).poly(), expected);
+
+            // Multiply by `x` at the current level, then switch both down
+            ct = ct * (x_i.clone(), rlk);
+            expected = (expected * pt.poly()) % (t, degree);
+            assert_eq!(ct.decrypt(&secret_key).poly(), expected);
+            let budget = ct.noise_budget(&secret_key);
+            match ct.mod_switch_next(&chain) {
+                Ok(switched) => {
+                    assert!(switched.noise_budget(&secret_key) >= budget - 1);
+                    ct = switched;
+                    x_i = x_i.mod_switch_next(&chain).unwrap();
+                }
+                Err(err) => {
+                    assert_eq!(level, chain.moduli().len() - 1);
+                    assert_eq!(err, BfvError::BottomOfModulusChain { q: 1 << 30 });
+                }
+            }
+        }
+
+        let outside = Plaintext::new(vec![0; degree], t).encrypt(
+            &secret_key.public_key_gen(1 << 35, std_dev, &mut rng),
+            std_dev,
+            &mut rng,
+        );
+        assert_eq!(
+            outside.mod_switch_next(&chain).unwrap_err(),
+            BfvError::ModulusNotInChain { q: 1 << 35 }
+        );
+
+        // The chain does not know `t`, so a level at or below it is only caught when switching
+        let short = ModulusChain::new(vec![1 << 50, t]).unwrap();
+        assert_eq!(
+            pt.encrypt(&public_key, std_dev, &mut rng)
+                .mod_switch_next(&short)
+                .unwrap_err(),
+            BfvError::InvalidModulus { q: t, t }
+        );
+        for (moduli, index) in [
+            (vec![], 0),
+            (vec![1], 0),
+            (vec![1 << 30, 1 << 30], 1),
+            (vec![1 << 30, 1 << 20, 1 << 25], 2),
+        ] {
+            assert_eq!(
+                ModulusChain::new(moduli),
+                Err(BfvError::InvalidModulusChain { index })
+            );
+        }
+    }
+
+    // Test that `+`, `-`, `+=` and `*` switch the operand at the lower level down to the other,
+    // and that operands which are not in one modulus chain are rejected
+    #[test]
+    fn mixed_level_operands() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(49);
+        let (degree, t, std_dev, base) = (8, 16, 3.2, 1 << 10);
+        let chain = ModulusChain::new(vec![1 << 50, 1 << 40, 1 << 30]).unwrap();
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(chain.moduli()[0], std_dev, &mut rng);
+        let rlk = secret_key
+            .relin_key_gen_1(1 << 30, std_dev, &mut rng, base)
+            .unwrap();
+
+        let pt_x = Plaintext::new(vec![1, 2, 0, 3, 0, 0, 0, 1], t);
+        let pt_y = Plaintext::new(vec![2, 0, 1, 0, 0, 0, 5, 0], t);
+        let x = pt_x.encrypt(&public_key, std_dev, &mut rng);
+        let y = pt_y.encrypt(&public_key, std_dev, &mut rng);
+        let y = y
+            .mod_switch_next(&chain)
+            .and_then(|y| y.mod_switch_next(&chain))
+            .unwrap();
+        assert_eq!((x.level(), y.level()), (0, 2));
+
+        let sum = (pt_x.poly() + pt_y.poly()) % (t, degree);
+        let difference = (pt_y.poly() - pt_x.poly()) % (t, degree);
+        let product = (pt_x.poly() * pt_y.poly()) % (t, degree);
+        let mut acc = x.clone();
+        acc += &y;
+        for (ct, expected) in [
+            (&x + &y, &sum),
+            (&y - &x, &difference),
+            (acc, &sum),
+            (x.try_add(&y).unwrap(), &sum),
+            (x.clone() * (y.clone(), &rlk), &product),
+            (x.clone().try_mul(y.clone(), &rlk, None).unwrap(), &product),
+        ] {
+            assert_eq!((ct.q(), ct.level()), (1 << 30, 2));
+            assert_eq!(ct.decrypt(&secret_key).poly(), *expected);
+        }
+
+        // A fresh ciphertext under another modulus shares the level but not the modulus of `x`,
+        // and one switched to `2^45` is at a higher level than `x` under `2^40`
+        let other = Plaintext::new(vec![0; degree], t).encrypt(
+            &secret_key.public_key_gen(1 << 40, std_dev, &mut rng),
+            std_dev,
+            &mut rng,
+        );
+        let higher = x.mod_switch(1 << 45);
+        for (lhs, rhs) in [(&x, &other), (&higher, &other)] {
+            let err = BfvError::ModulusChainMismatch {
+                q: lhs.q(),
+                level: lhs.level(),
+                other_q: 1 << 40,
+                other_level: 0,
+            };
+            assert_eq!(lhs.try_add(rhs).unwrap_err(), err);
+            assert_eq!(lhs.try_sub(rhs).unwrap_err(), err);
+            assert_eq!(
+                lhs.clone().try_mul(rhs.clone(), &rlk, None).unwrap_err(),
+                err
+            );
+            assert!(std::panic::catch_unwind(|| lhs + rhs).is_err());
+        }
+        let other_t =
+            Plaintext::new(vec![0; degree], 2 * t).encrypt(&public_key, std_dev, &mut rng);
+        assert_eq!(
+            x.try_add(&other_t).unwrap_err(),
+            BfvError::ModulusMismatch {
+                expected: t,
+                actual: 2 * t
+            }
+        );
+    }
+
+    #[test]
+    fn automorphism_without_key_switching() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let (degree, q, t, std_dev) = (8, 1 << 30, 16, 3.2);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let plaintext = Plaintext::new(vec![1, 2, 3, 4, 5, 6, 7, 8], t);
+        let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+
+        assert_eq!(ciphertext.automorphism(1), ciphertext);
+
+        // `X -> X^3` sends `X^i` to `X^{3i mod 16}`, negated past `X^7`
+        let k = 3;
+        let permuted = ciphertext.automorphism(k);
+        let targets = [0, 3, 6, 9, 12, 15, 2, 5];
+        for (i, target) in targets.iter().enumerate() {
+            let (j, sign) = if *target >= degree {
+                (target - degree, -1)
+            } else {
+                (*target, 1)
+            };
+            assert_eq!(
+                permuted.c_0.val()[j],
+                (sign * ciphertext.c_0.val()[i]).rem_euclid(q)
+            );
+            assert_eq!(
+                permuted.c_1.val()[j],
+                (sign * ciphertext.c_1.val()[i]).rem_euclid(q)
+            );
+        }
+
+        // It decrypts to `m(X^3)` under `s(X^3)`, and composes back to the identity
+        let transformed_key = SecretKey {
+            poly: secret_key.poly.automorphism(k),
+            ring: secret_key.ring,
+        };
+        assert_eq!(
+            permuted.decrypt(&transformed_key).poly(),
+            plaintext.poly().automorphism(k) % (t, degree)
+        );
+        // `3 * 11 = 33 = 1 mod 16`
+        assert_eq!(permuted.automorphism(11), ciphertext);
+    }
+
+    #[test]
+    fn degree_mismatch_errors() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let (q, t, std_dev) = (1 << 20, 16, 3.2);
+        let key_4 = SecretKey::generate(4, &mut rng);
+        let key_8 = SecretKey::generate(8, &mut rng);
+        let public_key_8 = key_8.public_key_gen(q, std_dev, &mut rng);
+        let plaintext = Plaintext::new(vec![1, 2, 3, 4], t);
+
+        assert_eq!(
+            plaintext
+                .try_encrypt(&public_key_8, std_dev, &mut rng)
+                .unwrap_err(),
+            BfvError::DegreeMismatch {
+                expected: 8,
+                actual: 4
+            }
+        );
+        let encrypted = std::panic::catch_unwind(|| {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+            plaintext.encrypt(&public_key_8, std_dev, &mut rng)
+        });
+        assert!(encrypted.is_err());
+
+        let ciphertext_4 = plaintext.encrypt(
+            &key_4.public_key_gen(q, std_dev, &mut rng),
+            std_dev,
+            &mut rng,
+        );
+        assert_eq!(
+            ciphertext_4.try_decrypt(&key_8),
+            Err(BfvError::DegreeMismatch {
+                expected: 8,
+                actual: 4
+            })
+        );
+        assert_eq!(
+            ciphertext_4.try_decrypt(&key_4),
+            Ok(Plaintext::new(vec![1, 2, 3, 4], t))
+        );
+
+        let ciphertext_8 = Plaintext::new(vec![1; 8], t).encrypt(&public_key_8, std_dev, &mut rng);
+        assert_eq!(
+            ciphertext_4.try_add(&ciphertext_8),
+            Err(BfvError::DegreeMismatch {
+                expected: 4,
+                actual: 8
+            })
+        );
+        let other_q = Plaintext::new(vec![1; 8], t).encrypt(
+            &key_8.public_key_gen(q << 1, std_dev, &mut rng),
+            std_dev,
+            &mut rng,
+        );
+        assert_eq!(
+            ciphertext_8.try_sub(&other_q),
+            Err(BfvError::ModulusChainMismatch {
+                q,
+                level: 0,
+                other_q: q << 1,
+                other_level: 0
+            })
+        );
+        assert!(std::panic::catch_unwind(|| &ciphertext_8 + &other_q).is_err());
+        assert_eq!(
+            ciphertext_8.try_add(&ciphertext_8).unwrap().decrypt(&key_8),
+            Plaintext::new(vec![2; 8], t)
+        );
+    }
+
+    // Test that the panicking entry points name both degrees, like the `try_` variants
+    #[test]
+    fn degree_mismatch_panic_messages() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let (q, t, std_dev, base) = (1 << 20, 16, 3.2, 16);
+        let key_4 = SecretKey::generate(4, &mut rng);
+        let key_8 = SecretKey::generate(8, &mut rng);
+        let public_key_8 = key_8.public_key_gen(q, std_dev, &mut rng);
+        let rlk = key_8.relin_key_gen_1(q, std_dev, &mut rng, base).unwrap();
+        let ct_4 = Plaintext::new(vec![1, 2, 3, 4], t).encrypt(
+            &key_4.public_key_gen(q, std_dev, &mut rng),
+            std_dev,
+            &mut rng,
+        );
+        let ct_8 = Plaintext::new(vec![1; 8], t).encrypt(&public_key_8, std_dev, &mut rng);
+
+        let panic_message = |f: &dyn Fn()| {
+            let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_err();
+            *payload.downcast::<String>().unwrap()
+        };
+        let cases: [(&dyn Fn(), &str); 6] = [
+            (&|| drop(&ct_4 + &ct_8), "cannot add"),
+            (&|| drop(&ct_4 - &ct_8), "cannot sub"),
+            (
+                &|| {
+                    let mut ct = ct_4.clone();
+                    ct += &ct_8;
+                },
+                "cannot add",
+            ),
+            (
+                &|| {
+                    let mut ct = ct_4.clone();
+                    ct -= &ct_8;
+                },
+                "cannot sub",
+            ),
+            (
+                &|| drop(ct_4.clone() * (ct_8.clone(), &rlk)),
+                "cannot multiply",
+            ),
+            (&|| drop(ct_4.decrypt(&key_8)), "cannot decrypt"),
+        ];
+        for (f, op) in cases {
+            let (expected, actual) = if op == "cannot decrypt" {
+                (8, 4)
+            } else {
+                (4, 8)
+            };
+            assert_eq!(
+                panic_message(f),
+                format!("{op}: expected degree {expected}, got degree {actual}")
+            );
+        }
+    }
+
+    #[test]
+    fn parameter_accessors() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        for (degree, q, t) in [(4, 1 << 16, 4), (8, 1 << 30, 17), (16, 65537, 257)] {
+            let secret_key = SecretKey::generate(degree, &mut rng);
+            let public_key = secret_key.public_key_gen(q, 3.2, &mut rng);
+            let plaintext = Plaintext::rand(degree, t, &mut rng);
+            assert_eq!((plaintext.t(), plaintext.degree()), (t, degree));
+
+            let ciphertext = plaintext.encrypt(&public_key, 3.2, &mut rng);
+            assert_eq!(
+                (ciphertext.q(), ciphertext.t(), ciphertext.degree()),
+                (q, t, degree)
+            );
+        }
+    }
+
+    // Test `(a + b) * c` and `a - b` on encrypted integers, which wrap around modulo `t`
+    #[test]
+    fn encrypted_integer_arithmetic() {
+        let (degree, q, std_dev) = (8, 1 << 40, 3.2);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(degree, &mut rng);
+        let public_key = secret_key.public_key_gen(q, std_dev, &mut rng);
+        let rlk_1 = secret_key
+            .relin_key_gen_1(q, std_dev, &mut rng, 1 << 10)
+            .unwrap();
+
+        for (t, a, b, c) in [(16, 3, 4, 2), (17, 9, 12, 7), (256, 100, -3, 5)] {
+            let [ct_a, ct_b, ct_c] = [a, b, c]
+                .map(|m| EncryptedInteger::encrypt(m, t, &public_key, &rlk_1, std_dev, &mut rng));
+            assert_eq!(ct_b.decrypt(&secret_key), b.rem_euclid(t));
+
+            let diff = ct_a.clone() - ct_b.clone();
+            assert_eq!(diff.decrypt(&secret_key), (a - b).rem_euclid(t));
+
+            let result = (ct_a + ct_b) * ct_c;
+            assert_eq!(result.ciphertext().t(), t);
+            assert_eq!(result.decrypt(&secret_key), ((a + b) * c).rem_euclid(t));
+        }
+    }
+
+    #[test]
+    fn plaintext_coefficient_access() {
+        let (degree, t) = (8, 17);
+        let mut pt = Plaintext::new(vec![0; degree], t);
+        for (i, value) in [(0, 5), (3, -1), (7, 40), (3, 16)] {
+            pt.set_coefficient(i, value).unwrap();
+            assert_eq!(pt.get_coefficient(i), Ok(value.rem_euclid(t)));
+        }
+        assert_eq!(pt, Plaintext::new(vec![5, 0, 0, 16, 0, 0, 0, 6], t));
+
+        // Coefficients of `new` are read back reduced
+        let pt = Plaintext::new(vec![-1, 18, 0, 0, 0, 0, 0, 0], t);
+        assert_eq!(
+            (pt.get_coefficient(0), pt.get_coefficient(1)),
+            (Ok(16), Ok(1))
+        );
+
+        let out_of_range = || BfvError::IndexOutOfRange {
+            index: degree,
+            degree,
+        };
+        assert_eq!(pt.get_coefficient(degree), Err(out_of_range()));
+        let mut pt = Plaintext::new(vec![0; degree], t);
+        assert_eq!(pt.set_coefficient(degree, 1), Err(out_of_range()));
+    }
 }
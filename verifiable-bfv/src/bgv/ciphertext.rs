@@ -0,0 +1,293 @@
+use crate::bfv::poly::{centered_coeff, Poly};
+use crate::bfv::random_source;
+use crate::bfv::rns::inv_mod;
+use crate::bfv::{Plaintext, PublicKey, SecretKey};
+use rand::{CryptoRng, RngCore};
+use std::cmp::Ordering;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A BGV Ciphertext
+///
+/// * `c` = `[c_0, c_1, ..., c_k]`, which decrypts with `(1, s, ..., s^k)`. A fresh ciphertext
+///   has `c_0 = [p_0 * u + t * e_1 + m]_q` and `c_1 = [p_1 * u + t * e_2]_q`, and each
+///   multiplication adds the sizes of its operands minus one.
+/// * `q` = the ciphertext modulus, which shrinks with every modulus switch
+/// * `t` = the plaintext modulus
+/// * `level` = the position of `q` in the modulus chain, counting the switches from the modulus
+///   of encryption
+///
+/// Binary operations on ciphertexts at different levels first switch the one with the larger
+/// modulus down to the other, which requires the larger modulus to be a multiple of the smaller.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BgvCiphertext {
+    pub(crate) c: Vec<Poly>,
+    pub(crate) q: i64,
+    pub(crate) t: i64,
+    pub(crate) level: usize,
+}
+
+impl BgvCiphertext {
+    /// Encrypt a plaintext with a BGV public key (see `SecretKey::bgv_public_key_gen`)
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let q = 65536;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// use verifiable_bfv::bgv::BgvCiphertext;
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.bgv_public_key_gen(q, t, std_dev, &mut rng);
+    ///
+    /// let ct = BgvCiphertext::encrypt(&pt, &public_key, std_dev, &mut rng);
+    /// assert_eq!(ct.decrypt(&secret_key), pt);
+    /// ```
+    pub fn encrypt<T: RngCore + CryptoRng>(
+        pt: &Plaintext,
+        pub_key: &PublicKey,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> BgvCiphertext {
+        let m = pt.poly();
+        let (q, t) = (pub_key.q, pt.t());
+        let degree = m.degree();
+        assert_eq!(degree, pub_key.p_0.degree());
+
+        let u = random_source::get_uniform(2, degree, rng);
+        let e_1 = random_source::get_gaussian(std_dev, degree, rng);
+        let e_2 = random_source::get_gaussian(std_dev, degree, rng);
+
+        let c_0 = (pub_key.p_0.mul_ntt(&u, q) + e_1 * t + m) % (q, degree);
+        let c_1 = (pub_key.p_1.mul_ntt(&u, q) + e_2 * t) % (q, degree);
+
+        BgvCiphertext {
+            c: vec![c_0, c_1],
+            q,
+            t,
+            level: 0,
+        }
+    }
+
+    /// Decrypt a ciphertext of any size to recover a plaintext, given a secret key
+    ///
+    /// The centered representatives of `[c(s)]_q` are `m + t * v`, so reducing them modulo `t`
+    /// removes the noise, as long as it stays below `q / 2`.
+    pub fn decrypt(&self, secret_key: &SecretKey) -> Plaintext {
+        let s = &secret_key.poly;
+        let degree = s.degree();
+
+        // Horner's rule: $ c(s) = c_0 + s * (c_1 + s * (c_2 + ...)) $
+        let raw = self
+            .c
+            .iter()
+            .rev()
+            .fold(Poly::new(vec![0; degree]), |acc, c_i| {
+                (acc.mul_ntt(s, self.q) + c_i.clone()) % (self.q, degree)
+            });
+        let centered = raw
+            .val()
+            .iter()
+            .map(|coeff| centered_coeff(*coeff, self.q))
+            .collect();
+
+        Plaintext::new_from_poly(Poly::new(centered) % (self.t, degree), self.t)
+    }
+
+    /// Return the number of polynomials in the ciphertext, 2 for a fresh one
+    pub fn size(&self) -> usize {
+        self.c.len()
+    }
+
+    /// Return the current ciphertext modulus
+    pub fn q(&self) -> i64 {
+        self.q
+    }
+
+    /// Return the level of the ciphertext in the modulus chain, 0 for a fresh one
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Switch the ciphertext from modulus `q` to `q / p`, dividing its noise by about `p`
+    ///
+    /// Each coefficient `c` is shifted by the smallest `delta` with `delta = -c mod p` and
+    /// `delta = 0 mod t`, then divided by `p`. `p` must divide `q` and equal `1` modulo `t`, so
+    /// that the message is left unchanged; the rounding adds at most `t * N / 2` to the noise.
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// # let std_dev = 3.2;
+    /// # let degree = 4;
+    /// # let t = 8;
+    /// #
+    /// use verifiable_bfv::bfv::{Plaintext, SecretKey};
+    /// use verifiable_bfv::bgv::BgvCiphertext;
+    /// let p = 1 + 8 * 1024;
+    /// let q = p * 65536;
+    /// let pt = Plaintext::new(vec![0, 1, 2, 3], t);
+    ///
+    /// let secret_key = SecretKey::generate(degree, &mut rng);
+    /// let public_key = secret_key.bgv_public_key_gen(q, t, std_dev, &mut rng);
+    ///
+    /// let ct = BgvCiphertext::encrypt(&pt, &public_key, std_dev, &mut rng).mod_switch(p);
+    /// assert_eq!(ct.q(), 65536);
+    /// assert_eq!(ct.decrypt(&secret_key), pt);
+    /// ```
+    pub fn mod_switch(&self, p: i64) -> BgvCiphertext {
+        assert_eq!(self.q % p, 0, "{p} does not divide the modulus {}", self.q);
+        assert_eq!(p % self.t, 1, "{p} is not 1 modulo {}", self.t);
+        let new_q = self.q / p;
+
+        let (p, t) = (p as i128, self.t as i128);
+        let t_inv = inv_mod(t, p);
+        let switch = |c_i: &Poly| {
+            let val = c_i
+                .val()
+                .iter()
+                .map(|coeff| {
+                    let coeff = *coeff as i128;
+                    let mut k = (-coeff * t_inv).rem_euclid(p);
+                    if k > p / 2 {
+                        k -= p;
+                    }
+                    ((coeff + t * k) / p).rem_euclid(new_q as i128) as i64
+                })
+                .collect();
+            Poly::new(val)
+        };
+
+        BgvCiphertext {
+            c: self.c.iter().map(switch).collect(),
+            q: new_q,
+            t: self.t,
+            level: self.level + 1,
+        }
+    }
+
+    /// Switch the operand with the larger modulus down to the modulus of the other one, which
+    /// then also takes its level.
+    fn align(self, other: BgvCiphertext) -> (BgvCiphertext, BgvCiphertext) {
+        assert_eq!(self.t, other.t);
+        let switch_down = |high: BgvCiphertext, low: &BgvCiphertext| {
+            assert_eq!(
+                high.q % low.q,
+                0,
+                "moduli {} and {} are not in the same modulus chain",
+                high.q,
+                low.q
+            );
+            let mut switched = high.mod_switch(high.q / low.q);
+            switched.level = low.level;
+            switched
+        };
+        match self.q.cmp(&other.q) {
+            Ordering::Greater => (switch_down(self, &other), other),
+            Ordering::Less => {
+                let other = switch_down(other, &self);
+                (self, other)
+            }
+            Ordering::Equal => (self, other),
+        }
+    }
+
+    fn zip_with(self, other: BgvCiphertext, op: impl Fn(&mut Poly, &Poly, i64)) -> BgvCiphertext {
+        let (this, other) = self.align(other);
+        let (q, t, level) = (this.q, this.t, this.level);
+        let (mut c, rest) = if this.c.len() >= other.c.len() {
+            (this.c, other.c)
+        } else {
+            let degree = this.c[0].degree();
+            let mut c = this.c;
+            c.resize(other.c.len(), Poly::new(vec![0; degree]));
+            (c, other.c)
+        };
+        for (lhs, rhs) in c.iter_mut().zip(rest.iter()) {
+            op(lhs, rhs, q);
+        }
+        BgvCiphertext { c, q, t, level }
+    }
+}
+
+/// Add two ciphertexts under the same moduli. They can be of different sizes.
+///
+/// An operand at a higher level of the modulus chain is first switched down to the other's level.
+impl Add<BgvCiphertext> for BgvCiphertext {
+    type Output = Self;
+    fn add(self, other: BgvCiphertext) -> Self::Output {
+        self.zip_with(other, |lhs, rhs, q| lhs.add_assign_mod(rhs, q))
+    }
+}
+
+/// Subtract one ciphertext from another under the same moduli. They can be of different sizes.
+///
+/// An operand at a higher level of the modulus chain is first switched down to the other's level.
+impl Sub<BgvCiphertext> for BgvCiphertext {
+    type Output = Self;
+    fn sub(self, other: BgvCiphertext) -> Self::Output {
+        self.zip_with(other, |lhs, rhs, q| lhs.sub_assign_mod(rhs, q))
+    }
+}
+
+/// Take the negation of a ciphertext.
+impl Neg for BgvCiphertext {
+    type Output = Self;
+    fn neg(mut self) -> Self::Output {
+        for c_i in self.c.iter_mut() {
+            let degree = c_i.degree();
+            *c_i = -c_i.clone() % (self.q, degree);
+        }
+        self
+    }
+}
+
+/// Multiply two ciphertexts under the same moduli with a tensor product, which decrypts to the
+/// product of the messages without any rescaling. The output has size `k + l - 1` for operands of
+/// sizes `k` and `l`, and its noise is about the product of their noises.
+///
+/// An operand at a higher level of the modulus chain is first switched down to the other's level.
+impl Mul<BgvCiphertext> for BgvCiphertext {
+    type Output = Self;
+    fn mul(self, other: BgvCiphertext) -> Self::Output {
+        let (this, other) = self.align(other);
+        let (q, t, level) = (this.q, this.t, this.level);
+        let degree = this.c[0].degree();
+
+        let mut c = vec![Poly::new(vec![0; degree]); this.c.len() + other.c.len() - 1];
+        for (i, lhs) in this.c.iter().enumerate() {
+            for (j, rhs) in other.c.iter().enumerate() {
+                c[i + j].add_assign_mod(&mul_mod(lhs, rhs, q), q);
+            }
+        }
+        BgvCiphertext { c, q, t, level }
+    }
+}
+
+/// Multiply two polynomials in `R_q`, widening the coefficient products to `i128`, since the
+/// moduli of BGV ciphertexts before a switch are typically too large for `Poly::mul_ntt`.
+fn mul_mod(lhs: &Poly, rhs: &Poly, q: i64) -> Poly {
+    let degree = lhs.degree();
+    let q = q as i128;
+    let mut out_val = vec![0i128; degree];
+    for (i, lhs_i) in lhs.val().iter().enumerate() {
+        for (j, rhs_j) in rhs.val().iter().enumerate() {
+            let product = (*lhs_i as i128 * *rhs_j as i128) % q;
+            if i + j >= degree {
+                out_val[i + j - degree] -= product;
+            } else {
+                out_val[i + j] += product;
+            }
+        }
+    }
+    Poly::new(
+        out_val
+            .into_iter()
+            .map(|coeff| coeff.rem_euclid(q) as i64)
+            .collect(),
+    )
+}
@@ -0,0 +1,48 @@
+use crate::bfv::poly::Poly;
+use crate::bfv::random_source;
+use crate::bfv::{PublicKey, SecretKey};
+use rand::{CryptoRng, RngCore};
+
+impl SecretKey {
+    /// Generate a BGV public key from a secret key
+    ///
+    /// The key has the same shape as a BFV12 one, `p_0 = [-(a * s + t * e)]_q` and `p_1 = a`, but
+    /// its error is scaled by the plaintext modulus `t`, so it only encrypts messages modulo `t`.
+    ///
+    /// * `q`: the ciphertext modulus
+    /// * `t`: the plaintext modulus
+    /// * `std_dev`: the standard deviation for error generation
+    /// * `rng`: the RNG used to generate randomness
+    ///
+    /// ```rust
+    /// # use rand::SeedableRng;
+    /// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+    /// #
+    /// use verifiable_bfv::bfv::SecretKey;
+    ///
+    /// let secret_key = SecretKey::generate(4, &mut rng);
+    /// let public_key = secret_key.bgv_public_key_gen(65536, 8, 3.2, &mut rng);
+    /// ```
+    pub fn bgv_public_key_gen<T: RngCore + CryptoRng>(
+        &self,
+        q: i64,
+        t: i64,
+        std_dev: f64,
+        rng: &mut T,
+    ) -> PublicKey {
+        let s = self.poly.clone();
+        let degree = s.degree();
+
+        let a = random_source::get_uniform(q, degree, rng);
+        let e = random_source::get_gaussian(std_dev, degree, rng);
+        let p_1 = a.clone();
+        let p_0: Poly = (-(a.mul_ring(&s, q, self.ring) + e * t)) % (q, self.ring);
+
+        PublicKey {
+            p_0,
+            p_1,
+            q,
+            ring: self.ring,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! The BGV scheme, sharing the keys, plaintexts and polynomial arithmetic of `bfv`
+//!
+//! Where BFV scales the message up by `delta = floor(q/t)` and keeps the noise in the low bits,
+//! BGV keeps the message in the low bits and scales the noise by `t`: a ciphertext `c` decrypts
+//! to `m = [[c(s)]_q]_t`, with `[c(s)]_q = m + t * v` for a small noise `v`. Multiplication is a
+//! plain tensor product, and the noise is managed by switching to a smaller modulus instead.
+
+mod ciphertext;
+mod keys;
+#[cfg(test)]
+mod tests;
+
+pub use ciphertext::BgvCiphertext;
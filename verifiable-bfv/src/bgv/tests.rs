@@ -0,0 +1,197 @@
+use crate::bfv::{Plaintext, SecretKey};
+use crate::bgv::ciphertext::BgvCiphertext;
+use rand::SeedableRng;
+
+fn encrypt_decrypt_helper(msg: Vec<i64>, t: i64, q: i64, std_dev: f64) {
+    let degree = msg.len();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+
+    let secret_key = SecretKey::generate(degree, &mut rng);
+    let public_key = secret_key.bgv_public_key_gen(q, t, std_dev, &mut rng);
+
+    let plaintext = Plaintext::new(msg, t);
+    let ciphertext = BgvCiphertext::encrypt(&plaintext, &public_key, std_dev, &mut rng);
+
+    let decrypted = ciphertext.decrypt(&secret_key);
+
+    assert_eq!(decrypted.poly(), plaintext.poly() % (t, degree));
+}
+
+#[test]
+fn encrypt_decrypt() {
+    for t in [2, 4, 8, 16, 32].iter() {
+        encrypt_decrypt_helper(vec![1, 0], *t, 65536, 3.2);
+        encrypt_decrypt_helper(vec![3, 2, 1, 0], *t, 65536, 3.2);
+        encrypt_decrypt_helper(vec![0, 1, 2, 3, 4, 5, 6, 7], *t, 65536, 3.2);
+    }
+}
+
+fn encrypt_add_sub_decrypt_helper(msg_1: Vec<i64>, msg_2: Vec<i64>, t: i64, q: i64, std_dev: f64) {
+    let degree = msg_1.len();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+
+    let secret_key = SecretKey::generate(degree, &mut rng);
+    let public_key = secret_key.bgv_public_key_gen(q, t, std_dev, &mut rng);
+
+    let plaintext_1 = Plaintext::new(msg_1, t);
+    let ciphertext_1 = BgvCiphertext::encrypt(&plaintext_1, &public_key, std_dev, &mut rng);
+    let decrypted_1 = ciphertext_1.decrypt(&secret_key);
+    assert_eq!(decrypted_1.poly(), plaintext_1.poly() % (t, degree));
+
+    let plaintext_2 = Plaintext::new(msg_2, t);
+    let ciphertext_2 = BgvCiphertext::encrypt(&plaintext_2, &public_key, std_dev, &mut rng);
+    let decrypted_2 = ciphertext_2.decrypt(&secret_key);
+    assert_eq!(decrypted_2.poly(), plaintext_2.poly() % (t, degree));
+
+    let add_ciphertext = ciphertext_1.clone() + ciphertext_2.clone();
+    let decrypted_add = add_ciphertext.decrypt(&secret_key);
+    let expected_add = (plaintext_1.poly() + plaintext_2.poly()) % (t, degree);
+    assert_eq!(decrypted_add.poly(), expected_add);
+
+    let sub_ciphertext = ciphertext_1.clone() - ciphertext_2;
+    let decrypted_sub = sub_ciphertext.decrypt(&secret_key);
+    let expected_sub = (plaintext_1.poly() - plaintext_2.poly()) % (t, degree);
+    assert_eq!(decrypted_sub.poly(), expected_sub);
+
+    let neg_ciphertext = -ciphertext_1;
+    let decrypted_neg = neg_ciphertext.decrypt(&secret_key);
+    let expected_neg = -plaintext_1.poly() % (t, degree);
+    assert_eq!(decrypted_neg.poly(), expected_neg);
+}
+
+#[test]
+fn encrypt_add_sub_decrypt() {
+    for t in [2, 4, 8, 16, 32].iter() {
+        encrypt_add_sub_decrypt_helper(vec![1, 0], vec![0, 1], *t, 65536, 3.2);
+        encrypt_add_sub_decrypt_helper(vec![3, 2, 1, 0], vec![1, 1, 3, 2], *t, 65536, 3.2);
+        encrypt_add_sub_decrypt_helper(
+            vec![0, 1, 2, 3, 4, 5, 6, 7],
+            vec![7, 6, 5, 4, 3, 2, 1, 0],
+            *t,
+            65536,
+            3.2,
+        );
+    }
+}
+
+fn mul_mod_switch_helper(msgs: [Vec<i64>; 3], t: i64, std_dev: f64) {
+    // `p = 1 mod 32` divides `q`, so that switching to `q / p` keeps the message modulo `t`.
+    let p = 1 + 32 * 1024;
+    let q = p * (1 << 25);
+    let degree = msgs[0].len();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(20);
+
+    let secret_key = SecretKey::generate(degree, &mut rng);
+    let public_key = secret_key.bgv_public_key_gen(q, t, std_dev, &mut rng);
+
+    let [pt_1, pt_2, pt_3] = msgs.map(|msg| Plaintext::new(msg, t));
+    let [ct_1, ct_2, ct_3] =
+        [&pt_1, &pt_2, &pt_3].map(|pt| BgvCiphertext::encrypt(pt, &public_key, std_dev, &mut rng));
+
+    let product = ct_1 * ct_2;
+    assert_eq!(product.size(), 3);
+    let expected = (pt_1.poly() * pt_2.poly()) % (t, degree);
+    assert_eq!(product.decrypt(&secret_key).poly(), expected);
+
+    // The modulus switch shrinks the product noise to make room for the next multiplication.
+    let switched = product.mod_switch(p);
+    assert_eq!(switched.q(), 1 << 25);
+    assert_eq!(switched.decrypt(&secret_key).poly(), expected);
+
+    let product = switched * ct_3.mod_switch(p);
+    assert_eq!(product.size(), 4);
+    let expected = (expected * pt_3.poly()) % (t, degree);
+    assert_eq!(product.decrypt(&secret_key).poly(), expected);
+}
+
+#[test]
+fn mul_mod_switch() {
+    for t in [2, 4, 8, 16, 32].iter() {
+        mul_mod_switch_helper([vec![1, 0], vec![1, 1], vec![0, 1]], *t, 3.2);
+        mul_mod_switch_helper(
+            [vec![3, 2, 1, 0], vec![1, 1, 3, 2], vec![2, 0, 1, 1]],
+            *t,
+            3.2,
+        );
+        mul_mod_switch_helper(
+            [
+                vec![0, 1, 2, 3, 4, 5, 6, 7],
+                vec![7, 6, 5, 4, 3, 2, 1, 0],
+                vec![1, 0, 1, 0, 1, 0, 1, 0],
+            ],
+            *t,
+            3.2,
+        );
+    }
+}
+
+fn mismatched_levels_helper(msg_1: Vec<i64>, msg_2: Vec<i64>, t: i64, std_dev: f64) {
+    // The modulus chain `q > q / p_1 > q / (p_1 * p_2)`, with `p_i = 1 mod 32`
+    let (p_1, p_2) = (1 + 32 * 1024, 1 + 32 * 16);
+    let q = p_1 * p_2 * (1 << 16);
+    let degree = msg_1.len();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(21);
+
+    let secret_key = SecretKey::generate(degree, &mut rng);
+    let public_key = secret_key.bgv_public_key_gen(q, t, std_dev, &mut rng);
+
+    let pt_1 = Plaintext::new(msg_1, t);
+    let pt_2 = Plaintext::new(msg_2, t);
+    let ct_1 = BgvCiphertext::encrypt(&pt_1, &public_key, std_dev, &mut rng);
+    let ct_2 = BgvCiphertext::encrypt(&pt_2, &public_key, std_dev, &mut rng).mod_switch(p_1);
+    let ct_3 = ct_2.mod_switch(p_2);
+    assert_eq!((ct_1.level(), ct_2.level(), ct_3.level()), (0, 1, 2));
+
+    let expected_add = (pt_1.poly() + pt_2.poly()) % (t, degree);
+    let expected_sub = (pt_1.poly() - pt_2.poly()) % (t, degree);
+    let expected_mul = (pt_1.poly() * pt_2.poly()) % (t, degree);
+    for (low, q_low, level) in [(&ct_2, q / p_1, 1), (&ct_3, q / (p_1 * p_2), 2)] {
+        // Either operand order switches the fresh ciphertext down to the lower level.
+        for sum in [ct_1.clone() + low.clone(), low.clone() + ct_1.clone()] {
+            assert_eq!((sum.q(), sum.level()), (q_low, level));
+            assert_eq!(sum.decrypt(&secret_key).poly(), expected_add);
+        }
+
+        let sub = ct_1.clone() - low.clone();
+        assert_eq!((sub.q(), sub.level()), (q_low, level));
+        assert_eq!(sub.decrypt(&secret_key).poly(), expected_sub);
+
+        let product = low.clone() * ct_1.clone();
+        assert_eq!((product.q(), product.level()), (q_low, level));
+        assert_eq!(product.decrypt(&secret_key).poly(), expected_mul);
+    }
+
+    // Both levels 1 and 2 are below the fresh ciphertext, so the sum lands at level 2.
+    let sum = ct_1 + ct_2 + ct_3;
+    assert_eq!(sum.level(), 2);
+    let expected = (pt_1.poly() + pt_2.poly() * 2) % (t, degree);
+    assert_eq!(sum.decrypt(&secret_key).poly(), expected);
+}
+
+#[test]
+fn mismatched_levels() {
+    for t in [2, 4, 8, 16, 32].iter() {
+        mismatched_levels_helper(vec![1, 0], vec![0, 1], *t, 3.2);
+        mismatched_levels_helper(vec![3, 2, 1, 0], vec![1, 1, 3, 2], *t, 3.2);
+        mismatched_levels_helper(
+            vec![0, 1, 2, 3, 4, 5, 6, 7],
+            vec![7, 6, 5, 4, 3, 2, 1, 0],
+            *t,
+            3.2,
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "not in the same modulus chain")]
+fn mismatched_chains() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(21);
+    let secret_key = SecretKey::generate(4, &mut rng);
+    let pt = Plaintext::new(vec![0, 1, 2, 3], 8);
+
+    let public_key_1 = secret_key.bgv_public_key_gen(65536, 8, 3.2, &mut rng);
+    let public_key_2 = secret_key.bgv_public_key_gen(65537 * 3, 8, 3.2, &mut rng);
+    let ct_1 = BgvCiphertext::encrypt(&pt, &public_key_1, 3.2, &mut rng);
+    let ct_2 = BgvCiphertext::encrypt(&pt, &public_key_2, 3.2, &mut rng);
+    let _ = ct_1 + ct_2;
+}
@@ -0,0 +1,13 @@
+use verifiable_bfv::ntt_params::generate_params_source;
+
+/// Prints a `params_<n>.rs` file for the ring dimension and NTT modulus given as arguments
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let [n, q] = args.as_slice() else {
+        eprintln!("usage: gen_params <N> <Q>");
+        std::process::exit(1);
+    };
+    let n = n.parse().expect("N must be an integer");
+    let q = q.parse().expect("Q must be an integer");
+    print!("{}", generate_params_source(n, q, &mut rand::thread_rng()));
+}
@@ -0,0 +1,4 @@
+pub mod bfv;
+pub mod bgv;
+pub mod ntt_params;
+pub mod vbfv;
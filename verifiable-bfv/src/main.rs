@@ -1,7 +1,3 @@
-mod bfv;
-mod ntt_params;
-mod vbfv;
-
 fn main() {
     println!("Hello, world!");
 }
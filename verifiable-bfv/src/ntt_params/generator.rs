@@ -0,0 +1,252 @@
+use rand::Rng;
+
+use super::bit_reverse_permute;
+
+/// Returns `base^exp mod q`.
+fn pow_mod(base: u64, mut exp: u64, q: u64) -> u64 {
+    let (mut base, mut acc) = (base as u128 % q as u128, 1u128);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = acc * base % q as u128;
+        }
+        base = base * base % q as u128;
+        exp >>= 1;
+    }
+    acc as u64
+}
+
+/// Returns the distinct prime factors of `n`, by trial division.
+fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = vec![];
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Returns the smallest generator of the multiplicative group modulo the prime `q`, the one
+/// `primitive_root` of "gen_param_file.sage" picks.
+fn primitive_root(q: u64) -> u64 {
+    let factors = prime_factors(q - 1);
+    (2..q)
+        .find(|g| factors.iter().all(|p| pow_mod(*g, (q - 1) / p, q) != 1))
+        .expect("q is prime")
+}
+
+/// Returns the primitive `2n`-th root of unity modulo `q` that the params files are built from,
+/// `psi = g^{(q - 1) / 2n}` for the smallest primitive root `g` modulo `q`, or `None` if `q` is
+/// not a prime or `2n` does not divide `q - 1`, i.e. `q` is not NTT-friendly for `n`
+///
+/// ```rust
+/// use verifiable_bfv::ntt_params::primitive_2n_root;
+/// assert_eq!(primitive_2n_root(8, 3329), Some(2699));
+/// assert_eq!(primitive_2n_root(512, 3329), None);
+/// ```
+pub fn primitive_2n_root(n: usize, q: u64) -> Option<u64> {
+    let two_n = 2 * n as u64;
+    if n == 0 || q < 3 || prime_factors(q) != [q] || !(q - 1).is_multiple_of(two_n) {
+        return None;
+    }
+    Some(pow_mod(primitive_root(q), (q - 1) / two_n, q))
+}
+
+/// Returns whether `roots` are the powers `[psi^j : j \in (0..n)]` of a primitive `2n`-th root of
+/// unity `psi` modulo `q`, in bit-reversed order, as the `ROOTS` and `INVROOTS` of the params
+/// files must be for the NTT to be correct
+///
+/// ```rust
+/// use verifiable_bfv::ntt_params::{params, verify_roots};
+/// assert!(verify_roots(&params::ROOTS, params::N, 3329));
+/// assert!(verify_roots(&params::INVROOTS, params::N, 3329));
+/// assert!(!verify_roots(&params::ROOTS, params::N, 3331));
+/// ```
+pub fn verify_roots(roots: &[u64], n: usize, q: u64) -> bool {
+    if !n.is_power_of_two() || roots.len() != n || q < 3 {
+        return false;
+    }
+    let mut powers = roots.to_vec();
+    bit_reverse_permute(&mut powers);
+    // `2n` is a power of two, so `psi^n = -1` makes `psi` a primitive `2n`-th root
+    let psi = if n == 1 { q - 1 } else { powers[1] };
+    pow_mod(psi, n as u64, q) == q - 1
+        && powers
+            .iter()
+            .enumerate()
+            .all(|(j, root)| *root == pow_mod(psi, j as u64, q))
+}
+
+/// Returns the powers `[psi^j : j \in (0..n)]` of `psi = primitive_2n_root(n, q)` and of its
+/// inverse, in bit-reversed order.
+pub(crate) fn bit_reversed_roots(n: usize, q: u64) -> (Vec<u64>, Vec<u64>) {
+    let psi = primitive_2n_root(n, q).expect("q is an NTT-friendly prime for n");
+    let psi_inv = pow_mod(psi, q - 2, q);
+    let powers = |root: u64| {
+        let mut powers = (0..n as u64)
+            .map(|j| pow_mod(root, j, q))
+            .collect::<Vec<_>>();
+        bit_reverse_permute(&mut powers);
+        powers
+    };
+    (powers(psi), powers(psi_inv))
+}
+
+/// Transforms the coefficients `g` into their evaluations at the odd powers of the root of
+/// `roots`, in bit-reversed order, with the butterflies of `ntt_forward`.
+pub(crate) fn ntt_forward_mod(g: &[u64], roots: &[u64], q: u64) -> Vec<u64> {
+    let n = g.len();
+    let mul = |x: u64, y: u64| (x as u128 * y as u128 % q as u128) as u64;
+    let mut a = g.to_vec();
+    let (mut m, mut t) = (1, n);
+    while m < n {
+        t /= 2;
+        for i in 0..m {
+            let j1 = 2 * i * t;
+            for j in j1..j1 + t {
+                let (u, v) = (a[j], mul(a[j + t], roots[m + i]));
+                a[j] = (u + v) % q;
+                a[j + t] = (u + q - v) % q;
+            }
+        }
+        m *= 2;
+    }
+    a
+}
+
+fn format_array(values: &[u64]) -> String {
+    let values = values.iter().map(u64::to_string).collect::<Vec<_>>();
+    format!("[{}]", values.join(", "))
+}
+
+/// Generates the source of a `params_<n>.rs` file for the ring dimension `n` and the NTT modulus
+/// `q`, the Rust counterpart of "gen_param_file.sage"
+///
+/// `ROOTS` and `INVROOTS` hold the powers of the `2n`-th root of unity `psi = g^{(q - 1) / 2n}`,
+/// where `g` is the smallest primitive root modulo `q`, and of its inverse, in bit-reversed order.
+/// `TESTG` is a fresh polynomial sampled from `rng` and `TESTGHAT` its forward NTT.
+///
+/// Panics if `n` is not a power of two, or `q` is not a prime with `2n | q - 1`.
+///
+/// ```rust
+/// # use rand::SeedableRng;
+/// # let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+/// use verifiable_bfv::ntt_params::generate_params_source;
+/// let source = generate_params_source(8, 3329, &mut rng);
+/// assert!(source.starts_with("pub const N: usize = 8;\npub const LOGN: u32 = 3;\n"));
+/// ```
+pub fn generate_params_source<R: Rng>(n: usize, q: u64, rng: &mut R) -> String {
+    assert!(n.is_power_of_two(), "N must be a power of two");
+    assert!(
+        q > 2 && prime_factors(q) == [q],
+        "Q must be an odd prime, got {q}"
+    );
+    assert!((q - 1).is_multiple_of(2 * n as u64), "2N must divide Q - 1");
+
+    let (roots, inv_roots) = bit_reversed_roots(n, q);
+    let logn = n.trailing_zeros();
+    let n_inv = pow_mod(pow_mod(2, q - 2, q), logn as u64, q);
+    let g = (0..n).map(|_| rng.gen_range(0, q)).collect::<Vec<_>>();
+    let ghat = ntt_forward_mod(&g, &roots, q);
+
+    let mut source = String::new();
+    source += &format!("pub const N: usize = {n};\n");
+    source += &format!("pub const LOGN: u32 = {logn};\n");
+    source += &format!("pub const NINV: u64 = {n_inv};\n\n");
+    source += &format!(
+        "pub const ROOTS: [u64; {n}] = {};\n\n",
+        format_array(&roots)
+    );
+    source += &format!(
+        "pub const INVROOTS: [u64; {n}] = {};\n\n",
+        format_array(&inv_roots)
+    );
+    source += "// Test Vectors\n\n";
+    source += &format!("pub const TESTG: [u64; {n}] = {};\n\n", format_array(&g));
+    source += &format!(
+        "pub const TESTGHAT: [u64; {n}] = {};\n",
+        format_array(&ghat)
+    );
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        bit_reversed_roots, generate_params_source, ntt_forward_mod, pow_mod, primitive_2n_root,
+        verify_roots,
+    };
+    use crate::ntt_params::{bit_reverse_permute, params};
+    use rand::SeedableRng;
+
+    const Q: u64 = 3329;
+
+    #[test]
+    fn test_generate_params_8() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let source = generate_params_source(params::N, Q, &mut rng);
+        let committed = include_str!("params_8.rs");
+        let (source, _) = source.split_once("// Test Vectors").unwrap();
+        let (committed, _) = committed.split_once("// Test Vectors").unwrap();
+        assert_eq!(source, committed);
+
+        // The committed test vectors are a forward NTT with the generated roots
+        let (roots, _) = bit_reversed_roots(params::N, Q);
+        assert_eq!(ntt_forward_mod(&params::TESTG, &roots, Q), params::TESTGHAT);
+    }
+
+    #[test]
+    fn test_generated_roots_are_primitive() {
+        for n in [2, 8, 64, 128] {
+            let (roots, inv_roots) = bit_reversed_roots(n, Q);
+            let psi = primitive_2n_root(n, Q).unwrap();
+            assert_eq!(roots[n / 2], psi);
+            assert_eq!(pow_mod(psi, 2 * n as u64, Q), 1);
+            assert_eq!(psi * inv_roots[n / 2] % Q, 1);
+            assert!(verify_roots(&roots, n, Q));
+            assert!(verify_roots(&inv_roots, n, Q));
+        }
+    }
+
+    #[test]
+    fn test_verify_params_roots() {
+        assert!(verify_roots(&params::ROOTS, params::N, Q));
+        assert!(verify_roots(&params::INVROOTS, params::N, Q));
+
+        // Natural order instead of bit-reversed order
+        let mut roots = params::ROOTS;
+        bit_reverse_permute(&mut roots);
+        assert!(!verify_roots(&roots, params::N, Q));
+        // A `N`-th root of unity, which is not primitive as a `2N`-th one
+        let squares = params::ROOTS.map(|root| root * root % Q);
+        assert!(!verify_roots(&squares, params::N, Q));
+        assert!(!verify_roots(&params::ROOTS[..4], params::N, Q));
+    }
+
+    #[test]
+    fn test_primitive_2n_root_rejects_unfriendly_q() {
+        // `3328 = 2^8 * 13`
+        assert!(primitive_2n_root(128, Q).is_some());
+        assert_eq!(primitive_2n_root(256, Q), None);
+        // `2^16 + 1` is a Fermat prime, `3331` is a prime with `3330 = 2 * 1665`
+        assert!(primitive_2n_root(1 << 15, 65537).is_some());
+        assert_eq!(primitive_2n_root(2, 3331), None);
+        // Not a prime
+        assert_eq!(primitive_2n_root(8, 17 * 97), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "2N must divide Q - 1")]
+    fn test_generate_params_rejects_small_q() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        generate_params_source(512, Q, &mut rng);
+    }
+}
@@ -1,5 +1,83 @@
 // use this path to set the ring dimension N (i.e. for N=512 set the path to "params_512.rs")
 // make sure to adjust the value of circuit_size in line 57 of "ivc_based_vpbs.rs" if
 // using a value other than 8 or 1024
+// params files are generated with `generate_params_source`, e.g.
+// `cargo run --bin gen_params -- 512 12289 > src/ntt_params/params_512.rs`
 #[path = "params_8.rs"]
-pub(crate) mod params;
+pub mod params;
+
+mod generator;
+pub use generator::{generate_params_source, primitive_2n_root, verify_roots};
+
+/// Returns `i` with its lowest `logn` bits reversed.
+///
+/// `ROOTS` and `INVROOTS` store the powers of the `2N`-th root of unity in this order, i.e.
+/// `ROOTS[bit_reverse_index(j, LOGN)]` is `psi^j`.
+pub fn bit_reverse_index(i: usize, logn: u32) -> usize {
+    if logn == 0 {
+        return 0;
+    }
+    i.reverse_bits() >> (usize::BITS - logn)
+}
+
+/// Permutes `data` in place so that `data[i]` moves to `data[bit_reverse_index(i, log2(len))]`.
+/// The length of `data` must be a power of two.
+pub fn bit_reverse_permute<T>(data: &mut [T]) {
+    assert!(data.len().is_power_of_two());
+    let logn = data.len().trailing_zeros();
+    for i in 0..data.len() {
+        let j = bit_reverse_index(i, logn);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bit_reverse_index, bit_reverse_permute, params};
+
+    #[test]
+    fn test_bit_reverse_index() {
+        for logn in 0..=10 {
+            for i in 0..(1usize << logn) {
+                // Reference: reverse the `logn`-bit binary representation of `i`.
+                let expected = if logn == 0 {
+                    0
+                } else {
+                    let bits = format!("{:0width$b}", i, width = logn as usize);
+                    usize::from_str_radix(&bits.chars().rev().collect::<String>(), 2).unwrap()
+                };
+                assert_eq!(bit_reverse_index(i, logn), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bit_reverse_permute() {
+        for logn in 0..=10 {
+            let n = 1usize << logn;
+            let original = (0..n).collect::<Vec<_>>();
+            let mut data = original.clone();
+            bit_reverse_permute(&mut data);
+            for (i, value) in data.iter().enumerate() {
+                assert_eq!(*value, bit_reverse_index(i, logn));
+            }
+            bit_reverse_permute(&mut data);
+            assert_eq!(data, original);
+        }
+    }
+
+    #[test]
+    fn test_roots_are_bit_reversed() {
+        const Q: u64 = 3329;
+        let mut roots = params::ROOTS;
+        bit_reverse_permute(&mut roots);
+        let psi = roots[1];
+        let mut power = 1;
+        for root in roots {
+            assert_eq!(root, power);
+            power = power * psi % Q;
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::{iter::once, marker::PhantomData, ops::Add};
 
-use anyhow::{Error, Result};
+use anyhow::Result;
 use itertools::{chain, Itertools};
 use plonky2::{
     field::extension::Extendable,
@@ -11,21 +11,29 @@ use plonky2::{
         witness::{PartitionWitness, Witness, WitnessWrite},
     },
     plonk::{circuit_builder::CircuitBuilder, circuit_data::CommonCircuitData},
-    util::serialization::{Buffer, IoResult, Write},
+    util::{
+        log2_ceil,
+        serialization::{Buffer, IoError, IoResult, Read, Write},
+    },
 };
 
-use super::assigned::{AssignedNTTPoly, AssignedValue};
+use super::{
+    assigned::{AssignedCenteredValue, AssignedNTTPoly, AssignedValue},
+    error::VbfvError,
+};
 
 #[derive(Debug)]
 enum ArithmeticOpKind<F: RichField + Extendable<D>, const D: usize, const Q: u64> {
     Add(AssignedValue<F, D, Q>, AssignedValue<F, D, Q>),
     Sub(AssignedValue<F, D, Q>, AssignedValue<F, D, Q>),
     Mul(AssignedValue<F, D, Q>, AssignedValue<F, D, Q>),
+    AddConst(F, AssignedValue<F, D, Q>),
     MulConst(F, AssignedValue<F, D, Q>),
 }
 
 #[derive(Debug)]
-struct ArithmeticOpsGenerator<F: RichField + Extendable<D>, const D: usize, const Q: u64> {
+pub(crate) struct ArithmeticOpsGenerator<F: RichField + Extendable<D>, const D: usize, const Q: u64>
+{
     quotient: AssignedValue<F, D, Q>,
     op_kind: ArithmeticOpKind<F, D, Q>,
 }
@@ -48,7 +56,7 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> SimpleGenerator
             ArithmeticOpKind::Add(x, y)
             | ArithmeticOpKind::Sub(x, y)
             | ArithmeticOpKind::Mul(x, y) => [x.value, y.value].to_vec(),
-            ArithmeticOpKind::MulConst(_, x) => vec![x.value],
+            ArithmeticOpKind::AddConst(_, x) | ArithmeticOpKind::MulConst(_, x) => vec![x.value],
         };
         dependencies
     }
@@ -74,6 +82,10 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> SimpleGenerator
                 let y = witness.get_target(y.value);
                 (x.to_canonical_u64() as u128) * (y.to_canonical_u64() as u128)
             }
+            ArithmeticOpKind::AddConst(constant, x) => {
+                let x = witness.get_target(x.value);
+                (constant.to_canonical_u64() as u128) + (x.to_canonical_u64() as u128)
+            }
             ArithmeticOpKind::MulConst(constant, x) => {
                 let x = witness.get_target(x.value);
                 (constant.to_canonical_u64() as u128) * (x.to_canonical_u64() as u128)
@@ -83,20 +95,144 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> SimpleGenerator
         out_buffer.set_target(self.quotient.value, F::from_canonical_u64(quotient))
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        let dependencies = self.dependencies();
-        dependencies
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        // The operation kind, then its constant if any, then its operands and the quotient
+        match self.op_kind {
+            ArithmeticOpKind::Add(..) => dst.write_u8(0)?,
+            ArithmeticOpKind::Sub(..) => dst.write_u8(1)?,
+            ArithmeticOpKind::Mul(..) => dst.write_u8(2)?,
+            ArithmeticOpKind::AddConst(constant, _) => {
+                dst.write_u8(3)?;
+                dst.write_field(constant)?;
+            }
+            ArithmeticOpKind::MulConst(constant, _) => {
+                dst.write_u8(4)?;
+                dst.write_field(constant)?;
+            }
+        }
+        self.dependencies()
             .into_iter()
             .chain(once(self.quotient.value))
-            .map(|target| dst.write_target(target))
-            .collect::<IoResult<()>>()
+            .try_for_each(|target| dst.write_target(target))
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
+    where
+        Self: Sized,
+    {
+        let read_value =
+            |src: &mut Buffer| Ok(AssignedValue::new_from_target_unchecked(src.read_target()?));
+        let op_kind = match src.read_u8()? {
+            0 => ArithmeticOpKind::Add(read_value(src)?, read_value(src)?),
+            1 => ArithmeticOpKind::Sub(read_value(src)?, read_value(src)?),
+            2 => ArithmeticOpKind::Mul(read_value(src)?, read_value(src)?),
+            3 => ArithmeticOpKind::AddConst(src.read_field()?, read_value(src)?),
+            4 => ArithmeticOpKind::MulConst(src.read_field()?, read_value(src)?),
+            _ => return Err(IoError),
+        };
+        Ok(Self::new(read_value(src)?, op_kind))
+    }
+}
+
+/// Witnesses the reduction of `x` modulo the plaintext modulus `t`:
+/// `x = quotient * t + remainder` with `remainder \in [0, t)`.
+#[derive(Debug)]
+pub(crate) struct ReduceModTGenerator {
+    plaintext_modulus: u64,
+    x: Target,
+    quotient: Target,
+    remainder: Target,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for ReduceModTGenerator {
+    fn id(&self) -> String {
+        "ReduceModTGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.x]
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let x = witness.get_target(self.x).to_canonical_u64();
+        let t = self.plaintext_modulus;
+        out_buffer.set_target(self.quotient, F::from_canonical_u64(x / t))?;
+        out_buffer.set_target(self.remainder, F::from_canonical_u64(x % t))
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.plaintext_modulus as usize)?;
+        dst.write_target(self.x)?;
+        dst.write_target(self.quotient)?;
+        dst.write_target(self.remainder)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            plaintext_modulus: src.read_usize()? as u64,
+            x: src.read_target()?,
+            quotient: src.read_target()?,
+            remainder: src.read_target()?,
+        })
+    }
+}
+
+/// Witnesses the base `B` digits of `x`, least significant first: `x = \sum_i digit_i B^i`,
+/// with the top digit taking the remaining quotient as `Poly::decompose` does.
+#[derive(Debug)]
+pub(crate) struct GadgetDecompositionGenerator {
+    base: u64,
+    x: Target,
+    digits: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for GadgetDecompositionGenerator
+{
+    fn id(&self) -> String {
+        "GadgetDecompositionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.x]
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let mut rest = witness.get_target(self.x).to_canonical_u64();
+        let (top, digits) = self.digits.split_last().expect("at least one digit");
+        for digit in digits.iter() {
+            out_buffer.set_target(*digit, F::from_canonical_u64(rest % self.base))?;
+            rest /= self.base;
+        }
+        out_buffer.set_target(*top, F::from_canonical_u64(rest))
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.base as usize)?;
+        dst.write_target(self.x)?;
+        dst.write_target_vec(&self.digits)
     }
 
-    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
     where
         Self: Sized,
     {
-        todo!()
+        Ok(Self {
+            base: src.read_usize()? as u64,
+            x: src.read_target()?,
+            digits: src.read_target_vec()?,
+        })
     }
 }
 
@@ -110,12 +246,38 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> ArithmeticChip<
         Self { cb }
     }
 
+    /// Allocates the quotient of a sum of two `\mathbb{Z}_Q` elements by `Q`, which is `0` or `1`.
+    /// A boolean constraint bounds it, so no range check is needed.
+    fn new_carry(&mut self) -> AssignedValue<F, D, Q> {
+        let carry = self.cb.add_virtual_bool_target_safe();
+        AssignedValue::new_from_target_unchecked(carry.target)
+    }
+
+    /// Returns `op(x, y) mod Q` as a constant if both operands are constants of the builder, i.e.
+    /// created with `cb.constant`, so that the operation needs neither a quotient nor a generator.
+    /// `op` must not underflow for operands in `[0, Q)`.
+    fn fold_constants(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        y: AssignedValue<F, D, Q>,
+        op: impl Fn(u128, u128) -> u128,
+    ) -> Option<AssignedValue<F, D, Q>> {
+        let x = self.cb.target_as_constant(x.value)?.to_canonical_u64() % Q;
+        let y = self.cb.target_as_constant(y.value)?.to_canonical_u64() % Q;
+        let result = op(x as u128, y as u128).rem_euclid(Q as u128) as u64;
+        let result = self.cb.constant(F::from_canonical_u64(result));
+        Some(AssignedValue::new_from_target_unchecked(result))
+    }
+
     pub(crate) fn add(
         &mut self,
         x: AssignedValue<F, D, Q>,
         y: AssignedValue<F, D, Q>,
-    ) -> Result<AssignedValue<F, D, Q>, Error> {
-        let quotient = AssignedValue::new(&mut self.cb);
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        if let Some(result) = self.fold_constants(x, y, |x, y| x + y) {
+            return Ok(result);
+        }
+        let quotient = self.new_carry();
         let op_kind = ArithmeticOpKind::Add(x, y);
         let arithmetic_ops_generator = ArithmeticOpsGenerator::new(quotient, op_kind);
         self.cb.add_simple_generator(arithmetic_ops_generator);
@@ -134,8 +296,11 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> ArithmeticChip<
         &mut self,
         x: AssignedValue<F, D, Q>,
         y: AssignedValue<F, D, Q>,
-    ) -> Result<AssignedValue<F, D, Q>, Error> {
-        let quotient = AssignedValue::new(&mut self.cb);
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        if let Some(result) = self.fold_constants(x, y, |x, y| x + Q as u128 - y) {
+            return Ok(result);
+        }
+        let quotient = self.new_carry();
         let op_kind = ArithmeticOpKind::Sub(x, y);
         let arithmetic_ops_generator = ArithmeticOpsGenerator::new(quotient, op_kind);
         self.cb.add_simple_generator(arithmetic_ops_generator);
@@ -151,11 +316,58 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> ArithmeticChip<
         Ok(AssignedValue::new_from_target(&mut self.cb, result))
     }
 
+    /// Adds a constant `\mathbb{Z}_Q` element, given as its representative in `[0, Q)`.
+    pub(crate) fn add_with_constant(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        constant: F,
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        assert!(constant.to_canonical_u64() < Q);
+        let quotient = self.new_carry();
+        let op_kind = ArithmeticOpKind::AddConst(constant, x);
+        let arithmetic_ops_generator = ArithmeticOpsGenerator::new(quotient, op_kind);
+        self.cb.add_simple_generator(arithmetic_ops_generator);
+
+        let ring_modulus = F::from_canonical_u64(Q);
+        let one = F::ONE;
+        let neg_one = self.cb.neg_one();
+        let tmp = self.cb.add_const(x.value, constant);
+        let result = self
+            .cb
+            .arithmetic(ring_modulus, one, neg_one, quotient.value, tmp);
+        Ok(AssignedValue::new_from_target(&mut self.cb, result))
+    }
+
+    /// Subtracts a constant `\mathbb{Z}_Q` element, given as its representative in `[0, Q)`, by
+    /// adding its negation `Q - constant`, so that the quotient is `1` unless `x < constant`
+    /// wraps around.
+    pub(crate) fn sub_with_constant(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        constant: F,
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        let constant = constant.to_canonical_u64();
+        assert!(constant < Q);
+        if constant == 0 {
+            return Ok(x);
+        }
+        self.add_with_constant(x, F::from_canonical_u64(Q - constant))
+    }
+
+    /// Negates a `\mathbb{Z}_Q` element, returning `(Q - x) mod Q`, i.e. `0` for `x = 0`.
+    pub(crate) fn neg(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        let zero = AssignedValue::new_from_target_unchecked(self.cb.zero());
+        self.sub(zero, x)
+    }
+
     pub(crate) fn mul_with_constant(
         &mut self,
         multiplicand: AssignedValue<F, D, Q>,
         constant: F,
-    ) -> Result<AssignedValue<F, D, Q>, Error> {
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
         let quotient = AssignedValue::new(&mut self.cb);
         let op_kind = ArithmeticOpKind::MulConst(constant, multiplicand);
         let arithmetic_ops_generator = ArithmeticOpsGenerator::new(quotient, op_kind);
@@ -171,12 +383,797 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> ArithmeticChip<
         Ok(AssignedValue::new_from_target(&mut self.cb, result))
     }
 
+    /// Reduces a `\mathbb{Z}_Q` element modulo the plaintext modulus `t`, returning `[x]_t` as a
+    /// value in `[0, t)`.
+    /// The quotient and remainder are witnessed and constrained by `x = quotient * t + remainder`
+    /// with `0 <= remainder < t`. `t` must lie in `[1, Q)`.
+    pub(crate) fn reduce_mod_t(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        t: u64,
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        if t == 0 || t >= Q {
+            return Err(VbfvError::UnsupportedModulus(t));
+        }
+        // Keeps `quotient * t + remainder < 2^63` below the field order (see `constrain_mod_t`).
+        if log2_ceil(Q as usize) > 62 {
+            return Err(VbfvError::UnsupportedModulus(Q));
+        }
+        let quotient = self.cb.add_virtual_target();
+        let remainder = self.cb.add_virtual_target();
+        self.cb.add_simple_generator(ReduceModTGenerator {
+            plaintext_modulus: t,
+            x: x.value,
+            quotient,
+            remainder,
+        });
+        self.constrain_mod_t(x, quotient, remainder, t);
+        Ok(AssignedValue::new_from_target_unchecked(remainder))
+    }
+
+    /// Constrains `x = quotient * t + remainder` with `0 <= remainder < t`.
+    /// `x` has at most `log2_ceil(Q)` bits, so `quotient` is range checked to the bits of
+    /// `(2^log2_ceil(Q) - 1) / t`, which keeps `quotient * t + remainder` below
+    /// `2^(log2_ceil(Q) + 1)`, hence the field order, and makes the decomposition unique.
+    pub(crate) fn constrain_mod_t(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        quotient: Target,
+        remainder: Target,
+        t: u64,
+    ) {
+        let x_bits = log2_ceil(Q as usize);
+        let quotient_max = ((1u128 << x_bits) - 1) / t as u128;
+        self.cb
+            .range_check(quotient, (128 - quotient_max.leading_zeros()) as usize);
+
+        self.range_check_below(remainder, t);
+
+        let t = F::from_canonical_u64(t);
+        let recomposed = self.cb.mul_const_add(t, quotient, remainder);
+        self.cb.connect(recomposed, x.value);
+    }
+
+    /// Decomposes a `\mathbb{Z}_Q` element into its base `B` digits `[digit_0, ..., digit_{l-1}]`,
+    /// least significant first, with `l` the smallest integer such that `B^l >= Q`.
+    /// The digits are witnessed and constrained by `x = \sum_i digit_i B^i` with
+    /// `0 <= digit_i < B`. `base` must lie in `[2, Q]` and `B^l` below `2^63`.
+    pub(crate) fn gadget_decompose(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        base: u64,
+    ) -> Result<Vec<AssignedValue<F, D, Q>>, VbfvError> {
+        if base < 2 || base > Q {
+            return Err(VbfvError::UnsupportedBase(base));
+        }
+        let mut l = 1;
+        let mut base_l = base as u128;
+        while base_l < Q as u128 {
+            l += 1;
+            base_l *= base as u128;
+        }
+        self.gadget_decompose_limbs(x, base, l)
+    }
+
+    /// Decomposes a `\mathbb{Z}_Q` element into `l` base `B` digits, least significant first, the
+    /// top one taking the remaining quotient as the limbs of a relinearization key or a GSW
+    /// ciphertext with `l = floor(log_B(Q))` do.
+    /// The digits are constrained by `constrain_gadget_digits`. `base` must lie in `[2, Q]` and
+    /// `B^{l-1}` times the bound of the top digit below `2^63`.
+    pub(crate) fn gadget_decompose_limbs(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        base: u64,
+        l: usize,
+    ) -> Result<Vec<AssignedValue<F, D, Q>>, VbfvError> {
+        if base < 2 || base > Q {
+            return Err(VbfvError::UnsupportedBase(base));
+        }
+        if l == 0 {
+            return Err(VbfvError::DimensionMismatch {
+                expected: 1,
+                actual: 0,
+            });
+        }
+        // Keeps `\sum_i digit_i B^i` below the field order (see `constrain_gadget_digits`).
+        match top_digit_bound::<Q>(base, l) {
+            Some((base_top, bound)) if base_top * bound as u128 <= 1 << 63 => {}
+            _ => return Err(VbfvError::UnsupportedBase(base)),
+        }
+        let digits = self.cb.add_virtual_targets(l);
+        self.cb.add_simple_generator(GadgetDecompositionGenerator {
+            base,
+            x: x.value,
+            digits: digits.clone(),
+        });
+        self.constrain_gadget_digits(x, &digits, base);
+        Ok(digits
+            .into_iter()
+            .map(AssignedValue::new_from_target_unchecked)
+            .collect_vec())
+    }
+
+    /// Constrains `x = \sum_i digit_i B^i` with `0 <= digit_i < B`, except the top digit, which is
+    /// below `floor((Q - 1) / B^{l-1}) + 1` as it takes the remaining quotient.
+    /// The sum is below `B^{l-1}` times that bound, hence the field order, so the digits are the
+    /// unique such representation of `x`.
+    fn constrain_gadget_digits(&mut self, x: AssignedValue<F, D, Q>, digits: &[Target], base: u64) {
+        let (_, top_bound) =
+            top_digit_bound::<Q>(base, digits.len()).expect("B^{l-1} overflows u128");
+        let mut recomposed = self.cb.zero();
+        for (i, digit) in digits.iter().enumerate() {
+            let bound = if i + 1 == digits.len() {
+                top_bound
+            } else {
+                base
+            };
+            self.range_check_below(*digit, bound);
+            let base_i = F::from_canonical_u64(base.pow(i as u32));
+            recomposed = self.cb.mul_const_add(base_i, *digit, recomposed);
+        }
+        self.cb.connect(recomposed, x.value);
+    }
+
+    // Constrains `0 <= x < bound`: `x < bound` iff both `x` and `bound - 1 - x` have
+    // `log2_ceil(bound)` bits.
+    fn range_check_below(&mut self, x: Target, bound: u64) {
+        let bits = log2_ceil(bound as usize);
+        self.cb.range_check(x, bits);
+        if !bound.is_power_of_two() {
+            let bound_minus_one = self.cb.constant(F::from_canonical_u64(bound - 1));
+            let slack = self.cb.sub(bound_minus_one, x);
+            self.cb.range_check(slack, bits);
+        }
+    }
+
+    /// Returns a boolean constrained to be `1` iff `x == y` in `\mathbb{Z}_Q`.
+    /// Both values are canonical representatives in `[0, Q)` and `Q` is below the field order, so
+    /// equality modulo `Q` coincides with equality of the underlying field elements.
+    pub(crate) fn is_equal(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        y: AssignedValue<F, D, Q>,
+    ) -> Result<BoolTarget, VbfvError> {
+        Ok(self.cb.is_equal(x.value, y.value))
+    }
+
+    /// Returns `x` if `cond` is `1` and `y` otherwise.
+    /// The result is one of two values already in `[0, Q)`, so it is not range checked again.
+    pub(crate) fn select(
+        &mut self,
+        cond: BoolTarget,
+        x: AssignedValue<F, D, Q>,
+        y: AssignedValue<F, D, Q>,
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        let result = self.cb.select(cond, x.value, y.value);
+        Ok(AssignedValue::new_from_target_unchecked(result))
+    }
+
+    /// Maps a `\mathbb{Z}_Q` element to its centered representative, `x` if
+    /// `x < Q - floor(Q/2)` and `x - Q` otherwise.
+    /// It is `[x + floor(Q/2)]_Q - floor(Q/2)`, so the shifted value is reduced as any sum and
+    /// the shift back needs no check.
+    pub(crate) fn center(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+    ) -> Result<AssignedCenteredValue<F, D, Q>, VbfvError> {
+        let half = AssignedCenteredValue::<F, D, Q>::HALF;
+        let shifted = self.add_with_constant(x, F::from_canonical_u64(half))?;
+        Ok(self.unshift(shifted))
+    }
+
+    /// Maps a centered representative back to the `\mathbb{Z}_Q` element in `[0, Q)`.
+    pub(crate) fn uncenter(
+        &mut self,
+        x: AssignedCenteredValue<F, D, Q>,
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        let half = AssignedCenteredValue::<F, D, Q>::HALF;
+        let shifted = self.shift(x);
+        if half == 0 {
+            return Ok(shifted);
+        }
+        self.add_with_constant(shifted, F::from_canonical_u64(Q - half))
+    }
+
+    /// Adds two centered values modulo `Q`, returning the centered representative of the sum.
+    pub(crate) fn add_centered(
+        &mut self,
+        x: AssignedCenteredValue<F, D, Q>,
+        y: AssignedCenteredValue<F, D, Q>,
+    ) -> Result<AssignedCenteredValue<F, D, Q>, VbfvError> {
+        // `[x + y + floor(Q/2)]_Q = [(x + floor(Q/2)) + (y + floor(Q/2)) - floor(Q/2)]_Q`
+        let half = AssignedCenteredValue::<F, D, Q>::HALF;
+        let (x, y) = (self.shift(x), self.shift(y));
+        let mut shifted = self.add(x, y)?;
+        if half != 0 {
+            shifted = self.add_with_constant(shifted, F::from_canonical_u64(Q - half))?;
+        }
+        Ok(self.unshift(shifted))
+    }
+
+    /// Subtracts two centered values modulo `Q`, returning the centered representative of the
+    /// difference.
+    pub(crate) fn sub_centered(
+        &mut self,
+        x: AssignedCenteredValue<F, D, Q>,
+        y: AssignedCenteredValue<F, D, Q>,
+    ) -> Result<AssignedCenteredValue<F, D, Q>, VbfvError> {
+        // `[x - y + floor(Q/2)]_Q = [(x + floor(Q/2)) - (y + floor(Q/2)) + floor(Q/2)]_Q`
+        let half = AssignedCenteredValue::<F, D, Q>::HALF;
+        let (x, y) = (self.shift(x), self.shift(y));
+        let mut shifted = self.sub(x, y)?;
+        if half != 0 {
+            shifted = self.add_with_constant(shifted, F::from_canonical_u64(half))?;
+        }
+        Ok(self.unshift(shifted))
+    }
+
+    /// Returns `x + floor(Q/2)`, which lies in `[0, Q)` for a valid centered value.
+    fn shift(&mut self, x: AssignedCenteredValue<F, D, Q>) -> AssignedValue<F, D, Q> {
+        let half = AssignedCenteredValue::<F, D, Q>::HALF;
+        let shifted = self.cb.add_const(x.value, F::from_canonical_u64(half));
+        AssignedValue::new_from_target_unchecked(shifted)
+    }
+
+    /// Returns `x - floor(Q/2)`, which is a valid centered value for `x` in `[0, Q)`.
+    fn unshift(&mut self, x: AssignedValue<F, D, Q>) -> AssignedCenteredValue<F, D, Q> {
+        let half = AssignedCenteredValue::<F, D, Q>::HALF;
+        let centered = self.cb.add_const(x.value, -F::from_canonical_u64(half));
+        AssignedCenteredValue::new_from_target_unchecked(centered)
+    }
+
+    /// Multiplies two `\mathbb{Z}_Q` elements.
+    /// `x * y` is computed natively in the field, so `Q^2` must be smaller than the field order;
+    /// larger moduli would need to base-B decompose `x` first.
     pub(crate) fn mul(
         &mut self,
         x: AssignedValue<F, D, Q>,
         y: AssignedValue<F, D, Q>,
-    ) -> Result<AssignedValue<F, D, Q>, Error> {
-        // base-B decompose `x`
-        todo!()
+    ) -> Result<AssignedValue<F, D, Q>, VbfvError> {
+        if (Q as u128) * (Q as u128) >= F::ORDER as u128 {
+            return Err(VbfvError::UnsupportedModulus(Q));
+        }
+        if let Some(result) = self.fold_constants(x, y, |x, y| x * y) {
+            return Ok(result);
+        }
+        let quotient = AssignedValue::new(&mut self.cb);
+        let op_kind = ArithmeticOpKind::Mul(x, y);
+        let arithmetic_ops_generator = ArithmeticOpsGenerator::new(quotient, op_kind);
+        self.cb.add_simple_generator(arithmetic_ops_generator);
+
+        let ring_modulus = F::from_canonical_u64(Q);
+        let one = F::ONE;
+        let neg_one = self.cb.neg_one();
+        let tmp = self.cb.mul(x.value, y.value);
+        let result = self
+            .cb
+            .arithmetic(ring_modulus, one, neg_one, quotient.value, tmp);
+        Ok(AssignedValue::new_from_target(&mut self.cb, result))
+    }
+}
+
+// Returns `B^{l-1}` and the bound of the top of `l` base `B` digits of a `\mathbb{Z}_Q` element,
+// `floor((Q - 1) / B^{l-1}) + 1`, which is at most `B` if `B^l >= Q`, or `None` if `B^{l-1}`
+// overflows.
+fn top_digit_bound<const Q: u64>(base: u64, l: usize) -> Option<(u128, u64)> {
+    let base_top = (base as u128).checked_pow(l as u32 - 1)?;
+    Some((base_top, ((Q - 1) as u128 / base_top) as u64 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Error, Result};
+    use plonky2::{
+        field::{
+            goldilocks_field::GoldilocksField,
+            types::{Field, PrimeField64},
+        },
+        iop::witness::{PartialWitness, WitnessWrite},
+        plonk::{
+            circuit_builder::CircuitBuilder, circuit_data::CircuitConfig,
+            config::PoseidonGoldilocksConfig,
+        },
+        util::log_floor,
+    };
+
+    use super::ArithmeticChip;
+    use crate::vbfv::{
+        assert_unprovable,
+        assigned::{AssignedCenteredValue, AssignedValue},
+        is_provable, VbfvError,
+    };
+
+    #[test]
+    fn test_add_with_constant() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        // (x, constant): no wrap-around, `x + constant = Q` and `x + constant = 2Q - 2`
+        let cases = [(5, 7), (Q - 10, 10), (Q - 1, Q - 1)];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+
+        let mut xs = vec![];
+        for (_, constant) in cases.iter() {
+            let x = AssignedValue::new(&mut arithmetic_chip.cb);
+            let z = arithmetic_chip.add_with_constant(x, F::from_canonical_u64(*constant))?;
+            z.register_as_public_input(&mut arithmetic_chip.cb);
+            xs.push(x);
+        }
+
+        let mut pw = PartialWitness::new();
+        for (x, (value, _)) in xs.iter().zip(cases.iter()) {
+            x.assign(&mut pw, F::from_canonical_u64(*value))?;
+        }
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        for (actual, (x, constant)) in proof.public_inputs.iter().zip(cases.iter()) {
+            assert_eq!(actual.to_canonical_u64(), (x + constant) % Q);
+        }
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_sub_with_constant_and_neg() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        // (x, constant): no wrap-around, `x = constant`, and `x < constant` wrapping by `+Q`
+        let cases = [
+            (7, 5),
+            (10, 10),
+            (0, 1),
+            (5, 7),
+            (0, Q - 1),
+            (Q - 2, Q - 1),
+            (3, 0),
+        ];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+
+        let mut xs = vec![];
+        for (_, constant) in cases.iter() {
+            let x = AssignedValue::new(&mut arithmetic_chip.cb);
+            let z = arithmetic_chip.sub_with_constant(x, F::from_canonical_u64(*constant))?;
+            z.register_as_public_input(&mut arithmetic_chip.cb);
+            let neg_x = arithmetic_chip.neg(x)?;
+            neg_x.register_as_public_input(&mut arithmetic_chip.cb);
+            xs.push(x);
+        }
+
+        let mut pw = PartialWitness::new();
+        for (x, (value, _)) in xs.iter().zip(cases.iter()) {
+            x.assign(&mut pw, F::from_canonical_u64(*value))?;
+        }
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        for (actual, (x, constant)) in proof.public_inputs.chunks(2).zip(cases.iter()) {
+            assert_eq!(actual[0].to_canonical_u64(), (x + Q - constant) % Q);
+            assert_eq!(actual[1].to_canonical_u64(), (Q - x) % Q);
+        }
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_constant_operands_are_folded() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        // `x + y` and `x * y` wrap around, `y - x` wraps by `+Q`
+        let (x, y) = (Q - 2, 5);
+        let expected = [(x + y) % Q, (x + Q - y) % Q, (y + Q - x) % Q, x * y % Q];
+
+        let ops = |chip: &mut ArithmeticChip<F, D, Q>, x, y| -> Result<(), Error> {
+            let results = [
+                chip.add(x, y)?,
+                chip.sub(x, y)?,
+                chip.sub(y, x)?,
+                chip.mul(x, y)?,
+            ];
+            for result in results {
+                result.register_as_public_input(&mut chip.cb);
+            }
+            Ok(())
+        };
+        let constant = |chip: &mut ArithmeticChip<F, D, Q>, value| {
+            AssignedValue::new_from_target_unchecked(chip.cb.constant(F::from_canonical_u64(value)))
+        };
+        let config = CircuitConfig::standard_recursion_config();
+
+        // The operands and results as constants, as a baseline
+        let mut chip = ArithmeticChip::<F, D, Q>::new(CircuitBuilder::new(config.clone()));
+        constant(&mut chip, x);
+        constant(&mut chip, y);
+        for value in expected {
+            constant(&mut chip, value).register_as_public_input(&mut chip.cb);
+        }
+        let baseline = chip.cb.build::<C>();
+
+        let mut chip = ArithmeticChip::<F, D, Q>::new(CircuitBuilder::new(config.clone()));
+        let (x_target, y_target) = (constant(&mut chip, x), constant(&mut chip, y));
+        ops(&mut chip, x_target, y_target)?;
+        let folded = chip.cb.build::<C>();
+        assert_eq!(
+            folded.prover_only.generators.len(),
+            baseline.prover_only.generators.len()
+        );
+        let proof = folded.prove(PartialWitness::new())?;
+        assert_eq!(proof.public_inputs, expected.map(F::from_canonical_u64));
+        folded.verify(proof)?;
+
+        // A single non-constant operand still needs the generators
+        let mut chip = ArithmeticChip::<F, D, Q>::new(CircuitBuilder::new(config));
+        let x_target = AssignedValue::new(&mut chip.cb);
+        let y_target = constant(&mut chip, y);
+        ops(&mut chip, x_target, y_target)?;
+        let data = chip.cb.build::<C>();
+        assert!(data.prover_only.generators.len() > baseline.prover_only.generators.len());
+        let mut pw = PartialWitness::new();
+        x_target.assign(&mut pw, F::from_canonical_u64(x))?;
+        let proof = data.prove(pw)?;
+        assert_eq!(proof.public_inputs, expected.map(F::from_canonical_u64));
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_reduce_mod_t() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        // (x, t): `x < t`, `x = t`, a multiple of `t`, `t = 1` and the largest `x` and `t`
+        let cases = [
+            (3, 4),
+            (4, 4),
+            (25, 5),
+            (17, 1),
+            (Q - 1, 7),
+            (Q - 1, Q - 1),
+            (1000, 3),
+        ];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+
+        let mut xs = vec![];
+        for (_, t) in cases.iter() {
+            let x = AssignedValue::new(&mut arithmetic_chip.cb);
+            let r = arithmetic_chip.reduce_mod_t(x, *t)?;
+            r.register_as_public_input(&mut arithmetic_chip.cb);
+            xs.push(x);
+        }
+        let x = AssignedValue::new(&mut arithmetic_chip.cb);
+        assert!(arithmetic_chip.reduce_mod_t(x, 0).is_err());
+        assert!(arithmetic_chip.reduce_mod_t(x, Q).is_err());
+        arithmetic_chip.cb.register_public_input(x.value);
+
+        let mut pw = PartialWitness::new();
+        for (x, (value, _)) in xs.iter().zip(cases.iter()) {
+            x.assign(&mut pw, F::from_canonical_u64(*value))?;
+        }
+        x.assign(&mut pw, F::ZERO)?;
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        for (actual, (x, t)) in proof.public_inputs.iter().zip(cases.iter()) {
+            assert_eq!(actual.to_canonical_u64(), x % t);
+        }
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_reduce_mod_t_rejects_large_remainder() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let t = 4;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+
+        let x = AssignedValue::new(&mut arithmetic_chip.cb);
+        let quotient = arithmetic_chip.cb.add_virtual_target();
+        let remainder = arithmetic_chip.cb.add_virtual_target();
+        arithmetic_chip.constrain_mod_t(x, quotient, remainder, t);
+
+        // `10 = 1 * 4 + 6` recomposes `x`, but `6 >= t`
+        let mut pw = PartialWitness::new();
+        x.assign(&mut pw, F::from_canonical_u64(10))?;
+        pw.set_target(quotient, F::ONE)?;
+        pw.set_target(remainder, F::from_canonical_u64(6))?;
+
+        let data = arithmetic_chip.cb.build::<C>();
+        assert_unprovable(&data, pw);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gadget_decompose() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        // (x, base): powers of two, a base that is not, `base = Q` and the largest `x`
+        let cases = [
+            (3000, 2),
+            (Q - 1, 16),
+            (1234, 10),
+            (Q - 1, 7),
+            (Q - 1, Q),
+            (0, 3),
+        ];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+
+        let mut xs = vec![];
+        for (_, base) in cases.iter() {
+            let x = AssignedValue::new(&mut arithmetic_chip.cb);
+            let digits = arithmetic_chip.gadget_decompose(x, *base)?;
+            for digit in digits.iter() {
+                digit.register_as_public_input(&mut arithmetic_chip.cb);
+            }
+            xs.push(x);
+        }
+        let x = AssignedValue::new(&mut arithmetic_chip.cb);
+        for base in [0, 1, Q + 1] {
+            assert!(matches!(
+                arithmetic_chip.gadget_decompose(x, base),
+                Err(VbfvError::UnsupportedBase(actual)) if actual == base
+            ));
+        }
+        arithmetic_chip.cb.register_public_input(x.value);
+
+        let mut pw = PartialWitness::new();
+        for (x, (value, _)) in xs.iter().zip(cases.iter()) {
+            x.assign(&mut pw, F::from_canonical_u64(*value))?;
+        }
+        x.assign(&mut pw, F::ZERO)?;
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        let mut public_inputs = proof.public_inputs.iter().map(|x| x.to_canonical_u64());
+        for (x, base) in cases.iter() {
+            let (mut recomposed, mut base_i) = (0, 1);
+            while base_i < Q {
+                let digit = public_inputs.next().unwrap();
+                assert!(digit < *base);
+                recomposed += digit * base_i;
+                base_i *= base;
+            }
+            assert_eq!(recomposed, *x);
+        }
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_gadget_decompose_limbs() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        // (x, base, digits): `l = floor(log_B(Q))` limbs, the top one taking the quotient
+        let cases = [
+            (Q - 1, 10, vec![8, 2, 33]),
+            (Q - 1, 16, vec![0, 208]),
+            (1234, 7, vec![2, 1, 4, 3]),
+        ];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+        let mut pw = PartialWitness::new();
+        for (value, base, _) in cases.iter() {
+            let x = AssignedValue::new(&mut arithmetic_chip.cb);
+            x.assign(&mut pw, F::from_canonical_u64(*value))?;
+            let l = log_floor(Q, *base);
+            for digit in arithmetic_chip.gadget_decompose_limbs(x, *base, l)? {
+                digit.register_as_public_input(&mut arithmetic_chip.cb);
+            }
+        }
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        let digits = cases.iter().flat_map(|(_, _, digits)| digits.clone());
+        assert!(proof
+            .public_inputs
+            .iter()
+            .map(|x| x.to_canonical_u64())
+            .eq(digits));
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_gadget_decompose_rejects_large_digit() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let base = 10;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+
+        let x = AssignedValue::new(&mut arithmetic_chip.cb);
+        let digits = arithmetic_chip.cb.add_virtual_targets(4);
+        arithmetic_chip.constrain_gadget_digits(x, &digits, base);
+
+        // `25 = 15 + 1 * 10` recomposes `x`, but `15 >= base`
+        let mut pw = PartialWitness::new();
+        x.assign(&mut pw, F::from_canonical_u64(25))?;
+        for (digit, value) in digits.iter().zip([15, 1, 0, 0]) {
+            pw.set_target(*digit, F::from_canonical_u64(value))?;
+        }
+
+        // Witness generation panics when `base - 1 - 15` does not fit in 4 bits
+        let data = arithmetic_chip.cb.build::<C>();
+        assert_unprovable(&data, pw);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_equal_and_select() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        // (x, y, cond): equal and unequal pairs, including both ends of `[0, Q)`
+        let cases = [
+            (5, 5, true),
+            (5, 7, true),
+            (5, 7, false),
+            (0, Q - 1, false),
+            (Q - 1, Q - 1, false),
+            (0, 0, true),
+        ];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+
+        let mut inputs = vec![];
+        for _ in cases.iter() {
+            let x = AssignedValue::new(&mut arithmetic_chip.cb);
+            let y = AssignedValue::new(&mut arithmetic_chip.cb);
+            let cond = arithmetic_chip.cb.add_virtual_bool_target_safe();
+            let eq = arithmetic_chip.is_equal(x, y)?;
+            let selected = arithmetic_chip.select(cond, x, y)?;
+            arithmetic_chip.cb.register_public_input(eq.target);
+            selected.register_as_public_input(&mut arithmetic_chip.cb);
+            inputs.push((x, y, cond));
+        }
+
+        let mut pw = PartialWitness::new();
+        for ((x, y, cond), (x_value, y_value, cond_value)) in inputs.iter().zip(cases.iter()) {
+            x.assign(&mut pw, F::from_canonical_u64(*x_value))?;
+            y.assign(&mut pw, F::from_canonical_u64(*y_value))?;
+            pw.set_bool_target(*cond, *cond_value)?;
+        }
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        for (actual, (x, y, cond)) in proof.public_inputs.chunks(2).zip(cases.iter()) {
+            assert_eq!(actual[0].to_canonical_u64(), (x == y) as u64);
+            assert_eq!(actual[1].to_canonical_u64(), if *cond { *x } else { *y });
+        }
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_centered_round_trip() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let half = Q / 2;
+        let centered = |x: u64| {
+            if x < Q - half {
+                x as i64
+            } else {
+                x as i64 - Q as i64
+            }
+        };
+        // Values around `Q/2`, where the centered representative changes sign, and both ends
+        let values = [0, 1, half - 1, half, half + 1, half + 2, Q - 1];
+        let pairs = [
+            (half, 1),
+            (half, half),
+            (Q - 1, 1),
+            (0, Q - 1),
+            (half + 1, half),
+        ];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+
+        let mut xs = vec![];
+        for _ in values.iter() {
+            let x = AssignedValue::new(&mut arithmetic_chip.cb);
+            let c = arithmetic_chip.center(x)?;
+            let y = arithmetic_chip.uncenter(c)?;
+            c.register_as_public_input(&mut arithmetic_chip.cb);
+            y.register_as_public_input(&mut arithmetic_chip.cb);
+            xs.push(x);
+        }
+        let mut operands = vec![];
+        for _ in pairs.iter() {
+            let target = arithmetic_chip.cb.add_virtual_target();
+            let x = AssignedCenteredValue::new_from_target(&mut arithmetic_chip.cb, target);
+            let target = arithmetic_chip.cb.add_virtual_target();
+            let y = AssignedCenteredValue::new_from_target(&mut arithmetic_chip.cb, target);
+            let sum = arithmetic_chip.add_centered(x, y)?;
+            let difference = arithmetic_chip.sub_centered(x, y)?;
+            sum.register_as_public_input(&mut arithmetic_chip.cb);
+            difference.register_as_public_input(&mut arithmetic_chip.cb);
+            operands.push((x, y));
+        }
+
+        let mut pw = PartialWitness::new();
+        for (x, value) in xs.iter().zip(values.iter()) {
+            x.assign(&mut pw, F::from_canonical_u64(*value))?;
+        }
+        for ((x, y), (x_value, y_value)) in operands.iter().zip(pairs.iter()) {
+            pw.set_target(x.value, F::from_noncanonical_i64(centered(*x_value)))?;
+            pw.set_target(y.value, F::from_noncanonical_i64(centered(*y_value)))?;
+        }
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        let (round_trips, arithmetic) = proof.public_inputs.split_at(2 * values.len());
+        for (actual, x) in round_trips.chunks(2).zip(values.iter()) {
+            assert_eq!(actual[0], F::from_noncanonical_i64(centered(*x)));
+            assert_eq!(actual[1].to_canonical_u64(), *x);
+        }
+        for (actual, (x, y)) in arithmetic.chunks(2).zip(pairs.iter()) {
+            let sum = centered((x + y) % Q);
+            let difference = centered((x + Q - y) % Q);
+            assert_eq!(actual[0], F::from_noncanonical_i64(sum));
+            assert_eq!(actual[1], F::from_noncanonical_i64(difference));
+        }
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_centered_range() -> Result<(), Error> {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let half = (Q / 2) as i64;
+        // `Q = 3329` has the centered domain `[-1664, 1664]`
+        for (value, in_range) in [
+            (-half, true),
+            (half, true),
+            (-half - 1, false),
+            (half + 1, false),
+        ] {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let target = builder.add_virtual_target();
+            AssignedCenteredValue::<F, D, Q>::new_from_target(&mut builder, target);
+
+            let mut pw = PartialWitness::new();
+            pw.set_target(target, F::from_noncanonical_i64(value))?;
+
+            // Witness generation panics when the shifted value does not fit in `[0, Q)`
+            let data = builder.build::<C>();
+            assert_eq!(is_provable(&data, pw), in_range, "value {value}");
+        }
+        Ok(())
     }
 }
@@ -1,9 +1,9 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
-use anyhow::Error;
 use itertools::Itertools;
 use plonky2::{
     field::extension::Extendable,
+    gates::lookup_table::LookupTable,
     hash::hash_types::RichField,
     iop::{
         target::Target,
@@ -13,10 +13,10 @@ use plonky2::{
     util::{log2_ceil, log2_strict, log_floor},
 };
 
-use super::{arithmetic_chip::ArithmeticChip, ntt_chip::NTTChip};
+use super::{arithmetic_chip::ArithmeticChip, error::VbfvError, ntt_chip::NTTChip};
 use crate::{
-    bfv::{Ciphertext, RelinearizationKey1},
-    vbfv::ntt_forward,
+    bfv::{Ciphertext, GswCiphertext, Plaintext, RelinearizationKey1},
+    vbfv::{ntt_forward, supports_ntt},
 };
 
 /// `AssignedValue` is assigned value of mod `Q` element
@@ -29,7 +29,43 @@ pub(crate) struct AssignedValue<F: RichField + Extendable<D>, const D: usize, co
 impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> AssignedValue<F, D, Q> {
     pub fn new(cb: &mut CircuitBuilder<F, D>) -> Self {
         let value = cb.add_virtual_target();
-        cb.range_check(value, log2_ceil(Q as usize));
+        Self::range_check_modulus(cb, value);
+        Self {
+            _marker: PhantomData,
+            value,
+        }
+    }
+
+    /// Allocates `count` values constrained to `[0, Q)` by lookups into a single table over
+    /// `[0, Q)`, which is shared by all values of the same modulus in the circuit and is much
+    /// cheaper than a bit decomposition per value. Falls back to `new` when `Q` does not fit in
+    /// `u16`, the input type of plonky2 lookup tables.
+    pub fn new_batch(cb: &mut CircuitBuilder<F, D>, count: usize) -> Vec<Self> {
+        if Q > u16::MAX as u64 {
+            return (0..count).map(|_| Self::new(cb)).collect_vec();
+        }
+        if count == 0 {
+            return vec![];
+        }
+        let table: LookupTable = Arc::new((0..Q).map(|i| (i as u16, i as u16)).collect_vec());
+        let lut_index = cb.add_lookup_table_from_pairs(table);
+        (0..count)
+            .map(|_| {
+                let value = cb.add_virtual_target();
+                cb.add_lookup_from_index(value, lut_index);
+                Self {
+                    _marker: PhantomData,
+                    value,
+                }
+            })
+            .collect_vec()
+    }
+
+    /// Allocates a value range checked to `[0, 2^bits)`, for elements known to be small.
+    pub fn new_with_bits(cb: &mut CircuitBuilder<F, D>, bits: usize) -> Self {
+        assert!(1 << bits <= Q);
+        let value = cb.add_virtual_target();
+        cb.range_check(value, bits);
         Self {
             _marker: PhantomData,
             value,
@@ -37,7 +73,33 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> AssignedValue<F
     }
 
     pub fn new_from_target(cb: &mut CircuitBuilder<F, D>, target: Target) -> Self {
-        cb.range_check(target, log2_ceil(Q as usize));
+        Self::range_check_modulus(cb, target);
+        Self {
+            _marker: PhantomData,
+            value: target,
+        }
+    }
+
+    /// Constrains `target < Q`. A `log2_ceil(Q)`-bit decomposition alone still admits
+    /// `[Q, 2^log2_ceil(Q))` when `Q` is not a power of two, so `Q - 1 - target` is decomposed as
+    /// well: it wraps around the field, far beyond `log2_ceil(Q)` bits, exactly when `target >= Q`.
+    fn range_check_modulus(cb: &mut CircuitBuilder<F, D>, target: Target) {
+        let bits = log2_ceil(Q as usize);
+        cb.range_check(target, bits);
+        if !Q.is_power_of_two() {
+            let q_minus_one = cb.constant(F::from_canonical_u64(Q - 1));
+            let slack = cb.sub(q_minus_one, target);
+            cb.range_check(slack, bits);
+        }
+    }
+
+    /// Wraps `target` without a range check.
+    ///
+    /// # Safety note
+    /// The caller guarantees that `target < Q` is already implied by other constraints, e.g. a
+    /// constant or a boolean target. Wrapping an unconstrained target lets a malicious prover
+    /// pick any field element, which breaks the soundness of every chip consuming it.
+    pub fn new_from_target_unchecked(target: Target) -> Self {
         Self {
             _marker: PhantomData,
             value: target,
@@ -48,43 +110,184 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> AssignedValue<F
         cb.register_public_input(self.value);
     }
 
-    pub fn assign(&self, pw: &mut PartialWitness<F>, value: F) -> Result<(), Error> {
+    pub fn assign(&self, pw: &mut PartialWitness<F>, value: F) -> Result<(), VbfvError> {
+        if value.to_canonical_u64() >= Q {
+            return Err(VbfvError::RangeCheckFailed {
+                value: value.to_canonical_u64(),
+                bound: Q,
+            });
+        }
         pw.set_target(self.value, value)
+            .map_err(VbfvError::WitnessAssign)
     }
 }
 
-/// `AssignedPoly` is assigned value of polynomial inside `R_Q = \mathbb{Z}_Q[X]/(X^N+1)`
-/// where `X^N+1` is `2N`-th cyclotomic polynomial(N is power-of-two).
+/// `AssignedCenteredValue` is assigned value of mod `Q` element in its centered representation
+/// `c \in [-floor(Q/2), Q - floor(Q/2))`, i.e. `[-Q/2, Q/2)` for even `Q`, stored as the field
+/// element `c mod p`.
+///
+/// It is valid iff `c + floor(Q/2)` is the canonical representative of `c + floor(Q/2)` in
+/// `[0, Q)`, which is how it is range checked and how `ArithmeticChip` operates on it.
 #[derive(Copy, Clone, Debug)]
-struct AssignedPoly<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64> {
+pub(crate) struct AssignedCenteredValue<F: RichField + Extendable<D>, const D: usize, const Q: u64>
+{
+    _marker: PhantomData<F>,
+    pub value: Target,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> AssignedCenteredValue<F, D, Q> {
+    /// `floor(Q/2)`, the shift between the centered and the canonical `[0, Q)` domains.
+    pub const HALF: u64 = Q / 2;
+
+    /// Wraps `target`, constrained to the centered domain by range checking
+    /// `target + floor(Q/2)` to `[0, Q)`.
+    pub fn new_from_target(cb: &mut CircuitBuilder<F, D>, target: Target) -> Self {
+        let shifted = cb.add_const(target, F::from_canonical_u64(Self::HALF));
+        AssignedValue::<F, D, Q>::new_from_target(cb, shifted);
+        Self::new_from_target_unchecked(target)
+    }
+
+    /// Wraps `target` without a range check, see `AssignedValue::new_from_target_unchecked`.
+    pub fn new_from_target_unchecked(target: Target) -> Self {
+        Self {
+            _marker: PhantomData,
+            value: target,
+        }
+    }
+
+    pub fn register_as_public_input(&self, cb: &mut CircuitBuilder<F, D>) {
+        cb.register_public_input(self.value);
+    }
+}
+
+/// `AssignedCoeffPoly` is assigned value of polynomial inside `R_Q = \mathbb{Z}_Q[X]/(X^N+1)`
+/// in coefficients form, where `X^N+1` is `2N`-th cyclotomic polynomial(N is power-of-two).
+/// It is used for moduli where `X^N+1` does not fully split, e.g. power-of-two `Q`.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct AssignedCoeffPoly<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    const N: usize,
+    const Q: u64,
+> {
     _marker: PhantomData<F>,
     coeffs: [AssignedValue<F, D, Q>; N],
 }
 
 impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
-    AssignedPoly<F, D, N, Q>
+    AssignedCoeffPoly<F, D, N, Q>
 {
-    fn new(cb: &mut CircuitBuilder<F, D>) -> Result<Self, Error> {
-        Ok(AssignedPoly {
+    pub fn new(cb: &mut CircuitBuilder<F, D>) -> Self {
+        Self {
             _marker: PhantomData,
-            coeffs: [(); N].map(|_| AssignedValue::new(cb)),
-        })
+            coeffs: AssignedValue::new_batch(cb, N).try_into().unwrap(),
+        }
+    }
+
+    pub fn new_from_values(values: [AssignedValue<F, D, Q>; N]) -> Self {
+        Self {
+            _marker: PhantomData,
+            coeffs: values,
+        }
     }
 
-    fn coeff_targets(&self) -> Vec<Target> {
-        self.coeffs.iter().map(|coeff| coeff.value).collect_vec()
+    pub fn coeffs(&self) -> &[AssignedValue<F, D, Q>; N] {
+        &self.coeffs
     }
 
-    fn assign(&self, pw: &mut PartialWitness<F>, coeffs: &Vec<i64>) -> Result<(), Error> {
+    pub fn assign(&self, pw: &mut PartialWitness<F>, coeffs: &[i64]) -> Result<(), VbfvError> {
+        self.assign_field(
+            pw,
+            &coeffs
+                .iter()
+                .map(|coeff| F::from_canonical_i64(*coeff))
+                .collect_vec(),
+        )
+    }
+
+    /// Assigns coefficients that are already field elements, without going through `i64`, so
+    /// that values `>= 2^63` are kept for moduli `Q` beyond `2^63`
+    pub fn assign_field(&self, pw: &mut PartialWitness<F>, coeffs: &[F]) -> Result<(), VbfvError> {
         // sanity check for the input
         assert_eq!(coeffs.len(), N);
         self.coeffs
             .iter()
             .zip(coeffs)
-            .map(|(tcoeff, coeff)| tcoeff.assign(pw, F::from_canonical_i64(*coeff)))
-            .collect::<Result<Vec<()>, Error>>()?;
+            .map(|(tcoeff, coeff)| tcoeff.assign(pw, *coeff))
+            .collect::<Result<Vec<()>, VbfvError>>()?;
         Ok(())
     }
+
+    pub fn add(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        other: AssignedCoeffPoly<F, D, N, Q>,
+    ) -> Result<AssignedCoeffPoly<F, D, N, Q>, VbfvError> {
+        let result = self
+            .coeffs()
+            .iter()
+            .zip_eq(other.coeffs().iter())
+            .map(|(coeff0, coeff1)| arithmetic_chip.add(*coeff0, *coeff1))
+            .collect::<Result<Vec<AssignedValue<F, D, Q>>, VbfvError>>()?;
+        Ok(AssignedCoeffPoly::new_from_values(
+            result.try_into().unwrap(),
+        ))
+    }
+
+    pub fn sub(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        other: AssignedCoeffPoly<F, D, N, Q>,
+    ) -> Result<AssignedCoeffPoly<F, D, N, Q>, VbfvError> {
+        let result = self
+            .coeffs()
+            .iter()
+            .zip_eq(other.coeffs().iter())
+            .map(|(coeff0, coeff1)| arithmetic_chip.sub(*coeff0, *coeff1))
+            .collect::<Result<Vec<AssignedValue<F, D, Q>>, VbfvError>>()?;
+        Ok(AssignedCoeffPoly::new_from_values(
+            result.try_into().unwrap(),
+        ))
+    }
+
+    /// Negates each coefficient by multiplying it with `Q-1`, i.e. `-1` in `\mathbb{Z}_Q`
+    pub fn neg(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+    ) -> Result<AssignedCoeffPoly<F, D, N, Q>, VbfvError> {
+        let result = self
+            .coeffs()
+            .iter()
+            .map(|coeff| arithmetic_chip.mul_with_constant(*coeff, F::from_canonical_u64(Q - 1)))
+            .collect::<Result<Vec<AssignedValue<F, D, Q>>, VbfvError>>()?;
+        Ok(AssignedCoeffPoly::new_from_values(
+            result.try_into().unwrap(),
+        ))
+    }
+
+    /// Negacyclic schoolbook multiplication: `a_i * b_j` is added to the coefficient of
+    /// `X^{i+j}` if `i + j < N` and subtracted from that of `X^{i+j-N}` otherwise, since
+    /// `X^N = -1`. Costs `N^2` multiplications.
+    pub fn mul(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        other: AssignedCoeffPoly<F, D, N, Q>,
+    ) -> Result<AssignedCoeffPoly<F, D, N, Q>, VbfvError> {
+        let zero = arithmetic_chip.cb.zero();
+        let mut result = [AssignedValue::new_from_target_unchecked(zero); N];
+        for (i, coeff0) in self.coeffs().iter().enumerate() {
+            for (j, coeff1) in other.coeffs().iter().enumerate() {
+                let product = arithmetic_chip.mul(*coeff0, *coeff1)?;
+                let k = (i + j) % N;
+                result[k] = if i + j < N {
+                    arithmetic_chip.add(result[k], product)?
+                } else {
+                    arithmetic_chip.sub(result[k], product)?
+                };
+            }
+        }
+        Ok(AssignedCoeffPoly::new_from_values(result))
+    }
 }
 
 /// `AssignedNTTPoly` is assigned value of polynomial inside `R_Q = \mathbb{Z}_Q[X]/(X^N+1)`
@@ -105,10 +308,10 @@ pub(crate) struct AssignedNTTPoly<
 impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
     AssignedNTTPoly<F, D, N, Q>
 {
-    fn new(cb: &mut CircuitBuilder<F, D>) -> Self {
+    pub fn new(cb: &mut CircuitBuilder<F, D>) -> Self {
         Self {
             _marker: PhantomData,
-            evals: [(); N].map(|_| AssignedValue::new(cb)),
+            evals: AssignedValue::new_batch(cb, N).try_into().unwrap(),
         }
     }
 
@@ -131,36 +334,56 @@ impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
     }
 
     /// Converts polynomial in coefficients form into NTT form and then assign
-    fn assign(&self, pw: &mut PartialWitness<F>, poly_coeffs: &Vec<i64>) -> Result<(), Error> {
-        let evals = ntt_forward::<F, D, Q>(
+    pub fn assign(&self, pw: &mut PartialWitness<F>, poly_coeffs: &[i64]) -> Result<(), VbfvError> {
+        self.assign_field(
+            pw,
             &poly_coeffs
                 .iter()
                 .map(|coeff| F::from_canonical_i64(*coeff))
                 .collect_vec(),
-        );
-        let evals = evals
-            .iter()
-            .map(|eval| F::from_canonical_u64(eval.to_canonical_u64()))
-            .collect_vec();
+        )
+    }
+
+    /// Assigns evaluations that are already in NTT form
+    pub fn assign_evals(&self, pw: &mut PartialWitness<F>, evals: &[F]) -> Result<(), VbfvError> {
+        if evals.len() != N {
+            return Err(VbfvError::DimensionMismatch {
+                expected: N,
+                actual: evals.len(),
+            });
+        }
         self.evals
             .iter()
             .zip(evals)
-            .map(|(teval, eval)| teval.assign(pw, eval))
-            .collect::<Result<Vec<()>, Error>>()?;
-        Ok(())
+            .try_for_each(|(teval, eval)| teval.assign(pw, *eval))
+    }
+
+    /// Same as `assign`, for coefficients that are already field elements
+    pub fn assign_field(
+        &self,
+        pw: &mut PartialWitness<F>,
+        poly_coeffs: &[F],
+    ) -> Result<(), VbfvError> {
+        if poly_coeffs.len() != N {
+            return Err(VbfvError::DimensionMismatch {
+                expected: N,
+                actual: poly_coeffs.len(),
+            });
+        }
+        self.assign_evals(pw, &ntt_forward::<F, D, Q>(poly_coeffs))
     }
 
     pub fn add(
         &self,
         arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
         other: AssignedNTTPoly<F, D, N, Q>,
-    ) -> Result<AssignedNTTPoly<F, D, N, Q>, Error> {
+    ) -> Result<AssignedNTTPoly<F, D, N, Q>, VbfvError> {
         let result = self
             .evals()
             .iter()
             .zip_eq(other.evals().iter())
             .map(|(eval0, eval1)| arithmetic_chip.add(*eval0, *eval1))
-            .collect::<Result<Vec<AssignedValue<F, D, Q>>, Error>>()?;
+            .collect::<Result<Vec<AssignedValue<F, D, Q>>, VbfvError>>()?;
         Ok(AssignedNTTPoly::new_from_values(result.try_into().unwrap()))
     }
 
@@ -168,18 +391,188 @@ impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
         &self,
         arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
         other: AssignedNTTPoly<F, D, N, Q>,
-    ) -> Result<AssignedNTTPoly<F, D, N, Q>, Error> {
+    ) -> Result<AssignedNTTPoly<F, D, N, Q>, VbfvError> {
         let result = self
             .evals()
             .iter()
             .zip_eq(other.evals().iter())
             .map(|(eval0, eval1)| arithmetic_chip.mul(*eval0, *eval1))
-            .collect::<Result<Vec<AssignedValue<F, D, Q>>, Error>>()?;
+            .collect::<Result<Vec<AssignedValue<F, D, Q>>, VbfvError>>()?;
+        Ok(AssignedNTTPoly::new_from_values(result.try_into().unwrap()))
+    }
+
+    pub fn sub(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        other: AssignedNTTPoly<F, D, N, Q>,
+    ) -> Result<AssignedNTTPoly<F, D, N, Q>, VbfvError> {
+        let result = self
+            .evals()
+            .iter()
+            .zip_eq(other.evals().iter())
+            .map(|(eval0, eval1)| arithmetic_chip.sub(*eval0, *eval1))
+            .collect::<Result<Vec<AssignedValue<F, D, Q>>, VbfvError>>()?;
         Ok(AssignedNTTPoly::new_from_values(result.try_into().unwrap()))
     }
+
+    /// Negates each evaluation by multiplying it with `Q-1`, i.e. `-1` in `\mathbb{Z}_Q`
+    pub fn neg(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+    ) -> Result<AssignedNTTPoly<F, D, N, Q>, VbfvError> {
+        self.scalar_mul(arithmetic_chip, F::from_canonical_u64(Q - 1))
+    }
+
+    /// Multiplies each evaluation by a public scalar `c`
+    pub fn scalar_mul(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        c: F,
+    ) -> Result<AssignedNTTPoly<F, D, N, Q>, VbfvError> {
+        let result = self
+            .evals()
+            .iter()
+            .map(|eval| arithmetic_chip.mul_with_constant(*eval, c))
+            .collect::<Result<Vec<AssignedValue<F, D, Q>>, VbfvError>>()?;
+        Ok(AssignedNTTPoly::new_from_values(result.try_into().unwrap()))
+    }
+
+    /// Computes `sum_i scalars[i] * polys[i]` for public scalars, e.g. to combine key-switching
+    /// key components with a gadget vector
+    pub fn inner_product(
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        polys: &[AssignedNTTPoly<F, D, N, Q>],
+        scalars: &[F],
+    ) -> Result<AssignedNTTPoly<F, D, N, Q>, VbfvError> {
+        if polys.len() != scalars.len() {
+            return Err(VbfvError::DimensionMismatch {
+                expected: polys.len(),
+                actual: scalars.len(),
+            });
+        }
+        let Some((first, rest)) = polys.split_first() else {
+            let zero = arithmetic_chip.cb.zero();
+            let zero = AssignedValue::new_from_target_unchecked(zero);
+            return Ok(AssignedNTTPoly::new_from_values([zero; N]));
+        };
+        let mut acc = first.scalar_mul(arithmetic_chip, scalars[0])?;
+        for (poly, scalar) in rest.iter().zip(&scalars[1..]) {
+            let term = poly.scalar_mul(arithmetic_chip, *scalar)?;
+            acc = acc.add(arithmetic_chip, term)?;
+        }
+        Ok(acc)
+    }
+}
+
+/// `AssignedPoly` is assigned value of polynomial inside `R_Q`, in the representation `Q`
+/// supports: NTT form if `X^N+1` fully splits modulo `Q` (see `supports_ntt`), and coefficients
+/// form otherwise. Operations dispatch on the representation, so that multiplication is
+/// slot-wise in NTT form and negacyclic schoolbook in coefficients form.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum AssignedPoly<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    const N: usize,
+    const Q: u64,
+> {
+    Ntt(AssignedNTTPoly<F, D, N, Q>),
+    Coeff(AssignedCoeffPoly<F, D, N, Q>),
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
+    AssignedPoly<F, D, N, Q>
+{
+    pub fn new(cb: &mut CircuitBuilder<F, D>) -> Self {
+        Self::new_from_values(AssignedValue::new_batch(cb, N).try_into().unwrap())
+    }
+
+    fn new_from_targets(cb: &mut CircuitBuilder<F, D>, targets: [Target; N]) -> Self {
+        Self::new_from_values(targets.map(|target| AssignedValue::new_from_target(cb, target)))
+    }
+
+    /// Wraps the evaluations of a polynomial in NTT form if `Q` supports NTT, and its
+    /// coefficients otherwise
+    pub fn new_from_values(values: [AssignedValue<F, D, Q>; N]) -> Self {
+        if supports_ntt(N, Q) {
+            Self::Ntt(AssignedNTTPoly::new_from_values(values))
+        } else {
+            Self::Coeff(AssignedCoeffPoly::new_from_values(values))
+        }
+    }
+
+    /// Returns the evaluations in NTT form, or the coefficients in coefficients form
+    pub fn values(&self) -> &[AssignedValue<F, D, Q>; N] {
+        match self {
+            Self::Ntt(poly) => poly.evals(),
+            Self::Coeff(poly) => poly.coeffs(),
+        }
+    }
+
+    /// Returns the polynomial in NTT form, or `UnsupportedModulus` if `Q` does not support NTT
+    pub fn ntt(&self) -> Result<AssignedNTTPoly<F, D, N, Q>, VbfvError> {
+        match self {
+            Self::Ntt(poly) => Ok(*poly),
+            Self::Coeff(_) => Err(VbfvError::UnsupportedModulus(Q)),
+        }
+    }
+
+    /// Assigns a polynomial given by its coefficients, transformed into NTT form if needed
+    pub fn assign(&self, pw: &mut PartialWitness<F>, coeffs: &[i64]) -> Result<(), VbfvError> {
+        match self {
+            Self::Ntt(poly) => poly.assign(pw, coeffs),
+            Self::Coeff(poly) => poly.assign(pw, coeffs),
+        }
+    }
+
+    pub fn add(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        other: AssignedPoly<F, D, N, Q>,
+    ) -> Result<AssignedPoly<F, D, N, Q>, VbfvError> {
+        match (self, other) {
+            (Self::Ntt(lhs), Self::Ntt(rhs)) => Ok(Self::Ntt(lhs.add(arithmetic_chip, rhs)?)),
+            (Self::Coeff(lhs), Self::Coeff(rhs)) => Ok(Self::Coeff(lhs.add(arithmetic_chip, rhs)?)),
+            _ => unreachable!("the representation only depends on `N` and `Q`"),
+        }
+    }
+
+    pub fn sub(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        other: AssignedPoly<F, D, N, Q>,
+    ) -> Result<AssignedPoly<F, D, N, Q>, VbfvError> {
+        match (self, other) {
+            (Self::Ntt(lhs), Self::Ntt(rhs)) => Ok(Self::Ntt(lhs.sub(arithmetic_chip, rhs)?)),
+            (Self::Coeff(lhs), Self::Coeff(rhs)) => Ok(Self::Coeff(lhs.sub(arithmetic_chip, rhs)?)),
+            _ => unreachable!("the representation only depends on `N` and `Q`"),
+        }
+    }
+
+    pub fn mul(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        other: AssignedPoly<F, D, N, Q>,
+    ) -> Result<AssignedPoly<F, D, N, Q>, VbfvError> {
+        match (self, other) {
+            (Self::Ntt(lhs), Self::Ntt(rhs)) => Ok(Self::Ntt(lhs.mul(arithmetic_chip, rhs)?)),
+            (Self::Coeff(lhs), Self::Coeff(rhs)) => Ok(Self::Coeff(lhs.mul(arithmetic_chip, rhs)?)),
+            _ => unreachable!("the representation only depends on `N` and `Q`"),
+        }
+    }
+
+    pub fn neg(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+    ) -> Result<AssignedPoly<F, D, N, Q>, VbfvError> {
+        match self {
+            Self::Ntt(poly) => Ok(Self::Ntt(poly.neg(arithmetic_chip)?)),
+            Self::Coeff(poly) => Ok(Self::Coeff(poly.neg(arithmetic_chip)?)),
+        }
+    }
 }
 
 /// `AssignedCiphertext` is assigned value of bfv ciphertext consisting of two `R_Q` polynomials.
+/// The polynomials are kept in the representation `Q` supports, see `AssignedPoly`.
 #[derive(Copy, Clone, Debug)]
 pub struct AssignedCiphertext<
     F: RichField + Extendable<D>,
@@ -188,15 +581,15 @@ pub struct AssignedCiphertext<
     const Q: u64,
 > {
     plaintext_modulus: u64,
-    ciphertext: [AssignedNTTPoly<F, D, N, Q>; 2],
+    ciphertext: [AssignedPoly<F, D, N, Q>; 2],
 }
 
 impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
     AssignedCiphertext<F, D, N, Q>
 {
     pub fn new(cb: &mut CircuitBuilder<F, D>, plaintext_modulus: u64) -> Self {
-        let ct_0 = AssignedNTTPoly::new(cb);
-        let ct_1 = AssignedNTTPoly::new(cb);
+        let ct_0 = AssignedPoly::new(cb);
+        let ct_1 = AssignedPoly::new(cb);
         AssignedCiphertext {
             plaintext_modulus,
             ciphertext: [ct_0, ct_1],
@@ -212,8 +605,8 @@ impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
         Self {
             plaintext_modulus,
             ciphertext: [
-                AssignedNTTPoly::new_from_targets(cb, ct_0_targets),
-                AssignedNTTPoly::new_from_targets(cb, ct_1_targets),
+                AssignedPoly::new_from_targets(cb, ct_0_targets),
+                AssignedPoly::new_from_targets(cb, ct_1_targets),
             ],
         }
     }
@@ -226,8 +619,8 @@ impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
         Self {
             plaintext_modulus,
             ciphertext: [
-                AssignedNTTPoly::new_from_values(ct_0_values),
-                AssignedNTTPoly::new_from_values(ct_1_values),
+                AssignedPoly::new_from_values(ct_0_values),
+                AssignedPoly::new_from_values(ct_1_values),
             ],
         }
     }
@@ -236,38 +629,254 @@ impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
         self.plaintext_modulus
     }
 
-    pub fn ciphertext(&self) -> &[AssignedNTTPoly<F, D, N, Q>; 2] {
+    pub fn ciphertext(&self) -> &[AssignedPoly<F, D, N, Q>; 2] {
         &self.ciphertext
     }
 
+    /// Returns the ciphertext polynomials in NTT form, or `UnsupportedModulus` if `Q` does not
+    /// support NTT
+    pub fn ntt_polys(&self) -> Result<[AssignedNTTPoly<F, D, N, Q>; 2], VbfvError> {
+        Ok([self.ciphertext[0].ntt()?, self.ciphertext[1].ntt()?])
+    }
+
     pub fn register_as_public_input(&self, cb: &mut CircuitBuilder<F, D>) {
-        self.ciphertext[0].evals.iter().for_each(|eval| {
-            cb.register_public_input(eval.value);
+        self.ciphertext[0].values().iter().for_each(|value| {
+            cb.register_public_input(value.value);
         });
-        self.ciphertext[1].evals.iter().for_each(|eval| {
-            cb.register_public_input(eval.value);
+        self.ciphertext[1].values().iter().for_each(|value| {
+            cb.register_public_input(value.value);
         });
     }
 
     pub(crate) fn values(&self) -> Vec<AssignedValue<F, D, Q>> {
-        self.ciphertext.iter().flat_map(|ct| ct.evals).collect_vec()
+        self.ciphertext
+            .iter()
+            .flat_map(|ct| *ct.values())
+            .collect_vec()
     }
 
     pub(crate) fn ciphertext_targets(&self) -> Vec<Target> {
         self.ciphertext
             .iter()
-            .flat_map(|ct| ct.evals.iter().map(|eval| eval.value).collect_vec())
+            .flat_map(|ct| ct.values().iter().map(|value| value.value).collect_vec())
             .collect_vec()
     }
 
-    pub fn assign(&self, pw: &mut PartialWitness<F>, ct: Ciphertext) -> Result<(), Error> {
+    /// Assigns a ciphertext given by its coefficients, as held by `Ciphertext`. They are
+    /// canonicalized into `[0, Q)`, then transformed into NTT form when `Q` supports NTT.
+    pub fn assign(&self, pw: &mut PartialWitness<F>, mut ct: Ciphertext) -> Result<(), VbfvError> {
+        ct.canonicalize();
         self.ciphertext[0].assign(pw, ct.c_0.val())?;
-        self.ciphertext[1].assign(pw, ct.c_1.val())?;
+        self.ciphertext[1].assign(pw, ct.c_1.val())
+    }
+
+    /// Assigns `cts[i]` to `assigned[i]` as `assign` does, for circuits over many ciphertexts.
+    /// The NTT roots are the constants `params::ROOTS`, so nothing is left to precompute across
+    /// ciphertexts. Fails with `DimensionMismatch` before assigning anything if the slices differ
+    /// in length.
+    pub fn assign_many(
+        pw: &mut PartialWitness<F>,
+        assigned: &[AssignedCiphertext<F, D, N, Q>],
+        cts: &[Ciphertext],
+    ) -> Result<(), VbfvError> {
+        if assigned.len() != cts.len() {
+            return Err(VbfvError::DimensionMismatch {
+                expected: assigned.len(),
+                actual: cts.len(),
+            });
+        }
+        assigned
+            .iter()
+            .zip(cts)
+            .try_for_each(|(assigned, ct)| assigned.assign(pw, ct.clone()))
+    }
+
+    /// Assigns a ciphertext given by the evaluations of `(c_0, c_1)` in NTT form, in the
+    /// bit-reversed order of `ntt_forward`, e.g. values computed by an in-circuit multiplication,
+    /// without transforming them again. Fails with `UnsupportedModulus` if `Q` does not support
+    /// NTT.
+    pub fn assign_ntt(
+        &self,
+        pw: &mut PartialWitness<F>,
+        ct_evals: &[[F; N]; 2],
+    ) -> Result<(), VbfvError> {
+        self.ntt_polys()?
+            .iter()
+            .zip(ct_evals)
+            .try_for_each(|(poly, evals)| poly.assign_evals(pw, evals))
+    }
+
+    pub fn sub(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+        other: AssignedCiphertext<F, D, N, Q>,
+    ) -> Result<AssignedCiphertext<F, D, N, Q>, VbfvError> {
+        if self.plaintext_modulus != other.plaintext_modulus {
+            return Err(VbfvError::PlaintextModulusMismatch(
+                self.plaintext_modulus,
+                other.plaintext_modulus,
+            ));
+        }
+        let ct_0 = self.ciphertext[0].sub(arithmetic_chip, other.ciphertext[0])?;
+        let ct_1 = self.ciphertext[1].sub(arithmetic_chip, other.ciphertext[1])?;
+        Ok(AssignedCiphertext {
+            plaintext_modulus: self.plaintext_modulus,
+            ciphertext: [ct_0, ct_1],
+        })
+    }
+
+    pub fn neg(
+        &self,
+        arithmetic_chip: &mut ArithmeticChip<F, D, Q>,
+    ) -> Result<AssignedCiphertext<F, D, N, Q>, VbfvError> {
+        let ct_0 = self.ciphertext[0].neg(arithmetic_chip)?;
+        let ct_1 = self.ciphertext[1].neg(arithmetic_chip)?;
+        Ok(AssignedCiphertext {
+            plaintext_modulus: self.plaintext_modulus,
+            ciphertext: [ct_0, ct_1],
+        })
+    }
+}
+
+/// `AssignedPlaintext` is assigned value of bfv plaintext, i.e. polynomial inside `R_t` in
+/// coefficients form, whose coefficients are in `[0, t)`.
+#[derive(Copy, Clone, Debug)]
+pub struct AssignedPlaintext<F: RichField + Extendable<D>, const D: usize, const N: usize> {
+    _marker: PhantomData<F>,
+    plaintext_modulus: u64,
+    coeffs: [Target; N],
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, const N: usize> AssignedPlaintext<F, D, N> {
+    pub fn new(cb: &mut CircuitBuilder<F, D>, plaintext_modulus: u64) -> Self {
+        Self {
+            _marker: PhantomData,
+            plaintext_modulus,
+            coeffs: [(); N].map(|_| {
+                // `coeff < t` iff both `coeff` and `t - 1 - coeff` have `log2_ceil(t)` bits.
+                let coeff = cb.add_virtual_target();
+                let bits = log2_ceil(plaintext_modulus as usize);
+                cb.range_check(coeff, bits);
+                if !plaintext_modulus.is_power_of_two() {
+                    let t_minus_one = cb.constant(F::from_canonical_u64(plaintext_modulus - 1));
+                    let slack = cb.sub(t_minus_one, coeff);
+                    cb.range_check(slack, bits);
+                }
+                coeff
+            }),
+        }
+    }
+
+    pub fn new_from_targets(plaintext_modulus: u64, coeffs: [Target; N]) -> Self {
+        Self {
+            _marker: PhantomData,
+            plaintext_modulus,
+            coeffs,
+        }
+    }
+
+    pub fn plaintext_modulus(&self) -> u64 {
+        self.plaintext_modulus
+    }
+
+    pub fn coeffs(&self) -> &[Target; N] {
+        &self.coeffs
+    }
+
+    pub fn register_as_public_input(&self, cb: &mut CircuitBuilder<F, D>) {
+        self.coeffs.iter().for_each(|coeff| {
+            cb.register_public_input(*coeff);
+        });
+    }
+
+    /// Assigns the coefficients of `pt` reduced into `[0, t)`
+    pub fn assign(&self, pw: &mut PartialWitness<F>, pt: &Plaintext) -> Result<(), VbfvError> {
+        let t = self.plaintext_modulus as i64;
+        self.coeffs
+            .iter()
+            .zip_eq(pt.poly().val())
+            .map(|(tcoeff, coeff)| {
+                pw.set_target(*tcoeff, F::from_canonical_i64(coeff.rem_euclid(t)))
+                    .map_err(VbfvError::WitnessAssign)
+            })
+            .collect::<Result<Vec<()>, VbfvError>>()?;
+        Ok(())
+    }
+}
+
+/// `AssignedEncryptionRandomness` is assigned value of the randomness `(u, e_1, e_2)` of bfv public
+/// key encryption in coefficients form. Coefficients of `u` are binary and those of `e_1`, `e_2` are
+/// range checked to `[-2^(error_bits - 1), 2^(error_bits - 1))`, which bounds the noise of the
+/// resulting ciphertext. The errors are signed, so they are held shifted by `2^(error_bits - 1)`
+/// into `[0, 2^error_bits)` and the shift is subtracted again in `CiphertextChip::encrypt`.
+#[derive(Copy, Clone, Debug)]
+pub struct AssignedEncryptionRandomness<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    const N: usize,
+    const Q: u64,
+> {
+    pub(crate) u: [AssignedValue<F, D, Q>; N],
+    pub(crate) e_1: [AssignedValue<F, D, Q>; N],
+    pub(crate) e_2: [AssignedValue<F, D, Q>; N],
+    error_offset: u64,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
+    AssignedEncryptionRandomness<F, D, N, Q>
+{
+    pub fn new(cb: &mut CircuitBuilder<F, D>, error_bits: usize) -> Self {
+        Self {
+            u: [(); N].map(|_| AssignedValue::new_with_bits(cb, 1)),
+            e_1: [(); N].map(|_| AssignedValue::new_with_bits(cb, error_bits)),
+            e_2: [(); N].map(|_| AssignedValue::new_with_bits(cb, error_bits)),
+            error_offset: (1 << error_bits) >> 1,
+        }
+    }
+
+    /// The shift `2^(error_bits - 1)` added to the coefficients of `e_1` and `e_2`.
+    pub(crate) fn error_offset(&self) -> u64 {
+        self.error_offset
+    }
+
+    /// Assigns the coefficients of `u`, `e_1` and `e_2`. The coefficients of `u` must lie in
+    /// `[0, 2)` and those of `e_1`, `e_2` are shifted by `2^(error_bits - 1)` before assignment, so
+    /// a coefficient the shift leaves negative is rejected with `VbfvError::WitnessAssign` rather
+    /// than wrapped around the field.
+    pub fn assign(
+        &self,
+        pw: &mut PartialWitness<F>,
+        u: &[i64],
+        e_1: &[i64],
+        e_2: &[i64],
+    ) -> Result<(), VbfvError> {
+        let offset = self.error_offset as i64;
+        [
+            (&self.u, u, 0),
+            (&self.e_1, e_1, offset),
+            (&self.e_2, e_2, offset),
+        ]
+        .into_iter()
+        .flat_map(|(tcoeffs, coeffs, offset)| {
+            tcoeffs
+                .iter()
+                .zip_eq(coeffs)
+                .map(move |(tcoeff, coeff)| (tcoeff, coeff, offset))
+        })
+        .map(|(tcoeff, coeff, offset)| {
+            let shifted = u64::try_from(coeff + offset).map_err(|_| {
+                VbfvError::WitnessAssign(anyhow::anyhow!(
+                    "randomness coefficient {coeff} below -{offset}"
+                ))
+            })?;
+            tcoeff.assign(pw, F::from_canonical_u64(shifted))
+        })
+        .collect::<Result<Vec<()>, VbfvError>>()?;
         Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AssignedRelinearizationKey<
     F: RichField + Extendable<D>,
     const D: usize,
@@ -282,30 +891,650 @@ pub struct AssignedRelinearizationKey<
 impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
     AssignedRelinearizationKey<F, D, N, Q>
 {
-    pub fn new(cb: &mut CircuitBuilder<F, D>, base: u64) -> Self {
-        let num_limbs = log_floor(Q, base);
-        Self {
+    /// Allocates the `floor(log_base(Q))` limbs of a key in base `base`, which must lie in
+    /// `[2, Q)` as the key has no limb otherwise
+    pub fn new(cb: &mut CircuitBuilder<F, D>, base: u64) -> Result<Self, VbfvError> {
+        let num_limbs = Self::num_limbs(base)?;
+        Ok(Self {
             value: (0..num_limbs)
                 .map(|_| [AssignedNTTPoly::new(cb), AssignedNTTPoly::new(cb)])
                 .into_iter()
                 .collect_vec(),
             base,
+        })
+    }
+
+    fn num_limbs(base: u64) -> Result<usize, VbfvError> {
+        if base < 2 || base >= Q {
+            return Err(VbfvError::UnsupportedBase(base));
         }
+        Ok(log_floor(Q, base))
+    }
+
+    pub(crate) fn new_from_values(value: Vec<[AssignedNTTPoly<F, D, N, Q>; 2]>, base: u64) -> Self {
+        Self { value, base }
+    }
+
+    /// Wraps the targets of a key supplied by the verifier, e.g. public inputs, range checking
+    /// every eval. `targets` holds the evals of `(rlk_0, rlk_1)` for each of the
+    /// `floor(log_base(Q))` limbs, in the order of `register_as_public_input`.
+    pub fn new_from_targets(
+        cb: &mut CircuitBuilder<F, D>,
+        targets: Vec<[[Target; N]; 2]>,
+        base: u64,
+    ) -> Result<Self, VbfvError> {
+        let num_limbs = Self::num_limbs(base)?;
+        if targets.len() != num_limbs {
+            return Err(VbfvError::DimensionMismatch {
+                expected: num_limbs,
+                actual: targets.len(),
+            });
+        }
+        let value = targets
+            .into_iter()
+            .map(|limb| limb.map(|evals| AssignedNTTPoly::new_from_targets(cb, evals)))
+            .collect_vec();
+        Ok(Self { value, base })
+    }
+
+    /// Returns the key components `(rlk_0, rlk_1)` of each limb in NTT form
+    pub fn value(&self) -> &[[AssignedNTTPoly<F, D, N, Q>; 2]] {
+        &self.value
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Registers the evals of `rlk_0` and `rlk_1` of each limb in turn
+    pub fn register_as_public_input(&self, cb: &mut CircuitBuilder<F, D>) {
+        self.value.iter().flatten().for_each(|poly| {
+            poly.evals.iter().for_each(|eval| {
+                cb.register_public_input(eval.value);
+            })
+        });
     }
 
+    pub(crate) fn targets(&self) -> Vec<Target> {
+        self.value
+            .iter()
+            .flatten()
+            .flat_map(|poly| poly.evals.iter().map(|eval| eval.value).collect_vec())
+            .collect_vec()
+    }
+
+    /// Assigns the limbs of `rlk`, which must be generated in the base of this key
     pub fn assign(
         &self,
         pw: &mut PartialWitness<F>,
         rlk: &RelinearizationKey1,
-    ) -> Result<(), Error> {
+    ) -> Result<(), VbfvError> {
+        let base = rlk.ksk.base() as u64;
+        if base != self.base {
+            return Err(VbfvError::BaseMismatch {
+                expected: self.base,
+                actual: base,
+            });
+        }
+        if rlk.ksk.val.len() != self.value.len() {
+            return Err(VbfvError::DimensionMismatch {
+                expected: self.value.len(),
+                actual: rlk.ksk.val.len(),
+            });
+        }
         self.value
             .iter()
-            .zip_eq(rlk.val.iter())
+            .zip(rlk.ksk.val.iter())
             .map(|(assigned_rlk, (rlk_0, rlk_1))| {
                 assigned_rlk[0].assign(pw, rlk_0.val())?;
                 assigned_rlk[1].assign(pw, rlk_1.val())
             })
-            .collect::<Result<Vec<()>, Error>>()?;
+            .collect::<Result<Vec<()>, VbfvError>>()?;
         Ok(())
     }
 }
+
+/// `AssignedGswCiphertext` is assigned value of a GSW ciphertext `RGSW(mu)` in base `T`: the
+/// gadget-decomposed rows of the key switching keys from `mu` and from `mu * s`, in NTT form
+#[derive(Clone, Debug)]
+pub struct AssignedGswCiphertext<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    const N: usize,
+    const Q: u64,
+> {
+    /// `[([T^i \cdot mu - (a_i \cdot s + e_i)]_q, a_i)]` and the same rows for `mu * s`
+    rows: [Vec<[AssignedNTTPoly<F, D, N, Q>; 2]>; 2],
+    base: u64,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
+    AssignedGswCiphertext<F, D, N, Q>
+{
+    /// Allocates the `floor(log_base(Q))` rows for `mu` and for `mu * s`. `base` must lie in
+    /// `[2, Q]`.
+    pub fn new(cb: &mut CircuitBuilder<F, D>, base: u64) -> Result<Self, VbfvError> {
+        if base < 2 || base > Q {
+            return Err(VbfvError::UnsupportedBase(base));
+        }
+        let num_limbs = log_floor(Q, base);
+        Ok(Self {
+            rows: [(); 2].map(|_| {
+                (0..num_limbs)
+                    .map(|_| [AssignedNTTPoly::new(cb), AssignedNTTPoly::new(cb)])
+                    .collect_vec()
+            }),
+            base,
+        })
+    }
+
+    /// Returns the rows decomposing `c_0` (for `mu`) and those decomposing `c_1` (for `mu * s`)
+    pub fn rows(&self) -> &[Vec<[AssignedNTTPoly<F, D, N, Q>; 2]>; 2] {
+        &self.rows
+    }
+
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    /// Registers the evals of both components of each row for `mu`, then of each row for
+    /// `mu * s`
+    pub fn register_as_public_input(&self, cb: &mut CircuitBuilder<F, D>) {
+        for target in self.targets() {
+            cb.register_public_input(target);
+        }
+    }
+
+    pub(crate) fn targets(&self) -> Vec<Target> {
+        self.rows
+            .iter()
+            .flatten()
+            .flatten()
+            .flat_map(|poly| poly.evals.iter().map(|eval| eval.value).collect_vec())
+            .collect_vec()
+    }
+
+    pub fn assign(&self, pw: &mut PartialWitness<F>, gsw: &GswCiphertext) -> Result<(), VbfvError> {
+        for (rows, ksk) in self.rows.iter().zip([&gsw.c_mu, &gsw.c_mu_s]) {
+            if rows.len() != ksk.val.len() {
+                return Err(VbfvError::DimensionMismatch {
+                    expected: rows.len(),
+                    actual: ksk.val.len(),
+                });
+            }
+            for (row, (ksk_0, ksk_1)) in rows.iter().zip(ksk.val.iter()) {
+                row[0].assign(pw, ksk_0.val())?;
+                row[1].assign(pw, ksk_1.val())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{Error, Result};
+    use plonky2::{
+        field::{
+            goldilocks_field::GoldilocksField,
+            types::{Field, Field64},
+        },
+        iop::witness::{PartialWitness, Witness, WitnessWrite},
+        plonk::{
+            circuit_builder::CircuitBuilder, circuit_data::CircuitConfig,
+            config::PoseidonGoldilocksConfig,
+        },
+    };
+
+    use itertools::Itertools;
+    use plonky2::util::log_floor;
+    use rand::SeedableRng;
+
+    use super::{
+        AssignedCiphertext, AssignedCoeffPoly, AssignedGswCiphertext, AssignedNTTPoly,
+        AssignedRelinearizationKey, AssignedValue, VbfvError,
+    };
+    use crate::{
+        bfv::{poly::Poly, Plaintext, SecretKey},
+        vbfv::{arithmetic_chip::ArithmeticChip, is_provable, ntt_forward},
+    };
+
+    const D: usize = 2;
+    const Q: u64 = 3329;
+    type C = PoseidonGoldilocksConfig;
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_new_batch_circuit_size() {
+        const N: usize = 1024;
+        let config = CircuitConfig::standard_recursion_config();
+
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        (0..N).for_each(|_| {
+            AssignedValue::<F, D, Q>::new(&mut builder);
+        });
+        let unbatched = builder.build::<C>().common.degree();
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        AssignedValue::<F, D, Q>::new_batch(&mut builder, N);
+        let batched = builder.build::<C>().common.degree();
+
+        assert!(batched < unbatched, "{batched} >= {unbatched}");
+    }
+
+    #[test]
+    fn test_new_batch_range() -> Result<(), Error> {
+        for (value, in_range) in [(0, true), (Q - 1, true), (Q, false)] {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let values = AssignedValue::<F, D, Q>::new_batch(&mut builder, 2);
+
+            let mut pw = PartialWitness::new();
+            values[0].assign(&mut pw, F::ONE)?;
+            let assigned =
+                values[1].assign(&mut PartialWitness::new(), F::from_canonical_u64(value));
+            assert_eq!(assigned.is_ok(), in_range);
+            if !in_range {
+                assert!(matches!(
+                    assigned,
+                    Err(VbfvError::RangeCheckFailed { value: Q, bound: Q })
+                ));
+            }
+            // bypass the check in `assign` to exercise the lookup constraint itself
+            pw.set_target(values[1].value, F::from_canonical_u64(value))?;
+
+            let data = builder.build::<C>();
+            let proved = data.prove(pw).is_ok();
+            assert_eq!(proved, in_range);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_range() -> Result<(), Error> {
+        // `Q = 3329` has 12 bits, so `[Q, 4096)` passes a plain bit decomposition
+        for (value, in_range) in [(0, true), (Q - 1, true), (Q, false), (4095, false)] {
+            for from_target in [false, true] {
+                let config = CircuitConfig::standard_recursion_config();
+                let mut builder = CircuitBuilder::<F, D>::new(config);
+                let assigned = if from_target {
+                    let target = builder.add_virtual_target();
+                    AssignedValue::<F, D, Q>::new_from_target(&mut builder, target)
+                } else {
+                    AssignedValue::<F, D, Q>::new(&mut builder)
+                };
+
+                // bypass the check in `assign` to exercise the range check itself
+                let mut pw = PartialWitness::new();
+                pw.set_target(assigned.value, F::from_canonical_u64(value))?;
+
+                // Witness generation panics when `Q - 1 - value` does not fit in 12 bits
+                let data = builder.build::<C>();
+                assert_eq!(is_provable(&data, pw), in_range, "value {value}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_inner_product() -> Result<(), Error> {
+        const N: usize = 8;
+        let evals = [
+            [0, 1, 2, 3, 4, 5, 6, 7],
+            [Q - 1, Q - 2, 100, 200, 300, 400, 500, 600],
+            [1234, 0, Q - 1, 1, 2048, 3000, 17, 42],
+        ];
+        let scalars = [3, Q - 1, 1 << 10];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+        let polys = evals
+            .iter()
+            .map(|_| AssignedNTTPoly::<F, D, N, Q>::new(&mut arithmetic_chip.cb))
+            .collect::<Vec<_>>();
+        let scalars_f = scalars.map(F::from_canonical_u64);
+        let result = AssignedNTTPoly::inner_product(&mut arithmetic_chip, &polys, &scalars_f)?;
+        let scaled = polys[1].scalar_mul(&mut arithmetic_chip, scalars_f[1])?;
+        for eval in result.evals().iter().chain(scaled.evals()) {
+            eval.register_as_public_input(&mut arithmetic_chip.cb);
+        }
+
+        assert!(matches!(
+            AssignedNTTPoly::inner_product(&mut arithmetic_chip, &polys, &scalars_f[..2]),
+            Err(VbfvError::DimensionMismatch {
+                expected: 3,
+                actual: 2
+            })
+        ));
+
+        let mut pw = PartialWitness::new();
+        for (poly, evals) in polys.iter().zip(evals.iter()) {
+            for (assigned, eval) in poly.evals().iter().zip(evals) {
+                assigned.assign(&mut pw, F::from_canonical_u64(*eval))?;
+            }
+        }
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        let expected = (0..N)
+            .map(|j| {
+                evals
+                    .iter()
+                    .zip(scalars)
+                    .map(|(evals, scalar)| evals[j] as u128 * scalar as u128)
+                    .sum::<u128>()
+                    % Q as u128
+            })
+            .chain(
+                evals[1]
+                    .iter()
+                    .map(|eval| *eval as u128 * scalars[1] as u128 % Q as u128),
+            )
+            .map(|x| F::from_canonical_u64(x as u64))
+            .collect::<Vec<_>>();
+        assert_eq!(proof.public_inputs, expected);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_coeff_poly_mul() -> Result<(), Error> {
+        const N: usize = 8;
+        const Q: u64 = 65536;
+        let a = vec![1, 65535, 3, 400, 5, 0, 7, 32768];
+        let b = vec![8, 7, 6, 5, 4, 3, 2, 65535];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::<F, D, Q>::new(builder);
+        let assigned_a = AssignedCoeffPoly::<F, D, N, Q>::new(&mut arithmetic_chip.cb);
+        let assigned_b = AssignedCoeffPoly::<F, D, N, Q>::new(&mut arithmetic_chip.cb);
+        let product = assigned_a.mul(&mut arithmetic_chip, assigned_b)?;
+        product
+            .coeffs()
+            .iter()
+            .for_each(|coeff| coeff.register_as_public_input(&mut arithmetic_chip.cb));
+
+        let mut pw = PartialWitness::new();
+        assigned_a.assign(&mut pw, &a)?;
+        assigned_b.assign(&mut pw, &b)?;
+
+        let data = arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        let expected = (Poly::new(a) * Poly::new(b)) % (Q as i64, N);
+        proof
+            .public_inputs
+            .iter()
+            .zip(expected.val())
+            .for_each(|(actual, expected)| assert_eq!(*actual, F::from_canonical_i64(*expected)));
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_relinearization_key_public_inputs() -> Result<(), Error> {
+        const N: usize = 8;
+        let base = 16;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let rlk = secret_key
+            .relin_key_gen_1(Q as i64, 3.2, &mut rng, base as i64)
+            .unwrap();
+        let expected = rlk
+            .ksk
+            .val
+            .iter()
+            .flat_map(|(rlk_0, rlk_1)| [rlk_0, rlk_1])
+            .flat_map(|poly| {
+                let coeffs = poly.val().iter().map(|coeff| F::from_canonical_i64(*coeff));
+                ntt_forward::<F, D, Q>(&coeffs.collect_vec())
+            })
+            .collect_vec();
+
+        // The prover exposes its key as public inputs.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let assigned_rlk = AssignedRelinearizationKey::<F, D, N, Q>::new(&mut builder, base)?;
+        assigned_rlk.register_as_public_input(&mut builder);
+
+        let mut pw = PartialWitness::new();
+        assigned_rlk.assign(&mut pw, &rlk)?;
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        assert_eq!(proof.public_inputs, expected);
+        data.verify(proof.clone())?;
+
+        // The verifier wires the key back in from public input targets.
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let targets = (0..log_floor(Q, base))
+            .map(|_| [(); 2].map(|_| builder.add_virtual_public_input_arr::<N>()))
+            .collect_vec();
+        assert!(matches!(
+            AssignedRelinearizationKey::<F, D, N, Q>::new_from_targets(
+                &mut builder,
+                targets[1..].to_vec(),
+                base
+            ),
+            Err(VbfvError::DimensionMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        let wired_rlk = AssignedRelinearizationKey::<F, D, N, Q>::new_from_targets(
+            &mut builder,
+            targets.clone(),
+            base,
+        )?;
+        let flat_targets = targets.iter().flatten().flatten().copied().collect_vec();
+        assert_eq!(wired_rlk.targets(), flat_targets);
+
+        let mut pw = PartialWitness::new();
+        for (target, value) in flat_targets.iter().zip_eq(&proof.public_inputs) {
+            pw.set_target(*target, *value)?;
+        }
+        let data = builder.build::<C>();
+        let wired_proof = data.prove(pw)?;
+        assert_eq!(wired_proof.public_inputs, proof.public_inputs);
+        data.verify(wired_proof)
+    }
+
+    #[test]
+    fn test_gsw_ciphertext_unsupported_base() -> Result<(), Error> {
+        const N: usize = 8;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        for base in [0, 1, Q + 1, u64::MAX] {
+            assert!(matches!(
+                AssignedGswCiphertext::<F, D, N, Q>::new(&mut builder, base),
+                Err(VbfvError::UnsupportedBase(actual)) if actual == base
+            ));
+        }
+        for base in [2, 16, Q] {
+            let gsw = AssignedGswCiphertext::<F, D, N, Q>::new(&mut builder, base)?;
+            assert_eq!(gsw.rows()[0].len(), log_floor(Q, base));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_relinearization_key_base_mismatch() -> Result<(), Error> {
+        const N: usize = 8;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(N, &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        for base in [0, 1, Q, Q + 1] {
+            assert!(matches!(
+                AssignedRelinearizationKey::<F, D, N, Q>::new(&mut builder, base),
+                Err(VbfvError::UnsupportedBase(actual)) if actual == base
+            ));
+        }
+        assert!(matches!(
+            AssignedRelinearizationKey::<F, D, N, Q>::new_from_targets(&mut builder, vec![], 1),
+            Err(VbfvError::UnsupportedBase(1))
+        ));
+
+        // Bases 15 and 16 both give 2 limbs for `Q = 3329`, so only the base tells them apart
+        let assigned_rlk = AssignedRelinearizationKey::<F, D, N, Q>::new(&mut builder, 16)?;
+        let mut pw = PartialWitness::new();
+        for (base, limbs) in [(15, 2), (8, 3)] {
+            assert_eq!(log_floor(Q, base), limbs);
+            let rlk = secret_key
+                .relin_key_gen_1(Q as i64, 3.2, &mut rng, base as i64)
+                .unwrap();
+            let err = assigned_rlk.assign(&mut pw, &rlk).unwrap_err();
+            assert!(matches!(
+                err,
+                VbfvError::BaseMismatch {
+                    expected: 16,
+                    actual
+                } if actual == base
+            ));
+            assert_eq!(
+                err.to_string(),
+                format!("expected a key in base 16, got base {base}")
+            );
+        }
+        let rlk = secret_key
+            .relin_key_gen_1(Q as i64, 3.2, &mut rng, 16)
+            .unwrap();
+        assigned_rlk.assign(&mut pw, &rlk)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_assign_field_beyond_i64() -> Result<(), Error> {
+        const N: usize = 8;
+        // A modulus beyond `2^63`, whose residues do not all fit in `i64`
+        const Q: u64 = F::ORDER - 1;
+        let coeffs = [
+            F::ORDER - 2,
+            1 << 63,
+            (1 << 63) + 1,
+            u64::MAX >> 1,
+            0,
+            1,
+            2,
+            3,
+        ]
+        .map(F::from_canonical_u64);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let poly = AssignedCoeffPoly::<F, D, N, Q>::new(&mut builder);
+        poly.coeffs()
+            .iter()
+            .for_each(|coeff| coeff.register_as_public_input(&mut builder));
+
+        let mut out_of_range = coeffs;
+        out_of_range[0] = F::from_canonical_u64(Q);
+        assert!(matches!(
+            poly.assign_field(&mut PartialWitness::new(), &out_of_range),
+            Err(VbfvError::RangeCheckFailed { value: Q, bound: Q })
+        ));
+
+        let mut pw = PartialWitness::new();
+        poly.assign_field(&mut pw, &coeffs)?;
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        assert_eq!(proof.public_inputs, coeffs.to_vec());
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_assign_ntt() -> Result<(), Error> {
+        const N: usize = 8;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, 3.2, &mut rng);
+        let ct =
+            Plaintext::new(vec![0, 1, 2, 3, 4, 5, 6, 7], 8).encrypt(&public_key, 3.2, &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let from_coeffs = AssignedCiphertext::<F, D, N, Q>::new(&mut builder, 8);
+        let from_evals = AssignedCiphertext::<F, D, N, Q>::new(&mut builder, 8);
+
+        let ct_evals = [&ct.c_0, &ct.c_1].map(|poly| {
+            let coeffs = poly.val().iter().map(|coeff| F::from_canonical_i64(*coeff));
+            ntt_forward::<F, D, Q>(&coeffs.collect_vec())
+                .try_into()
+                .unwrap()
+        });
+        let mut pw = PartialWitness::new();
+        from_coeffs.assign(&mut pw, ct)?;
+        from_evals.assign_ntt(&mut pw, &ct_evals)?;
+
+        for (lhs, rhs) in from_coeffs
+            .ciphertext_targets()
+            .into_iter()
+            .zip_eq(from_evals.ciphertext_targets())
+        {
+            assert_eq!(pw.try_get_target(lhs), pw.try_get_target(rhs));
+            assert!(pw.try_get_target(lhs).is_some());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_assign_ntt_errors() {
+        const N: usize = 8;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let poly = AssignedNTTPoly::<F, D, N, Q>::new(&mut builder);
+        let mut pw = PartialWitness::new();
+        for len in [N - 1, N + 1] {
+            for result in [
+                poly.assign_evals(&mut pw, &vec![F::ZERO; len]),
+                poly.assign_field(&mut pw, &vec![F::ZERO; len]),
+            ] {
+                assert!(matches!(
+                    result,
+                    Err(VbfvError::DimensionMismatch { expected: N, actual }) if actual == len
+                ));
+            }
+        }
+
+        // `X^8 + 1` does not split modulo a power of two
+        const Q_COEFF: u64 = 65536;
+        let ct = AssignedCiphertext::<F, D, N, Q_COEFF>::new(&mut builder, 8);
+        assert!(matches!(
+            ct.assign_ntt(&mut pw, &[[F::ZERO; N]; 2]),
+            Err(VbfvError::UnsupportedModulus(Q_COEFF))
+        ));
+    }
+
+    #[test]
+    fn test_assign_negated_ciphertext() -> Result<(), Error> {
+        const N: usize = 8;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(18);
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, 3.2, &mut rng);
+        let ct =
+            Plaintext::new(vec![0, 1, 2, 3, 4, 5, 6, 7], 8).encrypt(&public_key, 3.2, &mut rng);
+        let neg = -&ct;
+        assert!(neg.c_0().iter().any(|coeff| *coeff < 0));
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let assigned = AssignedCiphertext::<F, D, N, Q>::new(&mut builder, 8);
+        assigned.register_as_public_input(&mut builder);
+
+        let mut pw = PartialWitness::new();
+        assigned.assign(&mut pw, neg.clone())?;
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let mut canonical = neg;
+        canonical.canonicalize();
+        let expected = [&canonical.c_0, &canonical.c_1]
+            .into_iter()
+            .flat_map(|poly| {
+                let coeffs = poly.val().iter().map(|coeff| F::from_canonical_i64(*coeff));
+                ntt_forward::<F, D, Q>(&coeffs.collect_vec())
+            })
+            .collect_vec();
+        assert_eq!(proof.public_inputs, expected);
+        data.verify(proof)
+    }
+}
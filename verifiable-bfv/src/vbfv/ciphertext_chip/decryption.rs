@@ -0,0 +1,72 @@
+use anyhow::{Error, Result};
+use plonky2::{
+    field::{extension::Extendable, types::PrimeField64},
+    hash::hash_types::RichField,
+    iop::{
+        generator::{GeneratedValues, SimpleGenerator},
+        target::Target,
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::circuit_data::CommonCircuitData,
+    util::serialization::{Buffer, IoResult, Read, Write},
+};
+
+/// Witnesses the scale-and-round step of bfv decryption for a single coefficient `x \in [0, Q)`:
+/// `t * x + floor(Q/2) = rounded * Q + remainder`, so that `rounded = round(t/Q * x) \in [0, t]`,
+/// which is then reduced modulo `t` into the plaintext coefficient.
+#[derive(Debug)]
+pub(super) struct ScaleAndRoundGenerator<const Q: u64> {
+    pub(super) plaintext_modulus: u64,
+    pub(super) x: Target,
+    pub(super) rounded: Target,
+    pub(super) remainder: Target,
+}
+
+impl<F: PrimeField64 + RichField + Extendable<D>, const D: usize, const Q: u64>
+    SimpleGenerator<F, D> for ScaleAndRoundGenerator<Q>
+{
+    fn id(&self) -> String {
+        "ScaleAndRoundGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.x]
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<(), Error> {
+        let x = witness.get_target(self.x).to_canonical_u64() as u128;
+        let t = self.plaintext_modulus as u128;
+        let tmp = t * x + (Q as u128) / 2;
+        out_buffer.set_target(
+            self.rounded,
+            F::from_canonical_u64((tmp / Q as u128) as u64),
+        )?;
+        out_buffer.set_target(
+            self.remainder,
+            F::from_canonical_u64((tmp % (Q as u128)) as u64),
+        )
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.plaintext_modulus as usize)?;
+        dst.write_target(self.x)?;
+        dst.write_target(self.rounded)?;
+        dst.write_target(self.remainder)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            plaintext_modulus: src.read_usize()? as u64,
+            x: src.read_target()?,
+            rounded: src.read_target()?,
+            remainder: src.read_target()?,
+        })
+    }
+}
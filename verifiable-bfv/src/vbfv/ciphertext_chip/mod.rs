@@ -1,26 +1,44 @@
 use std::marker::PhantomData;
 
 use crate::{bfv::Ciphertext, vbfv::assigned::AssignedValue};
-use anyhow::{Error, Ok, Result};
+use anyhow::{Error, Result};
 use itertools::Itertools;
 use plonky2::{
-    field::{extension::Extendable, types::PrimeField64},
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::PrimeField64},
     hash::hash_types::RichField,
     iop::{
-        generator::{GeneratedValues, SimpleGenerator},
+        generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef},
         target::{BoolTarget, Target},
         witness::{PartialWitness, PartitionWitness, Witness, WitnessWrite},
     },
-    plonk::{circuit_builder::CircuitBuilder, circuit_data::CommonCircuitData},
-    util::serialization::{Buffer, IoResult, Read, Write},
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::{CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitData},
+        config::{GenericConfig, PoseidonGoldilocksConfig},
+        proof::ProofWithPublicInputs,
+    },
+    util::serialization::{
+        Buffer, DefaultGateSerializer, DefaultGeneratorSerializer, IoError, IoResult, Read,
+        WitnessGeneratorSerializer, Write,
+    },
 };
 
 use super::{
-    arithmetic_chip::ArithmeticChip,
-    assigned::{AssignedCiphertext, AssignedNTTPoly, AssignedRelinearizationKey},
+    arithmetic_chip::{
+        ArithmeticChip, ArithmeticOpsGenerator, GadgetDecompositionGenerator, ReduceModTGenerator,
+    },
+    assigned::{
+        AssignedCiphertext, AssignedEncryptionRandomness, AssignedGswCiphertext, AssignedNTTPoly,
+        AssignedPlaintext, AssignedPoly, AssignedRelinearizationKey,
+    },
+    error::VbfvError,
+    ntt_chip::NTTChip,
+    supports_ntt,
 };
+use crate::ntt_params::params;
+use decryption::ScaleAndRoundGenerator;
 
-mod relinearization;
+mod decryption;
 
 enum CiphertextOpKind<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64> {
     Add(
@@ -89,25 +107,61 @@ impl<F: PrimeField64 + RichField + Extendable<D>, const D: usize, const N: usize
     }
 
     fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.ct0.plaintext_modulus() as usize)?;
+        dst.write_usize(self.ct1.plaintext_modulus() as usize)?;
         self.dependencies()
-            .iter()
-            .map(|target| dst.write_target(*target))
-            .collect::<IoResult<Vec<_>>>()?;
-        self.quotient
-            .iter()
-            .map(|q| dst.write_target(q.value))
-            .collect::<IoResult<()>>()
+            .into_iter()
+            .chain(self.quotient.iter().map(|q| q.value))
+            .try_for_each(|target| dst.write_target(target))
     }
 
-    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
     where
         Self: Sized,
     {
-        todo!()
+        let plaintext_moduli = [src.read_usize()? as u64, src.read_usize()? as u64];
+        let read_values = |src: &mut Buffer, count: usize| {
+            (0..count)
+                .map(|_| Ok(AssignedValue::new_from_target_unchecked(src.read_target()?)))
+                .collect::<IoResult<Vec<_>>>()
+        };
+        let mut read_ciphertext = |plaintext_modulus| -> IoResult<_> {
+            let values = read_values(src, 2 * N)?;
+            let (ct_0_values, ct_1_values) = values.split_at(N);
+            Ok(AssignedCiphertext::new_from_values(
+                plaintext_modulus,
+                ct_0_values.try_into().unwrap(),
+                ct_1_values.try_into().unwrap(),
+            ))
+        };
+        let ct0 = read_ciphertext(plaintext_moduli[0])?;
+        let ct1 = read_ciphertext(plaintext_moduli[1])?;
+        let quotient = read_values(src, 2 * N)?;
+        Ok(Self::new(ct0, ct1, quotient))
     }
 }
 
 /// `CiphertextChip` is contraint builder for arithmetic operations between bfv ciphertexts
+///
+/// Gates are only added through the wrapped `CircuitBuilder`, so their layout (operations per
+/// `ArithmeticGate`, limbs per `BaseSumGate`, lookups per `LookupGate`) follows the builder's
+/// `CircuitConfig` and no wire count is assumed. The number of rows grows linearly in `N`, and
+/// plonky2 requires `degree_bits + rate_bits <= F::TWO_ADICITY`, i.e. at most `2^29` rows under
+/// both `standard_recursion_config` and `standard_recursion_zk_config` over Goldilocks.
+///
+/// Measured `degree_bits` of the `add_ciphertexts` circuit with `Q = 3329`:
+///
+/// | `N`  | standard | zk |
+/// |------|----------|----|
+/// | 8    | 8        | 14 |
+/// | 256  | 11       | 14 |
+/// | 2048 | 14       | 15 |
+/// | 8192 | 16       | 16 |
+///
+/// The zk config pads small circuits for blinding, and both converge for large `N`; extrapolating,
+/// `N = 2^16` needs about `2^19` rows, well within the bound. In practice the limit is memory:
+/// assigned ciphertexts hold `2N` targets inline, so `N >= 8192` needs a larger thread stack (e.g.
+/// `RUST_MIN_STACK`).
 struct CiphertextChip<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64> {
     arithmetic_chip: ArithmeticChip<F, D, Q>,
 }
@@ -121,21 +175,41 @@ impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
         }
     }
 
-    /// Assigns bfv ciphertexts and constrains the correct formulation of ciphertexts
-    /// Expects input ciphertext is not in NTT form
+    /// Creates a chip over a fresh `CircuitBuilder` for `config`, e.g.
+    /// `CircuitConfig::standard_recursion_zk_config()`
+    pub fn with_config(config: CircuitConfig) -> Self {
+        Self::new(CircuitBuilder::new(config))
+    }
+
+    /// Allocates an `AssignedCiphertext` for each of `cts`, under its plaintext modulus, and
+    /// assigns it. Expects input ciphertext is not in NTT form
     pub fn assign_ciphertexts(
+        &mut self,
         pw: &mut PartialWitness<F>,
-        ct: &Vec<Ciphertext>,
-    ) -> Result<Vec<AssignedCiphertext<F, D, N, Q>>, Error> {
-        todo!()
+        cts: &[Ciphertext],
+    ) -> Result<Vec<AssignedCiphertext<F, D, N, Q>>, VbfvError> {
+        cts.iter()
+            .map(|ct| {
+                let assigned_ct =
+                    AssignedCiphertext::new(&mut self.arithmetic_chip.cb, ct.t as u64);
+                assigned_ct.assign(pw, ct.clone())?;
+                Ok(assigned_ct)
+            })
+            .collect()
     }
 
+    /// Adds ciphertexts slot-wise, so it works in both NTT and coefficients form
     pub fn add_ciphertexts(
         &mut self,
         ct0: AssignedCiphertext<F, D, N, Q>,
         ct1: AssignedCiphertext<F, D, N, Q>,
-    ) -> Result<AssignedCiphertext<F, D, N, Q>, Error> {
-        assert_eq!(ct0.plaintext_modulus(), ct1.plaintext_modulus());
+    ) -> Result<AssignedCiphertext<F, D, N, Q>, VbfvError> {
+        if ct0.plaintext_modulus() != ct1.plaintext_modulus() {
+            return Err(VbfvError::PlaintextModulusMismatch(
+                ct0.plaintext_modulus(),
+                ct1.plaintext_modulus(),
+            ));
+        }
         let mut ct_result_values = vec![];
         let quotient = (0..2 * N)
             .map(|_| AssignedValue::new(&mut self.arithmetic_chip.cb))
@@ -162,40 +236,528 @@ impl<F: RichField + Extendable<D>, const D: usize, const N: usize, const Q: u64>
         Ok(ct_result)
     }
 
+    pub fn sub_ciphertexts(
+        &mut self,
+        ct0: AssignedCiphertext<F, D, N, Q>,
+        ct1: AssignedCiphertext<F, D, N, Q>,
+    ) -> Result<AssignedCiphertext<F, D, N, Q>, VbfvError> {
+        ct0.sub(&mut self.arithmetic_chip, ct1)
+    }
+
+    pub fn neg_ciphertext(
+        &mut self,
+        ct: AssignedCiphertext<F, D, N, Q>,
+    ) -> Result<AssignedCiphertext<F, D, N, Q>, VbfvError> {
+        ct.neg(&mut self.arithmetic_chip)
+    }
+
+    /// Constrains the tensor product `(c_0 d_0, c_0 d_1 + c_1 d_0, c_1 d_1)` of two ciphertexts,
+    /// slot-wise in NTT form and by negacyclic schoolbook multiplication in coefficients form.
+    /// The degree 2 ciphertext is not scaled by `t/Q`.
     pub fn mul_ciphertexts(
         &mut self,
         ct0: AssignedCiphertext<F, D, N, Q>,
         ct1: AssignedCiphertext<F, D, N, Q>,
-    ) -> Result<[AssignedNTTPoly<F, D, N, Q>; 3], Error> {
-        assert_eq!(ct0.plaintext_modulus(), ct1.plaintext_modulus());
-        let mut ct_tensor_product = vec![];
-        ct_tensor_product
-            .push(ct0.ciphertext()[0].mul(&mut self.arithmetic_chip, ct1.ciphertext()[0])?);
-        let cross_product_0 =
-            ct0.ciphertext()[0].mul(&mut self.arithmetic_chip, ct1.ciphertext()[1])?;
-        let cross_product_1 =
-            ct0.ciphertext()[1].mul(&mut self.arithmetic_chip, ct1.ciphertext()[0])?;
-        ct_tensor_product.push(cross_product_0.add(&mut self.arithmetic_chip, cross_product_1)?);
-        ct_tensor_product
-            .push(ct0.ciphertext()[1].mul(&mut self.arithmetic_chip, ct1.ciphertext()[1])?);
-
-        Ok(ct_tensor_product.try_into().unwrap())
+    ) -> Result<[AssignedPoly<F, D, N, Q>; 3], VbfvError> {
+        if ct0.plaintext_modulus() != ct1.plaintext_modulus() {
+            return Err(VbfvError::PlaintextModulusMismatch(
+                ct0.plaintext_modulus(),
+                ct1.plaintext_modulus(),
+            ));
+        }
+        let [c_0, c_1] = *ct0.ciphertext();
+        let [d_0, d_1] = *ct1.ciphertext();
+        let chip = &mut self.arithmetic_chip;
+        let cross_product_0 = c_0.mul(chip, d_1)?;
+        let cross_product_1 = c_1.mul(chip, d_0)?;
+        Ok([
+            c_0.mul(chip, d_0)?,
+            cross_product_0.add(chip, cross_product_1)?,
+            c_1.mul(chip, d_1)?,
+        ])
     }
 
+    /// Constrains relinearization (Version 1) of a degree 2 ciphertext `(c_0, c_1, c_2)` in NTT
+    /// form into `(c_0 + \sum_i rlk_0^(i) * c_2^(i), c_1 + \sum_i rlk_1^(i) * c_2^(i))`, where
+    /// `c_2^(i)` are the base `T` digits of the coefficients of `c_2`.
+    ///
+    /// The digits are witnessed and constrained by `decompose`, so that each is below `T` (the
+    /// top digit below `Q / T^{l-1}`) and they recompose `c_2`.
     pub fn relinearize(
         &mut self,
         plaintext_modulus: u64,
-        degree_2_ct: [AssignedNTTPoly<F, D, N, Q>; 3],
+        degree_2_ct: [AssignedPoly<F, D, N, Q>; 3],
         relinearization_key: AssignedRelinearizationKey<F, D, N, Q>,
-    ) -> Result<AssignedCiphertext<F, D, N, Q>, Error> {
-        todo!()
+    ) -> Result<AssignedCiphertext<F, D, N, Q>, VbfvError> {
+        if N != params::N {
+            return Err(VbfvError::DimensionMismatch {
+                expected: params::N,
+                actual: N,
+            });
+        }
+        if !supports_ntt(N, Q) {
+            return Err(VbfvError::UnsupportedModulus(Q));
+        }
+        let degree_2_ct = [
+            degree_2_ct[0].ntt()?,
+            degree_2_ct[1].ntt()?,
+            degree_2_ct[2].ntt()?,
+        ];
+        let base = relinearization_key.base();
+        let l = relinearization_key.value().len();
+        let digits = self.decompose(degree_2_ct[2], base, l)?;
+
+        let [mut c_0, mut c_1] = [degree_2_ct[0], degree_2_ct[1]];
+        for (digit, [rlk_0, rlk_1]) in digits.iter().zip_eq(relinearization_key.value()) {
+            let c_0_term = rlk_0.mul(&mut self.arithmetic_chip, *digit)?;
+            c_0 = c_0.add(&mut self.arithmetic_chip, c_0_term)?;
+            let c_1_term = rlk_1.mul(&mut self.arithmetic_chip, *digit)?;
+            c_1 = c_1.add(&mut self.arithmetic_chip, c_1_term)?;
+        }
+        Ok(AssignedCiphertext::new_from_values(
+            plaintext_modulus,
+            *c_0.evals(),
+            *c_1.evals(),
+        ))
+    }
+
+    /// Constrains the external product `RGSW(mu) ⊠ ct` of a GSW ciphertext with a ciphertext in
+    /// NTT form, i.e. `\sum_i c_0^(i) * rows_0[i] + \sum_i c_1^(i) * rows_1[i]`, where `c_k^(i)`
+    /// are the base `T` digits of the coefficients of `c_k`. The result decrypts to `mu` times
+    /// the message of `ct`, as `GswCiphertext::external_product`.
+    ///
+    /// A CMux `ct_0 + RGSW(b) ⊠ (ct_1 - ct_0)` selecting `ct_b` for a bit `b`, the step of blind
+    /// rotation, is this product between a `sub_ciphertexts` and an `add_ciphertexts`.
+    pub fn external_product(
+        &mut self,
+        gsw: &AssignedGswCiphertext<F, D, N, Q>,
+        ct: AssignedCiphertext<F, D, N, Q>,
+    ) -> Result<AssignedCiphertext<F, D, N, Q>, VbfvError> {
+        if N != params::N {
+            return Err(VbfvError::DimensionMismatch {
+                expected: params::N,
+                actual: N,
+            });
+        }
+        if !supports_ntt(N, Q) {
+            return Err(VbfvError::UnsupportedModulus(Q));
+        }
+        let mut out: Option<[AssignedNTTPoly<F, D, N, Q>; 2]> = None;
+        for (poly, rows) in ct.ntt_polys()?.iter().zip(gsw.rows()) {
+            let digits = self.decompose(*poly, gsw.base(), rows.len())?;
+            for (digit, row) in digits.iter().zip_eq(rows) {
+                let term = [
+                    row[0].mul(&mut self.arithmetic_chip, *digit)?,
+                    row[1].mul(&mut self.arithmetic_chip, *digit)?,
+                ];
+                out = Some(match out {
+                    None => term,
+                    Some([c_0, c_1]) => [
+                        c_0.add(&mut self.arithmetic_chip, term[0])?,
+                        c_1.add(&mut self.arithmetic_chip, term[1])?,
+                    ],
+                });
+            }
+        }
+        let [c_0, c_1] = out.expect("a GSW ciphertext has at least one row");
+        Ok(AssignedCiphertext::new_from_values(
+            ct.plaintext_modulus(),
+            *c_0.evals(),
+            *c_1.evals(),
+        ))
+    }
+
+    // Gadget-decomposes a polynomial in NTT form into `l` digits in base `T`, returned in NTT form.
+    // Each coefficient of `poly` is decomposed by `ArithmeticChip::gadget_decompose_limbs`, the top
+    // digit taking the remaining quotient as the limbs of a key do.
+    fn decompose(
+        &mut self,
+        poly: AssignedNTTPoly<F, D, N, Q>,
+        base: u64,
+        l: usize,
+    ) -> Result<Vec<AssignedNTTPoly<F, D, N, Q>>, VbfvError> {
+        let coeffs =
+            NTTChip::new(&mut self.arithmetic_chip).ntt_backward(&poly.evals().to_vec())?;
+        let coeff_digits = coeffs
+            .into_iter()
+            .map(|coeff| self.arithmetic_chip.gadget_decompose_limbs(coeff, base, l))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        (0..l)
+            .map(|i| {
+                let digit = coeff_digits.iter().map(|digits| digits[i]).collect_vec();
+                let evals = NTTChip::new(&mut self.arithmetic_chip).ntt_forward(&digit)?;
+                Ok(AssignedNTTPoly::new_from_values(evals.try_into().unwrap()))
+            })
+            .collect()
+    }
+
+    /// Constrains bfv public key encryption
+    /// `(c_0, c_1) = ([p_0 * u + e_1 + delta * m]_Q, [p_1 * u + e_2]_Q)` with `delta = floor(Q/t)`
+    /// and returns the ciphertext in NTT form. Expects the public key in NTT form, and the
+    /// plaintext and the randomness in coefficients form.
+    pub fn encrypt(
+        &mut self,
+        public_key: [AssignedNTTPoly<F, D, N, Q>; 2],
+        plaintext: AssignedPlaintext<F, D, N>,
+        randomness: AssignedEncryptionRandomness<F, D, N, Q>,
+    ) -> Result<AssignedCiphertext<F, D, N, Q>, VbfvError> {
+        if N != params::N {
+            return Err(VbfvError::DimensionMismatch {
+                expected: params::N,
+                actual: N,
+            });
+        }
+        if !supports_ntt(N, Q) {
+            return Err(VbfvError::UnsupportedModulus(Q));
+        }
+        let plaintext_modulus = plaintext.plaintext_modulus();
+        let delta = F::from_canonical_u64(Q / plaintext_modulus);
+        let scaled_plaintext = plaintext
+            .coeffs()
+            .iter()
+            .map(|m| {
+                let cb = &mut self.arithmetic_chip.cb;
+                let scaled = cb.mul_const(delta, *m);
+                // For a power-of-two `t`, the range check of `m` is exactly `[0, t)`, which
+                // implies `delta * m < Q`.
+                if plaintext_modulus.is_power_of_two() {
+                    AssignedValue::new_from_target_unchecked(scaled)
+                } else {
+                    AssignedValue::new_from_target(cb, scaled)
+                }
+            })
+            .collect_vec();
+        // `e_1` and `e_2` are assigned shifted into range, so the shift is removed again to give
+        // their signed coefficients modulo `Q`.
+        let error_offset = F::from_canonical_u64(randomness.error_offset());
+        let [e_1, e_2] = [randomness.e_1, randomness.e_2].map(|e| {
+            e.iter()
+                .map(|coeff| self.arithmetic_chip.sub_with_constant(*coeff, error_offset))
+                .collect::<Result<Vec<_>, VbfvError>>()
+        });
+        let (e_1, e_2) = (e_1?, e_2?);
+
+        let mut ntt_chip = NTTChip::new(&mut self.arithmetic_chip);
+        let [u, e_1, e_2, scaled_plaintext]: [AssignedNTTPoly<F, D, N, Q>; 4] =
+            [randomness.u.to_vec(), e_1, e_2, scaled_plaintext]
+                .iter()
+                .map(|coeffs| {
+                    let evals = ntt_chip.ntt_forward(coeffs)?;
+                    Ok(AssignedNTTPoly::new_from_values(evals.try_into().unwrap()))
+                })
+                .collect::<Result<Vec<_>, VbfvError>>()?
+                .try_into()
+                .unwrap();
+
+        let p_0_u = public_key[0].mul(&mut self.arithmetic_chip, u)?;
+        let c_0 = p_0_u
+            .add(&mut self.arithmetic_chip, e_1)?
+            .add(&mut self.arithmetic_chip, scaled_plaintext)?;
+        let p_1_u = public_key[1].mul(&mut self.arithmetic_chip, u)?;
+        let c_1 = p_1_u.add(&mut self.arithmetic_chip, e_2)?;
+
+        Ok(AssignedCiphertext::new_from_values(
+            plaintext_modulus,
+            *c_0.evals(),
+            *c_1.evals(),
+        ))
+    }
+
+    /// Constrains bfv decryption `m = [round(t/Q * [c_0 + c_1 * s]_Q)]_t` and returns the
+    /// plaintext coefficients, constrained to `[0, t)` by `ArithmeticChip::reduce_mod_t`. Expects
+    /// the secret key `s` in NTT form.
+    pub fn decrypt(
+        &mut self,
+        ct: AssignedCiphertext<F, D, N, Q>,
+        secret_key: AssignedNTTPoly<F, D, N, Q>,
+    ) -> Result<AssignedPlaintext<F, D, N>, VbfvError> {
+        if N != params::N {
+            return Err(VbfvError::DimensionMismatch {
+                expected: params::N,
+                actual: N,
+            });
+        }
+        if !supports_ntt(N, Q) {
+            return Err(VbfvError::UnsupportedModulus(Q));
+        }
+        let plaintext_modulus = ct.plaintext_modulus();
+        // `rounded * Q + remainder` must not wrap around the field for any `rounded` of the bit
+        // length of `t`, which bounds `t * x + floor(Q/2)` for any `x \in [0, Q)` as well.
+        let rounded_bits = 64 - plaintext_modulus.leading_zeros();
+        if (Q as u128) << rounded_bits >= F::ORDER as u128 {
+            return Err(VbfvError::UnsupportedModulus(Q));
+        }
+
+        // `[c_0 + c_1 * s]_Q` is computed in NTT form and then transformed back into coefficients.
+        let [c_0, c_1] = ct.ntt_polys()?;
+        let c_1_s = c_1.mul(&mut self.arithmetic_chip, secret_key)?;
+        let raw = c_0.add(&mut self.arithmetic_chip, c_1_s)?;
+        let raw_coeffs =
+            NTTChip::new(&mut self.arithmetic_chip).ntt_backward(&raw.evals().to_vec())?;
+
+        let plaintext_coeffs = raw_coeffs
+            .iter()
+            .map(|x| {
+                let cb = &mut self.arithmetic_chip.cb;
+                let rounded = cb.add_virtual_target();
+                let remainder = AssignedValue::<F, D, Q>::new(cb);
+                cb.add_simple_generator(ScaleAndRoundGenerator::<Q> {
+                    plaintext_modulus,
+                    x: x.value,
+                    rounded,
+                    remainder: remainder.value,
+                });
+                self.constrain_scale_and_round(*x, rounded, remainder, plaintext_modulus);
+                // `rounded \in [0, t]`, where `t` wraps around to `0`.
+                let rounded = AssignedValue::new_from_target_unchecked(rounded);
+                let plaintext = self
+                    .arithmetic_chip
+                    .reduce_mod_t(rounded, plaintext_modulus)?;
+                Ok(plaintext.value)
+            })
+            .collect::<Result<Vec<_>, VbfvError>>()?;
+
+        Ok(AssignedPlaintext::new_from_targets(
+            plaintext_modulus,
+            plaintext_coeffs.try_into().unwrap(),
+        ))
+    }
+
+    /// Constrains `t * x + floor(Q/2) = rounded * Q + remainder` for `remainder \in [0, Q)`, so
+    /// that `rounded = round(t/Q * x)`. `rounded` is range checked to the bit length of `t`, which
+    /// `decrypt` checks keeps both sides below the field order.
+    fn constrain_scale_and_round(
+        &mut self,
+        x: AssignedValue<F, D, Q>,
+        rounded: Target,
+        remainder: AssignedValue<F, D, Q>,
+        plaintext_modulus: u64,
+    ) {
+        let cb = &mut self.arithmetic_chip.cb;
+        cb.range_check(rounded, (64 - plaintext_modulus.leading_zeros()) as usize);
+        let t = F::from_canonical_u64(plaintext_modulus);
+        let mut lhs = cb.mul_const(t, x.value);
+        lhs = cb.add_const(lhs, F::from_canonical_u64(Q / 2));
+        let rhs = cb.mul_const_add(F::from_canonical_u64(Q), rounded, remainder.value);
+        cb.connect(lhs, rhs);
+    }
+
+    /// Constrains `ct0` and `ct1` to decrypt to the same plaintext under the secret key `s`, given
+    /// in NTT form, without revealing it. Both are decrypted with `decrypt`, so that two
+    /// encryptions of the same message with different noise are equal after the scale and round.
+    pub fn assert_same_plaintext(
+        &mut self,
+        ct0: AssignedCiphertext<F, D, N, Q>,
+        ct1: AssignedCiphertext<F, D, N, Q>,
+        secret_key: AssignedNTTPoly<F, D, N, Q>,
+    ) -> Result<(), VbfvError> {
+        if ct0.plaintext_modulus() != ct1.plaintext_modulus() {
+            return Err(VbfvError::PlaintextModulusMismatch(
+                ct0.plaintext_modulus(),
+                ct1.plaintext_modulus(),
+            ));
+        }
+        let pt0 = self.decrypt(ct0, secret_key)?;
+        let pt1 = self.decrypt(ct1, secret_key)?;
+
+        // `decrypt` reduces the coefficients to `[0, t)`, so they are equal mod `t` iff equal
+        let cb = &mut self.arithmetic_chip.cb;
+        for (x, y) in pt0.coeffs().iter().zip_eq(pt1.coeffs()) {
+            cb.connect(*x, *y);
+        }
+        Ok(())
+    }
+}
+
+/// A proof of a `vbfv` circuit over Goldilocks with Poseidon hashing, with its public inputs
+pub type VbfvProof = ProofWithPublicInputs<GoldilocksField, PoseidonGoldilocksConfig, 2>;
+
+/// The data needed to verify a `VbfvProof` without the prover circuit
+pub type VbfvVerifierData = VerifierCircuitData<GoldilocksField, PoseidonGoldilocksConfig, 2>;
+
+/// The prover and verifier data of a `vbfv` circuit
+pub type VbfvCircuitData = CircuitData<GoldilocksField, PoseidonGoldilocksConfig, 2>;
+
+/// The ids of the witness generators of this crate, in the order of their tags in
+/// `VbfvGeneratorSerializer`
+const VBFV_GENERATOR_IDS: [&str; 5] = [
+    "ArithmeticOpsGenerator",
+    "ReduceModTGenerator",
+    "GadgetDecompositionGenerator",
+    "CiphertextOpsGenerator",
+    "ScaleAndRoundGenerator",
+];
+
+/// Serializes the witness generators of the `vbfv` circuits over ciphertexts of degree `N` modulo
+/// `Q` along with the default ones of plonky2, so that the prover data of any such circuit can be
+/// saved with `circuit_data_to_bytes` and reloaded without rebuilding the circuit
+///
+/// A generator is written after a tag, which is `1 + i` for the `i`-th of `VBFV_GENERATOR_IDS` and
+/// `0` for a default generator, tagged again by `DefaultGeneratorSerializer`.
+#[derive(Debug, Default)]
+pub struct VbfvGeneratorSerializer<const N: usize, const Q: u64> {
+    default: DefaultGeneratorSerializer<PoseidonGoldilocksConfig, 2>,
+}
+
+impl<const N: usize, const Q: u64> WitnessGeneratorSerializer<GoldilocksField, 2>
+    for VbfvGeneratorSerializer<N, Q>
+{
+    fn read_generator(
+        &self,
+        buf: &mut Buffer,
+        common_data: &CommonCircuitData<GoldilocksField, 2>,
+    ) -> IoResult<WitnessGeneratorRef<GoldilocksField, 2>> {
+        type F = GoldilocksField;
+        const D: usize = 2;
+        fn adapt<G: SimpleGenerator<F, D>>(generator: G) -> WitnessGeneratorRef<F, D> {
+            WitnessGeneratorRef::new(generator.adapter())
+        }
+
+        let generator = match buf.read_u32()? {
+            0 => return self.default.read_generator(buf, common_data),
+            1 => adapt(ArithmeticOpsGenerator::<F, D, Q>::deserialize(
+                buf,
+                common_data,
+            )?),
+            2 => adapt(<ReduceModTGenerator as SimpleGenerator<F, D>>::deserialize(
+                buf,
+                common_data,
+            )?),
+            3 => adapt(
+                <GadgetDecompositionGenerator as SimpleGenerator<F, D>>::deserialize(
+                    buf,
+                    common_data,
+                )?,
+            ),
+            4 => adapt(CiphertextOpsGenerator::<F, D, N, Q>::deserialize(
+                buf,
+                common_data,
+            )?),
+            5 => adapt(
+                <ScaleAndRoundGenerator<Q> as SimpleGenerator<F, D>>::deserialize(
+                    buf,
+                    common_data,
+                )?,
+            ),
+            _ => return Err(IoError),
+        };
+        Ok(generator)
+    }
+
+    fn write_generator(
+        &self,
+        buf: &mut Vec<u8>,
+        generator: &WitnessGeneratorRef<GoldilocksField, 2>,
+        common_data: &CommonCircuitData<GoldilocksField, 2>,
+    ) -> IoResult<()> {
+        let id = generator.0.id();
+        match VBFV_GENERATOR_IDS.iter().position(|vbfv_id| *vbfv_id == id) {
+            Some(i) => {
+                buf.write_u32(i as u32 + 1)?;
+                generator.0.serialize(buf, common_data)
+            }
+            None => {
+                buf.write_u32(0)?;
+                self.default.write_generator(buf, generator, common_data)
+            }
+        }
     }
 }
 
+/// Serializes the data to verify a `VbfvProof`, so that a verifier can load it without the prover
+/// circuit data and without rebuilding the circuit
+pub fn verifier_data_to_bytes(verifier_data: &VbfvVerifierData) -> Result<Vec<u8>, Error> {
+    verifier_data
+        .to_bytes(&DefaultGateSerializer)
+        .map_err(Error::msg)
+}
+
+/// Deserializes verifier data written by `verifier_data_to_bytes`
+pub fn verifier_data_from_bytes(bytes: Vec<u8>) -> Result<VbfvVerifierData, Error> {
+    VbfvVerifierData::from_bytes(bytes, &DefaultGateSerializer).map_err(Error::msg)
+}
+
+/// Serializes the prover and verifier data of a `vbfv` circuit over ciphertexts of degree `N`
+/// modulo `Q`, with its witness generators, so that a prover can reload it with
+/// `circuit_data_from_bytes` instead of rebuilding the circuit
+pub fn circuit_data_to_bytes<const N: usize, const Q: u64>(
+    data: &VbfvCircuitData,
+) -> Result<Vec<u8>, Error> {
+    data.to_bytes(
+        &DefaultGateSerializer,
+        &VbfvGeneratorSerializer::<N, Q>::default(),
+    )
+    .map_err(Error::msg)
+}
+
+/// Deserializes circuit data written by `circuit_data_to_bytes` with the same `N` and `Q`
+pub fn circuit_data_from_bytes<const N: usize, const Q: u64>(
+    bytes: &[u8],
+) -> Result<VbfvCircuitData, Error> {
+    VbfvCircuitData::from_bytes(
+        bytes,
+        &DefaultGateSerializer,
+        &VbfvGeneratorSerializer::<N, Q>::default(),
+    )
+    .map_err(Error::msg)
+}
+
+/// Verifies a proof serialized with `VbfvProof::to_bytes` using only the verifier data, e.g. as
+/// loaded by `verifier_data_from_bytes`
+pub fn verify_proof_bytes(
+    verifier_data: &VbfvVerifierData,
+    proof_bytes: Vec<u8>,
+) -> Result<(), Error> {
+    let proof = VbfvProof::from_bytes(proof_bytes, &verifier_data.common)?;
+    verifier_data.verify(proof)
+}
+
+/// Builds the circuit adding two bfv ciphertexts of degree `N` modulo `Q` and proves it for
+/// `ct0` and `ct1`.
+///
+/// Returns the proof, whose public inputs are the sum in NTT form (or in coefficients form if `Q`
+/// does not support NTT), and the data to verify it, so that a separate party can check the
+/// addition without rebuilding the circuit.
+pub fn verify_ciphertext_addition<const N: usize, const Q: u64>(
+    ct0: Ciphertext,
+    ct1: Ciphertext,
+) -> Result<(VbfvProof, VbfvVerifierData), Error> {
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    let config = CircuitConfig::standard_recursion_config();
+    let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::with_config(config);
+    let mut pw = PartialWitness::new();
+    let [assigned_ct0, assigned_ct1] = ciphertext_chip
+        .assign_ciphertexts(&mut pw, &[ct0, ct1])?
+        .try_into()
+        .unwrap();
+    let assigned_sum = ciphertext_chip.add_ciphertexts(assigned_ct0, assigned_ct1)?;
+
+    let mut builder = ciphertext_chip.arithmetic_chip.cb;
+    assigned_sum.register_as_public_input(&mut builder);
+
+    let data = builder.build::<C>();
+    let proof = data.prove(pw)?;
+    Ok((proof, data.verifier_data()))
+}
+
+/// Builds the circuit adding two bfv ciphertexts of degree `N` modulo `Q`, proves it for `ct0`
+/// and `ct1` and verifies the proof, e.g. to benchmark proving
+pub fn prove_add_ciphertexts<const N: usize, const Q: u64>(
+    ct0: Ciphertext,
+    ct1: Ciphertext,
+) -> Result<(), Error> {
+    let (proof, verifier_data) = verify_ciphertext_addition::<N, Q>(ct0, ct1)?;
+    verifier_data.verify(proof)
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::{Error, Ok, Result};
-    use itertools::Itertools;
+    use itertools::{chain, Itertools};
     use plonky2::{
         field::{
             extension::Extendable,
@@ -203,26 +765,79 @@ mod tests {
             types::{Field, Field64, PrimeField64},
         },
         hash::hash_types::RichField,
-        iop::witness::PartialWitness,
+        iop::witness::{PartialWitness, WitnessWrite},
         plonk::{
             circuit_builder::CircuitBuilder,
             circuit_data::CircuitConfig,
             config::{GenericConfig, PoseidonGoldilocksConfig},
         },
+        util::log2_ceil,
     };
-    use rand::SeedableRng;
+    use rand::{seq::SliceRandom, SeedableRng};
+    use std::collections::HashSet;
 
     use crate::{
-        bfv::{Plaintext, SecretKey},
-        vbfv::{assigned::AssignedCiphertext, ciphertext_chip::CiphertextChip, ntt_forward},
+        bfv::{
+            poly::Poly, Ciphertext, GaussianSampler, GswCiphertext, Plaintext, PublicKey,
+            SecretKey, UniformSampler,
+        },
+        vbfv::{
+            assigned::{
+                AssignedCiphertext, AssignedEncryptionRandomness, AssignedGswCiphertext,
+                AssignedNTTPoly, AssignedPlaintext, AssignedPoly, AssignedRelinearizationKey,
+                AssignedValue,
+            },
+            ciphertext_chip::{
+                circuit_data_from_bytes, circuit_data_to_bytes, verifier_data_from_bytes,
+                verifier_data_to_bytes, verify_ciphertext_addition, verify_proof_bytes,
+                CiphertextChip, VBFV_GENERATOR_IDS,
+            },
+            estimate_circuit_size, is_provable, ntt_forward, supports_ntt, test_vectors,
+            CircuitOps, VbfvError,
+        },
     };
 
+    /// Constrains the addition of two ciphertexts and returns the public inputs of its verified
+    /// proof, the NTT form of the sum.
+    fn add_ciphertexts_public_inputs<const N: usize, const Q: u64>(
+        ciphertext1: Ciphertext,
+        ciphertext2: Ciphertext,
+        t: u64,
+    ) -> Result<Vec<GoldilocksField>, Error> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<<C as GenericConfig<D>>::F, D>::new(config);
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+
+        let assigned_ct1 =
+            AssignedCiphertext::<F, D, N, Q>::new(&mut ciphertext_chip.arithmetic_chip.cb, t);
+        let assigned_ct2 =
+            AssignedCiphertext::<F, D, N, Q>::new(&mut ciphertext_chip.arithmetic_chip.cb, t);
+        let assigned_ct_added = ciphertext_chip.add_ciphertexts(assigned_ct1, assigned_ct2)?;
+
+        let mut builder = ciphertext_chip.arithmetic_chip.cb;
+        assigned_ct_added.register_as_public_input(&mut builder);
+
+        // assign witnesses
+        let mut pw = PartialWitness::new();
+        assigned_ct1.assign(&mut pw, ciphertext1)?;
+        assigned_ct2.assign(&mut pw, ciphertext2)?;
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        let public_inputs = proof.public_inputs.clone();
+        data.verify(proof)?;
+        Ok(public_inputs)
+    }
+
     #[test]
     fn test_add_ciphertexts() -> Result<(), Error> {
         const D: usize = 2;
         const N: usize = 8;
         const Q: u64 = 3329;
-        type C = PoseidonGoldilocksConfig;
         type F = GoldilocksField;
         for t in vec![2, 4, 8, 16, 32].iter() {
             let msg_1 = vec![0, 1, 2, 3, 4, 5, 6, 7];
@@ -247,23 +862,8 @@ mod tests {
             let add_ciphertext = ciphertext1.clone() + ciphertext2.clone();
 
             // constrain adding ciphertexts
-            let config = CircuitConfig::standard_recursion_config();
-            let mut builder = CircuitBuilder::<<C as GenericConfig<D>>::F, D>::new(config);
-            let mut ciphertext_chip = CiphertextChip::new(builder);
-
-            let assigned_ct1 = AssignedCiphertext::<F, D, N, Q>::new(&mut builder, t);
-            let assigned_ct2 = AssignedCiphertext::<F, D, N, Q>::new(&mut builder, t);
-            let assigned_ct_added = ciphertext_chip.add_ciphertexts(assigned_ct1, assigned_ct2)?;
-
-            assigned_ct_added.register_as_public_input(&mut builder);
-
-            // assign witnesses
-            let mut pw = PartialWitness::new();
-            assigned_ct1.assign(&mut pw, ciphertext1)?;
-            assigned_ct2.assign(&mut pw, ciphertext2)?;
-
-            let data = builder.build::<C>();
-            let proof = data.prove(pw)?;
+            let public_inputs =
+                add_ciphertexts_public_inputs::<N, Q>(ciphertext1, ciphertext2, *t as u64)?;
 
             let add_ciphertext = add_ciphertext
                 .c_0
@@ -280,6 +880,572 @@ mod tests {
                 .into_iter()
                 .chain(expected_1.into_iter())
                 .collect_vec();
+            public_inputs
+                .iter()
+                .zip_eq(expected)
+                .for_each(|(actual, expected)| {
+                    assert_eq!(*actual, expected);
+                });
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_ciphertexts_fixed_vectors() -> Result<(), Error> {
+        const N: usize = test_vectors::N;
+        const Q: u64 = test_vectors::Q;
+        let ciphertext1 = test_vectors::ciphertext(test_vectors::TESTCT1);
+        let ciphertext2 = test_vectors::ciphertext(test_vectors::TESTCT2);
+
+        let public_inputs = add_ciphertexts_public_inputs::<N, Q>(
+            ciphertext1,
+            ciphertext2,
+            test_vectors::T as u64,
+        )?;
+
+        let expected = test_vectors::TESTSUMHAT.concat();
+        public_inputs
+            .iter()
+            .zip_eq(expected)
+            .for_each(|(actual, expected)| {
+                assert_eq!(actual.to_canonical_u64(), expected);
+            });
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_ciphertext_addition() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type F = GoldilocksField;
+        let t = 16;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(41);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+        let ciphertext1 = Plaintext::rand(N, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext2 = Plaintext::rand(N, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+        let add_ciphertext = &ciphertext1 + &ciphertext2;
+
+        let (proof, verifier_data) = verify_ciphertext_addition::<N, Q>(ciphertext1, ciphertext2)?;
+
+        let expected = [add_ciphertext.c_0, add_ciphertext.c_1]
+            .into_iter()
+            .flat_map(|c| {
+                ntt_forward::<F, D, Q>(
+                    &(c % (Q as i64, N))
+                        .val()
+                        .iter()
+                        .map(|coeff| F::from_canonical_i64(*coeff))
+                        .collect_vec(),
+                )
+            })
+            .collect_vec();
+        assert_eq!(proof.public_inputs, expected);
+
+        // Swapping a single public input for another value breaks the proof.
+        let mut tampered = proof.clone();
+        tampered.public_inputs[0] += F::ONE;
+        assert!(verifier_data.verify(tampered).is_err());
+
+        verifier_data.verify(proof)
+    }
+
+    #[test]
+    fn test_verify_with_serialized_verifier_data() -> Result<(), Error> {
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        let t = 16;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(43);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+        let ciphertext1 = Plaintext::rand(N, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext2 = Plaintext::rand(N, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+
+        // The prover ships bytes only, the verifier never sees the circuit or the prover data.
+        let (proof, verifier_data) = verify_ciphertext_addition::<N, Q>(ciphertext1, ciphertext2)?;
+        let verifier_bytes = verifier_data_to_bytes(&verifier_data)?;
+        let mut proof_bytes = proof.to_bytes();
+        drop((proof, verifier_data));
+
+        let verifier_data = verifier_data_from_bytes(verifier_bytes)?;
+        verify_proof_bytes(&verifier_data, proof_bytes.clone())?;
+
+        // Flipping a byte of the proof breaks it.
+        let last = proof_bytes.len() - 1;
+        proof_bytes[last] ^= 1;
+        assert!(verify_proof_bytes(&verifier_data, proof_bytes).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reprove_with_serialized_circuit_data() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let t = 16;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(44);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+        let ciphertext1 = Plaintext::rand(N, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext2 = Plaintext::rand(N, t, &mut rng).encrypt(&public_key, std_dev, &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::with_config(config);
+        let mut pw = PartialWitness::new();
+        let [ct0, ct1] = ciphertext_chip
+            .assign_ciphertexts(&mut pw, &[ciphertext1.clone(), ciphertext2.clone()])?
+            .try_into()
+            .unwrap();
+        let sum = ciphertext_chip.add_ciphertexts(ct0, ct1)?;
+        let mut builder = ciphertext_chip.arithmetic_chip.cb;
+        sum.register_as_public_input(&mut builder);
+        let data = builder.build::<C>();
+
+        // The witness only refers to targets, so it still fits the reloaded circuit.
+        let bytes = circuit_data_to_bytes::<N, Q>(&data)?;
+        drop(data);
+        let data = circuit_data_from_bytes::<N, Q>(&bytes)?;
+        let proof = data.prove(pw)?;
+
+        let (expected, _) = verify_ciphertext_addition::<N, Q>(ciphertext1, ciphertext2)?;
+        assert_eq!(proof.public_inputs, expected.public_inputs);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_reprove_mul_relinearize_decrypt_with_serialized_circuit_data() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let t = 4;
+        let base = 16;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(45);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+        let rlk = secret_key
+            .relin_key_gen_1(Q as i64, std_dev, &mut rng, base as i64)
+            .unwrap();
+        let ciphertext1 =
+            Plaintext::new(vec![0, 1, 2, 3, 0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext2 =
+            Plaintext::new(vec![1, 0, 0, 0, 0, 0, 0, 1], t).encrypt(&public_key, std_dev, &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::with_config(config);
+        let mut pw = PartialWitness::new();
+        let [ct0, ct1] = ciphertext_chip
+            .assign_ciphertexts(&mut pw, &[ciphertext1, ciphertext2])?
+            .try_into()
+            .unwrap();
+        let assigned_rlk =
+            AssignedRelinearizationKey::new(&mut ciphertext_chip.arithmetic_chip.cb, base)?;
+        let assigned_sk = AssignedNTTPoly::new(&mut ciphertext_chip.arithmetic_chip.cb);
+        let product = ciphertext_chip.mul_ciphertexts(ct0, ct1)?;
+        let relinearized = ciphertext_chip.relinearize(t as u64, product, assigned_rlk.clone())?;
+        // Adding a ciphertext to the product brings in the last of the vbfv generators.
+        let sum = ciphertext_chip.add_ciphertexts(relinearized, ct0)?;
+        let decrypted = ciphertext_chip.decrypt(sum, assigned_sk)?;
+        let mut builder = ciphertext_chip.arithmetic_chip.cb;
+        decrypted.register_as_public_input(&mut builder);
+        assigned_rlk.assign(&mut pw, &rlk)?;
+        assigned_sk.assign(&mut pw, secret_key.poly.val())?;
+        let data = builder.build::<C>();
+
+        // The circuit runs a generator of each of the tags of `VbfvGeneratorSerializer`.
+        let ids = data
+            .prover_only
+            .generators
+            .iter()
+            .map(|generator| generator.0.id())
+            .collect_vec();
+        for id in VBFV_GENERATOR_IDS {
+            assert!(ids.iter().any(|generator_id| generator_id == id));
+        }
+        let expected = data.prove(pw.clone())?;
+
+        // Each of them is read back, so the reloaded circuit computes the same witness.
+        let bytes = circuit_data_to_bytes::<N, Q>(&data)?;
+        drop(data);
+        let data = circuit_data_from_bytes::<N, Q>(&bytes)?;
+        let proof = data.prove(pw)?;
+        assert_eq!(proof.public_inputs, expected.public_inputs);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_assign_many() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let t = 8;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+        let cts = (0..4)
+            .map(|i| {
+                Plaintext::new(vec![i, 1, 0, 0, 0, 0, 0, 7], t).encrypt(
+                    &public_key,
+                    std_dev,
+                    &mut rng,
+                )
+            })
+            .collect_vec();
+
+        // constrain `((ct_0 + ct_1) + ct_2) + ct_3`
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+        let assigned_cts = (0..4)
+            .map(|_| {
+                AssignedCiphertext::<F, D, N, Q>::new(
+                    &mut ciphertext_chip.arithmetic_chip.cb,
+                    t as u64,
+                )
+            })
+            .collect_vec();
+        let mut sum = assigned_cts[0];
+        for assigned_ct in &assigned_cts[1..] {
+            sum = ciphertext_chip.add_ciphertexts(sum, *assigned_ct)?;
+        }
+        let mut builder = ciphertext_chip.arithmetic_chip.cb;
+        sum.register_as_public_input(&mut builder);
+
+        let mut pw = PartialWitness::new();
+        assert!(matches!(
+            AssignedCiphertext::assign_many(&mut pw, &assigned_cts, &cts[..3]),
+            Err(VbfvError::DimensionMismatch {
+                expected: 4,
+                actual: 3
+            })
+        ));
+        AssignedCiphertext::assign_many(&mut pw, &assigned_cts, &cts)?;
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        let expected_sum = Ciphertext::add_many(&cts);
+        assert_eq!(
+            expected_sum.decrypt(&secret_key),
+            Plaintext::new(vec![6, 4, 0, 0, 0, 0, 0, 4], t)
+        );
+        let expected = [&expected_sum.c_0, &expected_sum.c_1]
+            .into_iter()
+            .flat_map(|poly| {
+                let coeffs = poly.val().iter().map(|coeff| F::from_canonical_i64(*coeff));
+                ntt_forward::<F, D, Q>(&coeffs.collect_vec())
+            })
+            .collect_vec();
+        assert_eq!(proof.public_inputs, expected);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_estimate_add_ciphertexts_size() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        fn helper<const N: usize, const Q: u64>() {
+            let config = CircuitConfig::standard_recursion_config();
+            let ops = CircuitOps {
+                ciphertexts: 2,
+                adds: 1,
+                ..Default::default()
+            };
+            let estimate = estimate_circuit_size::<N, Q>(&config, ops);
+
+            let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::with_config(config);
+            let cb = &mut ciphertext_chip.arithmetic_chip.cb;
+            let assigned_ct1 = AssignedCiphertext::<F, D, N, Q>::new(cb, 16);
+            let assigned_ct2 = AssignedCiphertext::<F, D, N, Q>::new(cb, 16);
+            let assigned_ct_added = ciphertext_chip
+                .add_ciphertexts(assigned_ct1, assigned_ct2)
+                .unwrap();
+            let mut builder = ciphertext_chip.arithmetic_chip.cb;
+            assigned_ct_added.register_as_public_input(&mut builder);
+
+            let data = builder.build::<C>();
+            let rows = data.common.degree();
+            assert!(
+                estimate.gates <= rows && rows < 2 * estimate.gates,
+                "N = {N}, Q = {Q}: estimated {} gates for {rows} rows",
+                estimate.gates
+            );
+            assert_eq!(estimate.degree_bits(), data.common.degree_bits());
+        }
+        // With and without lookups, and with a power of two `Q`
+        helper::<8, 3329>();
+        helper::<256, 3329>();
+        helper::<256, 4096>();
+        helper::<64, 65537>();
+    }
+
+    #[test]
+    fn test_add_ciphertexts_zk_config() {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let config = CircuitConfig::standard_recursion_zk_config();
+        let rate_bits = config.fri_config.rate_bits;
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::with_config(config);
+        let assigned_ct1 =
+            AssignedCiphertext::<F, D, N, Q>::new(&mut ciphertext_chip.arithmetic_chip.cb, 16);
+        let assigned_ct2 =
+            AssignedCiphertext::<F, D, N, Q>::new(&mut ciphertext_chip.arithmetic_chip.cb, 16);
+        let assigned_ct_added = ciphertext_chip
+            .add_ciphertexts(assigned_ct1, assigned_ct2)
+            .unwrap();
+
+        let mut builder = ciphertext_chip.arithmetic_chip.cb;
+        assigned_ct_added.register_as_public_input(&mut builder);
+
+        let data = builder.build::<C>();
+        assert!(data.common.config.zero_knowledge);
+        assert_eq!(data.common.num_public_inputs, 2 * N);
+        assert!(data.common.degree_bits() + rate_bits <= F::TWO_ADICITY);
+    }
+
+    #[test]
+    fn test_add_ciphertexts_plaintext_modulus_mismatch() {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type F = GoldilocksField;
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+
+        let assigned_ct1 =
+            AssignedCiphertext::<F, D, N, Q>::new(&mut ciphertext_chip.arithmetic_chip.cb, 4);
+        let assigned_ct2 =
+            AssignedCiphertext::<F, D, N, Q>::new(&mut ciphertext_chip.arithmetic_chip.cb, 8);
+        let err = ciphertext_chip
+            .add_ciphertexts(assigned_ct1, assigned_ct2)
+            .unwrap_err();
+        assert!(matches!(err, VbfvError::PlaintextModulusMismatch(4, 8)));
+
+        // the variant survives the conversion into `anyhow::Error`
+        let err: Error = err.into();
+        assert!(matches!(
+            err.downcast_ref::<VbfvError>(),
+            Some(VbfvError::PlaintextModulusMismatch(4, 8))
+        ));
+    }
+
+    #[test]
+    fn test_add_ciphertexts_coeff_form() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        // `2N` does not divide `Q-1`, so ciphertexts are kept in coefficients form
+        const Q: u64 = 65536;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let t = 16;
+        let msg_1 = vec![0, 1, 2, 3, 4, 5, 6, 7];
+        let msg_2 = vec![7, 6, 5, 4, 3, 2, 1, 0];
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(36);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+
+        let ciphertext1 = Plaintext::new(msg_1, t).encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext2 = Plaintext::new(msg_2, t).encrypt(&public_key, std_dev, &mut rng);
+        let add_ciphertext = ciphertext1.clone() + ciphertext2.clone();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+
+        let assigned_ct1 = AssignedCiphertext::<F, D, N, Q>::new(
+            &mut ciphertext_chip.arithmetic_chip.cb,
+            t as u64,
+        );
+        let assigned_ct2 = AssignedCiphertext::<F, D, N, Q>::new(
+            &mut ciphertext_chip.arithmetic_chip.cb,
+            t as u64,
+        );
+        let assigned_ct_added = ciphertext_chip.add_ciphertexts(assigned_ct1, assigned_ct2)?;
+
+        let mut builder = ciphertext_chip.arithmetic_chip.cb;
+        assigned_ct_added.register_as_public_input(&mut builder);
+
+        let mut pw = PartialWitness::new();
+        assigned_ct1.assign(&mut pw, ciphertext1)?;
+        assigned_ct2.assign(&mut pw, ciphertext2)?;
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let expected = [add_ciphertext.c_0, add_ciphertext.c_1]
+            .into_iter()
+            .flat_map(|c| (c % (Q as i64, N)).val().to_owned())
+            .map(F::from_canonical_i64)
+            .collect_vec();
+        proof
+            .public_inputs
+            .iter()
+            .zip_eq(expected)
+            .for_each(|(actual, expected)| {
+                assert_eq!(*actual, expected);
+            });
+
+        data.verify(proof)
+    }
+
+    /// Proves the tensor product of two ciphertexts modulo `Q` and checks it against the
+    /// products of their polynomials, in NTT form if `Q` supports NTT.
+    fn mul_ciphertexts_helper<const Q: u64>() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let t = 16;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(37);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+        let ct1 =
+            Plaintext::new(vec![0, 1, 2, 3, 4, 5, 6, 7], t).encrypt(&public_key, std_dev, &mut rng);
+        let ct2 =
+            Plaintext::new(vec![7, 6, 5, 4, 3, 2, 1, 0], t).encrypt(&public_key, std_dev, &mut rng);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::with_config(config);
+        let mut pw = PartialWitness::new();
+        let [assigned_ct1, assigned_ct2] = ciphertext_chip
+            .assign_ciphertexts(&mut pw, &[ct1.clone(), ct2.clone()])?
+            .try_into()
+            .unwrap();
+        let product = ciphertext_chip.mul_ciphertexts(assigned_ct1, assigned_ct2)?;
+        for poly in product.iter() {
+            for value in poly.values() {
+                value.register_as_public_input(&mut ciphertext_chip.arithmetic_chip.cb);
+            }
+        }
+
+        let data = ciphertext_chip.arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let ring = (Q as i64, N);
+        let [c_0, c_1, d_0, d_1] = [ct1.c_0, ct1.c_1, ct2.c_0, ct2.c_1];
+        let expected = [
+            c_0.clone() * d_0.clone(),
+            c_0 * d_1.clone() + c_1.clone() * d_0,
+            c_1 * d_1,
+        ]
+        .into_iter()
+        .flat_map(|poly| {
+            let coeffs = (poly % ring)
+                .val()
+                .iter()
+                .map(|c| F::from_canonical_i64(*c))
+                .collect_vec();
+            if supports_ntt(N, Q) {
+                ntt_forward::<F, D, Q>(&coeffs)
+            } else {
+                coeffs
+            }
+        })
+        .collect_vec();
+        assert_eq!(proof.public_inputs, expected);
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_mul_ciphertexts() -> Result<(), Error> {
+        mul_ciphertexts_helper::<3329>()?;
+        // `2N` does not divide `Q-1`, so the product is computed in coefficients form
+        mul_ciphertexts_helper::<65536>()
+    }
+
+    #[test]
+    fn test_sub_neg_ciphertexts() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        for t in [2, 16].iter() {
+            let msg_1 = vec![0, 1, 2, 3, 4, 5, 6, 7];
+            let msg_2 = vec![7, 6, 5, 4, 3, 2, 1, 0];
+            let std_dev = 3.2;
+            // Prepare ciphertexts
+            let mut rng = rand::rngs::StdRng::seed_from_u64(35);
+
+            let secret_key = SecretKey::generate(N, &mut rng);
+            let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+
+            let ciphertext1 = Plaintext::new(msg_1, *t).encrypt(&public_key, std_dev, &mut rng);
+            let ciphertext2 = Plaintext::new(msg_2, *t).encrypt(&public_key, std_dev, &mut rng);
+
+            let sub_ciphertext = ciphertext1.clone() - ciphertext2.clone();
+            let neg_ciphertext = -ciphertext1.clone();
+
+            // constrain subtraction and negation
+            let config = CircuitConfig::standard_recursion_config();
+            let builder = CircuitBuilder::<F, D>::new(config);
+            let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+
+            let assigned_ct1 = AssignedCiphertext::<F, D, N, Q>::new(
+                &mut ciphertext_chip.arithmetic_chip.cb,
+                *t as u64,
+            );
+            let assigned_ct2 = AssignedCiphertext::<F, D, N, Q>::new(
+                &mut ciphertext_chip.arithmetic_chip.cb,
+                *t as u64,
+            );
+            let assigned_ct_sub = ciphertext_chip.sub_ciphertexts(assigned_ct1, assigned_ct2)?;
+            let assigned_ct_neg = ciphertext_chip.neg_ciphertext(assigned_ct1)?;
+
+            let mut builder = ciphertext_chip.arithmetic_chip.cb;
+            assigned_ct_sub.register_as_public_input(&mut builder);
+            assigned_ct_neg.register_as_public_input(&mut builder);
+
+            // assign witnesses
+            let mut pw = PartialWitness::new();
+            assigned_ct1.assign(&mut pw, ciphertext1)?;
+            assigned_ct2.assign(&mut pw, ciphertext2)?;
+
+            let data = builder.build::<C>();
+            let proof = data.prove(pw)?;
+
+            let expected = [
+                sub_ciphertext.c_0,
+                sub_ciphertext.c_1,
+                neg_ciphertext.c_0,
+                neg_ciphertext.c_1,
+            ]
+            .into_iter()
+            .flat_map(|c| {
+                ntt_forward::<F, D, Q>(
+                    &(c % (Q as i64, N))
+                        .val()
+                        .iter()
+                        .map(|coeff| F::from_canonical_i64(*coeff))
+                        .collect_vec(),
+                )
+            })
+            .collect_vec();
             proof
                 .public_inputs
                 .iter()
@@ -292,4 +1458,574 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_decrypt() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        for t in [3, 4, 5, 16].iter() {
+            let msg = vec![0, 1, 2, 3, 4, 5, 6, 7];
+            let std_dev = 3.2;
+            // Prepare ciphertext
+            let mut rng = rand::rngs::StdRng::seed_from_u64(33);
+
+            let secret_key = SecretKey::generate(N, &mut rng);
+            let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+
+            let plaintext = Plaintext::new(msg, *t);
+            let ciphertext = plaintext.encrypt(&public_key, std_dev, &mut rng);
+            let decrypted = ciphertext.decrypt(&secret_key);
+            assert_eq!(decrypted.poly(), plaintext.poly() % (*t, N));
+
+            // constrain decryption
+            let config = CircuitConfig::standard_recursion_config();
+            let builder = CircuitBuilder::<F, D>::new(config);
+            let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+
+            let assigned_ct = AssignedCiphertext::<F, D, N, Q>::new(
+                &mut ciphertext_chip.arithmetic_chip.cb,
+                *t as u64,
+            );
+            let assigned_sk =
+                AssignedNTTPoly::<F, D, N, Q>::new(&mut ciphertext_chip.arithmetic_chip.cb);
+            let assigned_pt = ciphertext_chip.decrypt(assigned_ct, assigned_sk)?;
+
+            let mut builder = ciphertext_chip.arithmetic_chip.cb;
+            assigned_pt.register_as_public_input(&mut builder);
+
+            // assign witnesses
+            let mut pw = PartialWitness::new();
+            assigned_ct.assign(&mut pw, ciphertext)?;
+            assigned_sk.assign(&mut pw, secret_key.poly.val())?;
+
+            let data = builder.build::<C>();
+            let proof = data.prove(pw)?;
+
+            proof
+                .public_inputs
+                .iter()
+                .zip_eq(decrypted.poly().val())
+                .for_each(|(actual, expected)| {
+                    assert_eq!(*actual, F::from_canonical_i64(*expected));
+                });
+
+            data.verify(proof)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_decrypt_rejects_out_of_range_plaintext() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        for t in [3, 5] {
+            // `x = Q - 1` rounds to `t`, the plaintext `0`, which a loose range check to
+            // `log2_ceil(t)` bits would also let through as any of `[t, 2^log2_ceil(t))`.
+            let x_value = Q - 1;
+            let (rounded_value, remainder_value) =
+                ((t * x_value + Q / 2) / Q, (t * x_value + Q / 2) % Q);
+            assert_eq!(rounded_value, t);
+            let bound = 1 << log2_ceil(t as usize);
+            for plaintext_value in chain([0], t..bound) {
+                // `rounded = quotient * t + plaintext` holds in the field
+                let quotient_value = (F::from_canonical_u64(t)
+                    - F::from_canonical_u64(plaintext_value))
+                    / F::from_canonical_u64(t);
+
+                let config = CircuitConfig::standard_recursion_config();
+                let builder = CircuitBuilder::<F, D>::new(config);
+                let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+                let cb = &mut ciphertext_chip.arithmetic_chip.cb;
+                let x = AssignedValue::<F, D, Q>::new(cb);
+                let remainder = AssignedValue::<F, D, Q>::new(cb);
+                let [rounded, quotient, plaintext] = [(); 3].map(|_| cb.add_virtual_target());
+                ciphertext_chip.constrain_scale_and_round(x, rounded, remainder, t);
+                ciphertext_chip.arithmetic_chip.constrain_mod_t(
+                    AssignedValue::new_from_target_unchecked(rounded),
+                    quotient,
+                    plaintext,
+                    t,
+                );
+
+                let mut pw = PartialWitness::new();
+                x.assign(&mut pw, F::from_canonical_u64(x_value))?;
+                remainder.assign(&mut pw, F::from_canonical_u64(remainder_value))?;
+                pw.set_target(rounded, F::from_canonical_u64(rounded_value))?;
+                pw.set_target(quotient, quotient_value)?;
+                pw.set_target(plaintext, F::from_canonical_u64(plaintext_value))?;
+
+                let data = ciphertext_chip.arithmetic_chip.cb.build::<C>();
+                let honest = plaintext_value == 0;
+                assert_eq!(
+                    is_provable(&data, pw),
+                    honest,
+                    "t = {t}, plaintext = {plaintext_value}"
+                );
+            }
+
+            // An assigned plaintext is constrained to `[0, t)` as well
+            for value in 0..bound {
+                let config = CircuitConfig::standard_recursion_config();
+                let mut builder = CircuitBuilder::<F, D>::new(config);
+                let assigned_pt = AssignedPlaintext::<F, D, N>::new(&mut builder, t);
+                let mut pw = PartialWitness::new();
+                for (i, coeff) in assigned_pt.coeffs().iter().enumerate() {
+                    let coeff_value = if i == 0 { value } else { 0 };
+                    pw.set_target(*coeff, F::from_canonical_u64(coeff_value))?;
+                }
+                let data = builder.build::<C>();
+                assert_eq!(
+                    is_provable(&data, pw),
+                    value < t,
+                    "t = {t}, value = {value}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_assert_same_plaintext() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let std_dev = 3.2;
+        for t in [3, 4, 5, 16] {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(33);
+            let secret_key = SecretKey::generate(N, &mut rng);
+            let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+
+            let msg = vec![0, 1, 2, 3, 4, 5, 6, 7];
+            let msg = msg.into_iter().map(|m| m % t).collect_vec();
+            let mut other_msg = msg.clone();
+            other_msg[5] = (other_msg[5] + 1) % t;
+            for (msg1, same) in [(msg.clone(), true), (other_msg, false)] {
+                let ct0 = Plaintext::new(msg.clone(), t).encrypt(&public_key, std_dev, &mut rng);
+                let ct1 = Plaintext::new(msg1, t).encrypt(&public_key, std_dev, &mut rng);
+
+                let config = CircuitConfig::standard_recursion_config();
+                let builder = CircuitBuilder::<F, D>::new(config);
+                let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+                let cb = &mut ciphertext_chip.arithmetic_chip.cb;
+                let assigned_ct0 = AssignedCiphertext::<F, D, N, Q>::new(cb, t as u64);
+                let assigned_ct1 = AssignedCiphertext::<F, D, N, Q>::new(cb, t as u64);
+                let assigned_sk = AssignedNTTPoly::<F, D, N, Q>::new(cb);
+                ciphertext_chip.assert_same_plaintext(assigned_ct0, assigned_ct1, assigned_sk)?;
+
+                let mut pw = PartialWitness::new();
+                assigned_ct0.assign(&mut pw, ct0)?;
+                assigned_ct1.assign(&mut pw, ct1)?;
+                assigned_sk.assign(&mut pw, secret_key.poly.val())?;
+
+                // Witness generation panics when the decryptions differ
+                let data = ciphertext_chip.arithmetic_chip.cb.build::<C>();
+                assert_eq!(is_provable(&data, pw), same, "t = {t}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_assert_same_plaintext_rejects_forged_decryption() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        for t in [3, 5] {
+            // `xs[0]` decodes to `0`, `xs[1]` to each of `[0, t)` in turn, and the prover claims
+            // both decode to `0` by picking the quotient of `xs[1]` by `t` in the field
+            for m in 0..t {
+                let xs = [0, (m * Q + t / 2) / t];
+                let config = CircuitConfig::standard_recursion_config();
+                let builder = CircuitBuilder::<F, D>::new(config);
+                let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+                let mut pw = PartialWitness::new();
+                let mut plaintexts = vec![];
+                for x_value in xs {
+                    let tmp = t * x_value + Q / 2;
+                    let rounded_value = tmp / Q;
+                    let quotient_value =
+                        (F::from_canonical_u64(rounded_value)) / F::from_canonical_u64(t);
+
+                    let cb = &mut ciphertext_chip.arithmetic_chip.cb;
+                    let x = AssignedValue::<F, D, Q>::new(cb);
+                    let remainder = AssignedValue::<F, D, Q>::new(cb);
+                    let [rounded, quotient, plaintext] = [(); 3].map(|_| cb.add_virtual_target());
+                    ciphertext_chip.constrain_scale_and_round(x, rounded, remainder, t);
+                    ciphertext_chip.arithmetic_chip.constrain_mod_t(
+                        AssignedValue::new_from_target_unchecked(rounded),
+                        quotient,
+                        plaintext,
+                        t,
+                    );
+                    x.assign(&mut pw, F::from_canonical_u64(x_value))?;
+                    remainder.assign(&mut pw, F::from_canonical_u64(tmp % Q))?;
+                    pw.set_target(rounded, F::from_canonical_u64(rounded_value))?;
+                    pw.set_target(quotient, quotient_value)?;
+                    pw.set_target(plaintext, F::ZERO)?;
+                    plaintexts.push(plaintext);
+                }
+                let cb = &mut ciphertext_chip.arithmetic_chip.cb;
+                cb.connect(plaintexts[0], plaintexts[1]);
+
+                let data = ciphertext_chip.arithmetic_chip.cb.build::<C>();
+                assert_eq!(is_provable(&data, pw), m == 0, "t = {t}, m = {m}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Proves public key encryption of `plaintext` under `public_key` with the given randomness,
+    /// whose errors lie in `[-2^(ERROR_BITS - 1), 2^(ERROR_BITS - 1))`, checks the resulting
+    /// ciphertext and returns it in coefficients form.
+    fn encrypt_helper(
+        public_key: &PublicKey,
+        plaintext: &Plaintext,
+        t: u64,
+        u: &[i64],
+        e_1: &[i64],
+        e_2: &[i64],
+    ) -> Result<[Poly; 2], Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        const ERROR_BITS: usize = 5;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        // constrain encryption
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::new(builder);
+
+        let cb = &mut ciphertext_chip.arithmetic_chip.cb;
+        let assigned_pk = [AssignedNTTPoly::new(cb), AssignedNTTPoly::new(cb)];
+        let assigned_pt = AssignedPlaintext::new(cb, t);
+        let assigned_randomness = AssignedEncryptionRandomness::new(cb, ERROR_BITS);
+        let assigned_ct = ciphertext_chip.encrypt(assigned_pk, assigned_pt, assigned_randomness)?;
+
+        let mut builder = ciphertext_chip.arithmetic_chip.cb;
+        assigned_ct.register_as_public_input(&mut builder);
+
+        // assign witnesses
+        let mut pw = PartialWitness::new();
+        assigned_pk[0].assign(&mut pw, public_key.p_0.val())?;
+        assigned_pk[1].assign(&mut pw, public_key.p_1.val())?;
+        assigned_pt.assign(&mut pw, plaintext)?;
+        assigned_randomness.assign(&mut pw, u, e_1, e_2)?;
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let q = Q as i64;
+        let delta = q / t as i64;
+        let u = Poly::new(u.to_vec());
+        let c_0 = (public_key.p_0.mul_ntt(&u, q)
+            + Poly::new(e_1.to_vec())
+            + (plaintext.poly() % (t as i64, N)) * delta)
+            % (q, N);
+        let c_1 = (public_key.p_1.mul_ntt(&u, q) + Poly::new(e_2.to_vec())) % (q, N);
+        let expected = [&c_0, &c_1]
+            .iter()
+            .flat_map(|c| {
+                ntt_forward::<F, D, Q>(
+                    &c.val()
+                        .iter()
+                        .map(|coeff| F::from_canonical_i64(*coeff))
+                        .collect_vec(),
+                )
+            })
+            .collect_vec();
+        proof
+            .public_inputs
+            .iter()
+            .zip_eq(expected)
+            .for_each(|(actual, expected)| {
+                assert_eq!(*actual, expected);
+            });
+
+        data.verify(proof)?;
+        Ok([c_0, c_1])
+    }
+
+    #[test]
+    fn test_encrypt() -> Result<(), Error> {
+        const N: usize = 8;
+        const Q: i64 = 3329;
+        let u = vec![1, 0, 1, 1, 0, 0, 1, 0];
+        let e_1 = vec![3, 0, -5, 1, 2, 7, 0, -4];
+        let e_2 = vec![0, -16, 1, 2, 15, 0, 3, -1];
+        for t in [4, 16].iter() {
+            let msg = vec![0, 1, 2, 3, 4, 5, 6, 7];
+            let std_dev = 3.2;
+            let mut rng = rand::rngs::StdRng::seed_from_u64(34);
+
+            let secret_key = SecretKey::generate(N, &mut rng);
+            let public_key = secret_key.public_key_gen(Q, std_dev, &mut rng);
+            let plaintext = Plaintext::new(msg, *t);
+
+            encrypt_helper(&public_key, &plaintext, *t as u64, &u, &e_1, &e_2)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypt_error_out_of_bound() {
+        const N: usize = 8;
+        const Q: i64 = 3329;
+        let u = vec![1, 0, 1, 1, 0, 0, 1, 0];
+        let t = 4;
+        let msg = vec![0, 1, 2, 3, 0, 1, 2, 3];
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(34);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q, std_dev, &mut rng);
+        let plaintext = Plaintext::new(msg, t);
+
+        // `16` and `-17` do not fit in the 5 bits allowed for the errors
+        for bad in [16, -17] {
+            let e_1 = vec![3, 0, -5, 1, bad, 7, 0, 4];
+            let e_2 = vec![0, 6, 1, 2, -16, 0, 3, -1];
+            let result = encrypt_helper(&public_key, &plaintext, t as u64, &u, &e_1, &e_2);
+            assert!(result.is_err(), "e = {bad}");
+            let result = encrypt_helper(&public_key, &plaintext, t as u64, &u, &e_2, &e_1);
+            assert!(result.is_err(), "e = {bad}");
+        }
+    }
+
+    #[test]
+    fn test_encrypt_with_noise() -> Result<(), Error> {
+        const N: usize = 8;
+        const Q: i64 = 3329;
+        let t = 4;
+        let msg = vec![0, 1, 2, 3, 0, 1, 2, 3];
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(34);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q, std_dev, &mut rng);
+        let plaintext = Plaintext::new(msg, t);
+        // the uniform errors in `[-15, 15]` use the whole signed range allowed by 5 bits
+        let encryptions = [
+            plaintext.encrypt_with_noise_sampler(
+                &public_key,
+                &GaussianSampler::new(std_dev),
+                &mut rng,
+            ),
+            plaintext.encrypt_with_noise_sampler(&public_key, &UniformSampler::new(15), &mut rng),
+        ];
+        let noise = &encryptions[1].1;
+        assert!(chain(noise.e_1().val(), noise.e_2().val()).any(|e| *e < 0));
+
+        for (ciphertext, noise) in encryptions {
+            let [c_0, c_1] = encrypt_helper(
+                &public_key,
+                &plaintext,
+                t as u64,
+                noise.u().val(),
+                noise.e_1().val(),
+                noise.e_2().val(),
+            )?;
+            assert_eq!(c_0, ciphertext.c_0);
+            assert_eq!(c_1, ciphertext.c_1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_relinearize() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let t = 4;
+        let base = 16;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(40);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+        let rlk = secret_key
+            .relin_key_gen_1(Q as i64, std_dev, &mut rng, base as i64)
+            .unwrap();
+        let ciphertext1 =
+            Plaintext::new(vec![0, 1, 2, 3, 0, 1, 2, 3], t).encrypt(&public_key, std_dev, &mut rng);
+        let ciphertext2 =
+            Plaintext::new(vec![1, 0, 0, 0, 0, 0, 0, 1], t).encrypt(&public_key, std_dev, &mut rng);
+        let (c_0, c_1, c_2) = ciphertext1.basic_mul(ciphertext2.clone());
+        let mul_ciphertext = ciphertext1 * (ciphertext2, &rlk);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::with_config(config);
+        let cb = &mut ciphertext_chip.arithmetic_chip.cb;
+        let source_ct = [(); 3].map(|_| AssignedPoly::<F, D, N, Q>::new(cb));
+        let source_rlk = AssignedRelinearizationKey::<F, D, N, Q>::new(cb, base)?;
+        let degree_2_ct = [(); 3].map(|_| AssignedPoly::<F, D, N, Q>::new(cb));
+        let assigned_rlk = AssignedRelinearizationKey::<F, D, N, Q>::new(cb, base)?;
+        let relinearized =
+            ciphertext_chip.relinearize(t as u64, degree_2_ct, assigned_rlk.clone())?;
+
+        // The inputs of the relinearization are only set by copy generators added after its
+        // witness generators, in a shuffled order. The ciphertext is copied directly, so they are
+        // woken up in the second round of witness generation, while the key is copied through two
+        // intermediate targets, so it is only set in the third round: unless the key is among the
+        // dependencies of a generator reading it, that generator would read it unset.
+        let poly_targets = |polys: &[AssignedPoly<F, D, N, Q>]| {
+            polys
+                .iter()
+                .flat_map(|poly| poly.values().map(|value| value.value))
+                .collect_vec()
+        };
+        let mut builder = ciphertext_chip.arithmetic_chip.cb;
+        let mut ct_copies = poly_targets(&source_ct)
+            .into_iter()
+            .zip_eq(poly_targets(&degree_2_ct))
+            .collect_vec();
+        ct_copies.shuffle(&mut rng);
+        let rlk_hops = [
+            source_rlk.targets(),
+            builder.add_virtual_targets(source_rlk.targets().len()),
+            builder.add_virtual_targets(source_rlk.targets().len()),
+            assigned_rlk.targets(),
+        ];
+        // The last hop is added first, so that each round of witness generation takes one hop.
+        let rlk_copies = rlk_hops
+            .windows(2)
+            .rev()
+            .flat_map(|hop| {
+                let [srcs, dsts] = hop else { unreachable!() };
+                let mut hop = srcs
+                    .iter()
+                    .copied()
+                    .zip_eq(dsts.iter().copied())
+                    .collect_vec();
+                hop.shuffle(&mut rng);
+                hop
+            })
+            .collect_vec();
+        for (src, dst) in chain!(ct_copies, rlk_copies) {
+            builder.generate_copy(src, dst);
+        }
+        relinearized.register_as_public_input(&mut builder);
+
+        let mut pw = PartialWitness::new();
+        for (poly, c) in source_ct.iter().zip([c_0, c_1, c_2]) {
+            poly.assign(&mut pw, c.val())?;
+        }
+        source_rlk.assign(&mut pw, &rlk)?;
+
+        let data = builder.build::<C>();
+        // The key is read by the `ArithmeticOpsGenerator`s of the products `rlk^(i) * c_2^(i)`,
+        // which therefore wait for every one of its targets.
+        let key_readers = data
+            .prover_only
+            .generators
+            .iter()
+            .filter(|generator| generator.0.id() == "ArithmeticOpsGenerator")
+            .flat_map(|generator| generator.0.watch_list())
+            .collect::<HashSet<_>>();
+        assert!(assigned_rlk
+            .targets()
+            .iter()
+            .all(|target| key_readers.contains(target)));
+        let proof = data.prove(pw)?;
+
+        let expected = [mul_ciphertext.c_0, mul_ciphertext.c_1]
+            .into_iter()
+            .flat_map(|c| {
+                ntt_forward::<F, D, Q>(
+                    &(c % (Q as i64, N))
+                        .val()
+                        .iter()
+                        .map(|coeff| F::from_canonical_i64(*coeff))
+                        .collect_vec(),
+                )
+            })
+            .collect_vec();
+        assert_eq!(proof.public_inputs, expected);
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_external_product_cmux() -> Result<(), Error> {
+        const D: usize = 2;
+        const N: usize = 8;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+        let t = 4;
+        // Q leaves little room for noise, so binary digits keep the product noise small.
+        let base = 2;
+        let std_dev = 3.2;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(41);
+
+        let secret_key = SecretKey::generate(N, &mut rng);
+        let public_key = secret_key.public_key_gen(Q as i64, std_dev, &mut rng);
+        let pts = [[0, 1, 2, 3, 0, 1, 2, 3], [3, 3, 0, 1, 2, 0, 0, 1]]
+            .map(|msg| Plaintext::new(msg.to_vec(), t));
+        let cts = pts
+            .each_ref()
+            .map(|pt| pt.encrypt(&public_key, std_dev, &mut rng));
+
+        // CMux: ct_0 + RGSW(b) ⊠ (ct_1 - ct_0) selects ct_b.
+        let cmux = |gsw: &GswCiphertext| {
+            let mut selected = &cts[0] + &gsw.external_product(&(&cts[1] - &cts[0]));
+            selected.canonicalize();
+            selected
+        };
+        let gsw = [0, 1].map(|b| {
+            GswCiphertext::encrypt(b, &secret_key, base as i64, Q as i64, std_dev, &mut rng)
+        });
+        for (gsw_b, pt_b) in gsw.iter().zip(&pts) {
+            assert_eq!(cmux(gsw_b).decrypt(&secret_key), *pt_b);
+        }
+
+        // The circuit is slow to prove, so only the selection of ct_1 is constrained.
+        let config = CircuitConfig::standard_recursion_config();
+        let mut ciphertext_chip = CiphertextChip::<F, D, N, Q>::with_config(config);
+        let mut pw = PartialWitness::new();
+        let [ct_0, ct_1] = ciphertext_chip
+            .assign_ciphertexts(&mut pw, &cts)?
+            .try_into()
+            .unwrap();
+        let assigned_gsw = AssignedGswCiphertext::<F, D, N, Q>::new(
+            &mut ciphertext_chip.arithmetic_chip.cb,
+            base,
+        )?;
+        assigned_gsw.assign(&mut pw, &gsw[1])?;
+
+        let diff = ciphertext_chip.sub_ciphertexts(ct_1, ct_0)?;
+        let product = ciphertext_chip.external_product(&assigned_gsw, diff)?;
+        let selected = ciphertext_chip.add_ciphertexts(ct_0, product)?;
+        selected.register_as_public_input(&mut ciphertext_chip.arithmetic_chip.cb);
+
+        let data = ciphertext_chip.arithmetic_chip.cb.build::<C>();
+        let proof = data.prove(pw)?;
+        let expected = cmux(&gsw[1]);
+        let expected = [expected.c_0, expected.c_1]
+            .into_iter()
+            .flat_map(|c| {
+                ntt_forward::<F, D, Q>(
+                    &c.val()
+                        .iter()
+                        .map(|coeff| F::from_canonical_i64(*coeff))
+                        .collect_vec(),
+                )
+            })
+            .collect_vec();
+        assert_eq!(proof.public_inputs, expected);
+        data.verify(proof)
+    }
 }
@@ -0,0 +1,168 @@
+use std::ops::{Add, Mul};
+
+use plonky2::{plonk::circuit_data::CircuitConfig, util::log2_ceil};
+
+use crate::ntt_params::params;
+
+/// The operations of a circuit over ciphertexts of degree `N`, to estimate its size with
+/// `estimate_circuit_size` before building it
+///
+/// * `ciphertexts` = the number of assigned input ciphertexts
+/// * `adds` = the number of `add_ciphertexts`
+/// * `muls` = the number of `mul_ciphertexts`, without relinearization
+/// * `ntts` = the number of in-circuit NTTs of a polynomial of degree `params::N`, forward or
+///   backward
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CircuitOps {
+    pub ciphertexts: usize,
+    pub adds: usize,
+    pub muls: usize,
+    pub ntts: usize,
+}
+
+/// The estimated size of a circuit, see `estimate_circuit_size`
+///
+/// * `gates` = the number of gates, i.e. rows, before padding
+/// * `range_checks` = the number of range checks, each a `BaseSumGate` for moduli below `2^63`
+/// * `lookups` = the number of lookups into the `[0, Q)` table of `AssignedValue::new_batch`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircuitSizeEstimate {
+    pub gates: usize,
+    pub range_checks: usize,
+    pub lookups: usize,
+}
+
+impl CircuitSizeEstimate {
+    /// Returns the `degree_bits` of the circuit once the rows are padded to a power of two. The
+    /// zk config pads small circuits further for blinding.
+    pub fn degree_bits(&self) -> usize {
+        log2_ceil(self.gates)
+    }
+}
+
+/// The range checks and `ArithmeticGate` operations added by a gadget
+#[derive(Clone, Copy, Debug, Default)]
+struct Cost {
+    range_checks: usize,
+    arithmetic_ops: usize,
+}
+
+impl Cost {
+    const fn new(range_checks: usize, arithmetic_ops: usize) -> Self {
+        Self {
+            range_checks,
+            arithmetic_ops,
+        }
+    }
+
+    /// `AssignedValue::range_check_modulus`: a single range check if `Q` is a power of two, and
+    /// otherwise a second one on `Q - 1 - x`, which costs a subtraction
+    const fn value<const Q: u64>() -> Self {
+        if Q.is_power_of_two() {
+            Self::new(1, 0)
+        } else {
+            Self::new(2, 1)
+        }
+    }
+}
+
+impl Add for Cost {
+    type Output = Cost;
+    fn add(self, other: Cost) -> Cost {
+        Cost::new(
+            self.range_checks + other.range_checks,
+            self.arithmetic_ops + other.arithmetic_ops,
+        )
+    }
+}
+
+impl Mul<usize> for Cost {
+    type Output = Cost;
+    fn mul(self, k: usize) -> Cost {
+        Cost::new(self.range_checks * k, self.arithmetic_ops * k)
+    }
+}
+
+/// `ArithmeticChip::add`: a boolean carry, the sum, the reduction and the range checked result
+fn add_cost<const Q: u64>() -> Cost {
+    Cost::new(0, 3) + Cost::value::<Q>()
+}
+
+/// `ArithmeticChip::sub`: as `add`, with `x + Q - y` taking two operations
+fn sub_cost<const Q: u64>() -> Cost {
+    Cost::new(0, 4) + Cost::value::<Q>()
+}
+
+/// `ArithmeticChip::mul` and `mul_with_constant`: a range checked quotient, the product, the
+/// reduction and the range checked result
+fn mul_cost<const Q: u64>() -> Cost {
+    Cost::value::<Q>() + Cost::new(0, 2) + Cost::value::<Q>()
+}
+
+/// Estimates the number of gates and range checks of a circuit doing `ops` over ciphertexts of
+/// degree `N` modulo `Q` under `config`, from the per-operation counts of `ArithmeticChip` and
+/// `NTTChip`, so that callers can give up on a too large circuit before the expensive `build`.
+///
+/// Every range check of at most 63 bits fills a `BaseSumGate`, the arithmetic operations share
+/// `ArithmeticGate`s of `num_routed_wires / 4` operations each, and the lookups share
+/// `LookupGate`s next to the rows of their table. Constants, public inputs and the grouping of
+/// operations by their coefficients add a few gates that are not counted, so the estimate is a
+/// slight underestimate. Every NTT is counted as a backward one, which also scales the `N`
+/// coefficients by `N^{-1}`.
+///
+/// ```rust
+/// use plonky2::{field::types::Field, plonk::circuit_data::CircuitConfig};
+/// use plonky2::field::goldilocks_field::GoldilocksField;
+/// use verifiable_bfv::vbfv::{estimate_circuit_size, CircuitOps};
+///
+/// let config = CircuitConfig::standard_recursion_config();
+/// let ops = CircuitOps {
+///     ciphertexts: 2,
+///     adds: 1,
+///     ..Default::default()
+/// };
+/// let estimate = estimate_circuit_size::<8192, 3329>(&config, ops);
+/// assert_eq!(estimate.lookups, 2 * 2 * 8192);
+/// // plonky2 requires `degree_bits + rate_bits <= F::TWO_ADICITY`
+/// let rate_bits = config.fri_config.rate_bits;
+/// assert!(estimate.degree_bits() + rate_bits <= GoldilocksField::TWO_ADICITY);
+/// ```
+pub fn estimate_circuit_size<const N: usize, const Q: u64>(
+    config: &CircuitConfig,
+    ops: CircuitOps,
+) -> CircuitSizeEstimate {
+    // `add_ciphertexts` also allocates a range checked quotient per slot.
+    let ciphertext_add = (Cost::value::<Q>() + add_cost::<Q>()) * (2 * N);
+    // The tensor product `(c_0 * c'_0, c_0 * c'_1 + c_1 * c'_0, c_1 * c'_1)` of NTT forms
+    let ciphertext_mul = mul_cost::<Q>() * (4 * N) + add_cost::<Q>() * N;
+    let butterflies = params::N / 2 * params::LOGN as usize;
+    let ntt = (mul_cost::<Q>() + add_cost::<Q>() + sub_cost::<Q>()) * butterflies
+        + mul_cost::<Q>() * params::N;
+
+    let cost = ciphertext_add * ops.adds + ciphertext_mul * ops.muls + ntt * ops.ntts;
+
+    // The inputs are allocated with `AssignedValue::new_batch`, which looks the values up in a
+    // table over `[0, Q)` if `Q` fits in `u16`, added with a `NoopGate` when building the circuit.
+    let inputs = 2 * N * ops.ciphertexts;
+    let (cost, lookups, lookup_gates) = if Q > u16::MAX as u64 {
+        (cost + Cost::value::<Q>() * inputs, 0, 0)
+    } else if inputs == 0 {
+        (cost, 0, 0)
+    } else {
+        let lookup_gates = inputs.div_ceil(config.num_routed_wires / 2)
+            + (Q as usize).div_ceil(config.num_routed_wires / 3)
+            + 1;
+        (cost, inputs, lookup_gates)
+    };
+
+    let limbs_per_gate = 63.min(config.num_routed_wires - 1);
+    let gates_per_range_check = log2_ceil(Q as usize).div_ceil(limbs_per_gate);
+    let ops_per_gate = config.num_routed_wires / 4;
+    CircuitSizeEstimate {
+        gates: cost.range_checks * gates_per_range_check
+            + cost.arithmetic_ops.div_ceil(ops_per_gate)
+            + lookup_gates,
+        range_checks: cost.range_checks,
+        lookups,
+    }
+}
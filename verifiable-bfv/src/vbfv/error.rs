@@ -0,0 +1,53 @@
+use std::fmt;
+
+/// `VbfvError` is the error returned by the constraint builders of `vbfv`
+#[derive(Debug)]
+pub enum VbfvError {
+    /// An input has `actual` elements where `expected` are required
+    DimensionMismatch { expected: usize, actual: usize },
+    /// Ciphertexts under different plaintext moduli are combined
+    PlaintextModulusMismatch(u64, u64),
+    /// A witness lies outside of `[0, bound)`
+    RangeCheckFailed { value: u64, bound: u64 },
+    /// The ring modulus `Q` is not supported by the operation
+    UnsupportedModulus(u64),
+    /// A gadget decomposition base lies outside of `[2, Q)`
+    UnsupportedBase(u64),
+    /// A key decomposed in base `actual` is assigned to a circuit built for base `expected`
+    BaseMismatch { expected: u64, actual: u64 },
+    /// Setting a witness in the partial witness failed
+    WitnessAssign(anyhow::Error),
+}
+
+impl fmt::Display for VbfvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VbfvError::DimensionMismatch { expected, actual } => {
+                write!(f, "expected {expected} elements, got {actual}")
+            }
+            VbfvError::PlaintextModulusMismatch(t0, t1) => {
+                write!(f, "plaintext moduli {t0} and {t1} do not match")
+            }
+            VbfvError::RangeCheckFailed { value, bound } => {
+                write!(f, "{value} is not in [0, {bound})")
+            }
+            VbfvError::UnsupportedModulus(q) => write!(f, "unsupported ring modulus {q}"),
+            VbfvError::UnsupportedBase(base) => {
+                write!(f, "unsupported decomposition base {base}")
+            }
+            VbfvError::BaseMismatch { expected, actual } => {
+                write!(f, "expected a key in base {expected}, got base {actual}")
+            }
+            VbfvError::WitnessAssign(err) => write!(f, "failed to assign witness: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for VbfvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VbfvError::WitnessAssign(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
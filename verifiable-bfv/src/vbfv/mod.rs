@@ -9,53 +9,223 @@ use plonky2::{
         config::{GenericConfig, PoseidonGoldilocksConfig},
     },
 };
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 mod arithmetic_chip;
 mod assigned;
 mod ciphertext_chip;
+mod circuit_size;
+mod error;
 mod ntt_chip;
+#[cfg(test)]
+mod test_vectors;
 
+pub use ciphertext_chip::{
+    circuit_data_from_bytes, circuit_data_to_bytes, prove_add_ciphertexts,
+    verifier_data_from_bytes, verifier_data_to_bytes, verify_ciphertext_addition,
+    verify_proof_bytes, VbfvCircuitData, VbfvGeneratorSerializer, VbfvProof, VbfvVerifierData,
+};
+pub use circuit_size::{estimate_circuit_size, CircuitOps, CircuitSizeEstimate};
+pub use error::VbfvError;
+
+/// Returns whether `X^N+1` fully splits in `\mathbb{Z}_Q`, i.e. whether `2N | Q-1`, so that
+/// polynomials in `R_Q` can be kept in NTT form.
+pub(crate) const fn supports_ntt(n: usize, q: u64) -> bool {
+    (q - 1).is_multiple_of(2 * n as u64)
+}
+
+/// Applies the butterflies of a single NTT block, where `lo` and `hi` are `a[j1..j2]` and
+/// `a[j1 + t..j2 + t]` respectively.
+fn ntt_fw_butterfly<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    lo: &mut [F],
+    hi: &mut [F],
+    root: u64,
+) {
+    let s = F::from_canonical_u64(root);
+    for (a_j, a_jt) in lo.iter_mut().zip(hi.iter_mut()) {
+        let u = *a_j;
+        let v = F::from_canonical_u64(
+            ((a_jt.to_canonical_u64() as u128) * (s.to_canonical_u64() as u128))
+                .rem_euclid(Q as u128) as u64,
+        );
+        *a_j = F::from_canonical_u64(
+            ((u.to_canonical_u64() as u128) + (v.to_canonical_u64() as u128)).rem_euclid(Q as u128)
+                as u64,
+        );
+        *a_jt = F::from_canonical_u64(
+            ((u.to_canonical_u64() as u128) + (Q as u128) - (v.to_canonical_u64() as u128))
+                .rem_euclid(Q as u128) as u64,
+        );
+    }
+}
+
+/// Applies the inverse butterflies of a single NTT block, where `lo` and `hi` are `a[j1..j2]` and
+/// `a[j1 + t..j2 + t]` respectively.
+fn ntt_bw_butterfly<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    lo: &mut [F],
+    hi: &mut [F],
+    root: u64,
+) {
+    let s = F::from_canonical_u64(root);
+    for (a_j, a_jt) in lo.iter_mut().zip(hi.iter_mut()) {
+        let u = *a_j;
+        let v = *a_jt;
+        *a_j = F::from_canonical_u64(
+            ((u.to_canonical_u64() as u128) + (v.to_canonical_u64() as u128)).rem_euclid(Q as u128)
+                as u64,
+        );
+        let w = ((u.to_canonical_u64() as u128) + (Q as u128) - (v.to_canonical_u64() as u128))
+            .rem_euclid(Q as u128);
+        *a_jt = F::from_canonical_u64(
+            (w * (s.to_canonical_u64() as u128)).rem_euclid(Q as u128) as u64
+        );
+    }
+}
+
+/// Applies the `m` blocks of butterflies of a forward NTT round to `a` of any power-of-two length
+/// `n`, where `roots` are the powers of a primitive `2n`-th root of unity modulo `Q` in
+/// bit-reversed order, e.g. `params::ROOTS` for `n = params::N`.
 fn ntt_fw_update<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
-    input: &[F],
+    a: &mut [F],
     m: usize,
-) -> Vec<F> {
-    let mut a = input.to_vec();
+    roots: &[u64],
+) {
+    let t = a.len() / (2 * m);
+    for i in 0..m {
+        let j1 = 2 * i * t;
+        let j2 = j1 + t;
+        let (lo, hi) = a[j1..j2 + t].split_at_mut(t);
+        ntt_fw_butterfly::<F, D, Q>(lo, hi, roots[m + i]);
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn ntt_fw_update_par<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    a: &mut [F],
+    m: usize,
+    roots: &[u64],
+) {
+    let t = a.len() / (2 * m);
+    // The `i`-th block only touches `a[2it..2(i+1)t]`, so the `m` blocks are processed in parallel.
+    a.par_chunks_mut(2 * t).enumerate().for_each(|(i, block)| {
+        let (lo, hi) = block.split_at_mut(t);
+        ntt_fw_butterfly::<F, D, Q>(lo, hi, roots[m + i]);
+    });
+}
+
+/// Returns the block counts `m = 1, 2, 4, ..., n / 2` of the rounds of an NTT of length `n`.
+fn ntt_rounds(n: usize) -> impl DoubleEndedIterator<Item = usize> {
+    (0..n.trailing_zeros()).map(|i| 2usize.pow(i))
+}
+
+fn ntt_bw_update<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    a: &mut [F],
+    m: usize,
+) {
     let t = params::N / (2 * m);
     for i in 0..m {
         let j1 = 2 * i * t;
         let j2 = j1 + t;
-        let root = params::ROOTS[m + i];
-        let s = F::from_canonical_u64(root);
-        for j in j1..j2 {
-            let u = a[j];
-            let v = F::from_canonical_u64(
-                ((a[j + t].to_canonical_u64() as u128) * (s.to_canonical_u64() as u128))
-                    .rem_euclid(Q as u128) as u64,
-            );
-            a[j] = F::from_canonical_u64(
-                ((u.to_canonical_u64() as u128) + (v.to_canonical_u64() as u128))
-                    .rem_euclid(Q as u128) as u64,
-            );
-            a[j + t] = F::from_canonical_u64(
-                ((u.to_canonical_u64() as u128) + (Q as u128) - (v.to_canonical_u64() as u128))
-                    .rem_euclid(Q as u128) as u64,
-            );
-        }
+        let (lo, hi) = a[j1..j2 + t].split_at_mut(t);
+        ntt_bw_butterfly::<F, D, Q>(lo, hi, params::INVROOTS[m + i]);
     }
-    a
 }
 
+/// Transforms `buf` into NTT form in place, reusing the same buffer across all `LOGN` rounds.
+///
+/// The input coefficients are in natural order, and the output evaluations are in bit-reversed
+/// order (see `ntt_params::bit_reverse_permute`).
+pub fn ntt_forward_inplace<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    buf: &mut [F],
+) {
+    for m in ntt_rounds(params::N) {
+        ntt_fw_update::<F, D, Q>(buf, m, &params::ROOTS);
+    }
+}
+
+/// Transforms `buf` from NTT form back into coefficient form in place.
+///
+/// Expects evaluations in the bit-reversed order produced by `ntt_forward_inplace` and returns
+/// coefficients in natural order.
+pub fn ntt_backward_inplace<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    buf: &mut [F],
+) {
+    for m in (0..params::LOGN).rev().map(|i| 2usize.pow(i)) {
+        ntt_bw_update::<F, D, Q>(buf, m);
+    }
+    let n_inv = params::NINV as u128;
+    for g in buf.iter_mut() {
+        *g = F::from_canonical_u64(
+            ((g.to_canonical_u64() as u128) * n_inv).rem_euclid(Q as u128) as u64,
+        );
+    }
+}
+
+/// Transforms natural-order coefficients into bit-reversed-order evaluations.
 pub fn ntt_forward<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
     input: &[F],
 ) -> Vec<F> {
     let mut current = input.to_vec();
-    for m in (0..params::LOGN).map(|i| 2usize.pow(i)) {
-        current = ntt_fw_update::<F, D, Q>(&current, m);
-    }
+    ntt_forward_inplace::<F, D, Q>(&mut current);
+    current
+}
 
+/// Transforms bit-reversed-order evaluations back into natural-order coefficients, the inverse of
+/// `ntt_forward` and the plain counterpart of `NTTChip::ntt_backward`.
+pub fn ntt_backward<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    input: &[F],
+) -> Vec<F> {
+    let mut current = input.to_vec();
+    ntt_backward_inplace::<F, D, Q>(&mut current);
+    current
+}
+
+/// Same as `ntt_forward`, but processes the independent butterfly blocks of each round in parallel.
+/// The output is identical to `ntt_forward`.
+#[cfg(feature = "rayon")]
+pub fn ntt_forward_par<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    input: &[F],
+) -> Vec<F> {
+    let mut current = input.to_vec();
+    ntt_forward_par_with_roots::<F, D, Q>(&mut current, &params::ROOTS);
     current
 }
 
+/// Transforms `buf` into NTT form in place with the parallel rounds of `ntt_forward_par`, for any
+/// power-of-two length with matching `roots` (see `ntt_fw_update`).
+#[cfg(feature = "rayon")]
+fn ntt_forward_par_with_roots<F: RichField + Extendable<D>, const D: usize, const Q: u64>(
+    buf: &mut [F],
+    roots: &[u64],
+) {
+    for m in ntt_rounds(buf.len()) {
+        ntt_fw_update_par::<F, D, Q>(buf, m, roots);
+    }
+}
+
+/// Returns whether `pw` proves the circuit of `data` and the proof verifies. Witness generation
+/// panics on a range check whose value does not fit, which counts as a failure to prove.
+#[cfg(test)]
+pub(crate) fn is_provable(
+    data: &VbfvCircuitData,
+    pw: plonky2::iop::witness::PartialWitness<plonky2::field::goldilocks_field::GoldilocksField>,
+) -> bool {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        data.prove(pw).and_then(|proof| data.verify(proof)).is_ok()
+    }))
+    .unwrap_or(false)
+}
+
+/// Asserts that `pw` does not prove the circuit of `data`, see `is_provable`
+#[cfg(test)]
+pub(crate) fn assert_unprovable(
+    data: &VbfvCircuitData,
+    pw: plonky2::iop::witness::PartialWitness<plonky2::field::goldilocks_field::GoldilocksField>,
+) {
+    assert!(!is_provable(data, pw), "the witness proves the circuit");
+}
+
 pub fn prove_bfv_ops() -> Result<(), Error> {
     const D: usize = 2;
     type C = PoseidonGoldilocksConfig;
@@ -66,3 +236,94 @@ pub fn prove_bfv_ops() -> Result<(), Error> {
 
     todo!()
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::{goldilocks_field::GoldilocksField, types::Field};
+    #[cfg(feature = "rayon")]
+    use rand::{Rng, SeedableRng};
+
+    use super::{ntt_backward, ntt_backward_inplace, ntt_forward, ntt_forward_inplace};
+    #[cfg(feature = "rayon")]
+    use super::{ntt_forward_par, ntt_forward_par_with_roots, ntt_fw_update, ntt_rounds};
+    use crate::ntt_params::params;
+    #[cfg(feature = "rayon")]
+    use crate::ntt_params::{bit_reverse_permute, primitive_2n_root, verify_roots};
+
+    const D: usize = 2;
+    const Q: u64 = 3329;
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_ntt_inplace() {
+        let input = params::TESTG.map(F::from_canonical_u64);
+        let mut buf = input;
+        ntt_forward_inplace::<F, D, Q>(&mut buf);
+        assert_eq!(buf.to_vec(), ntt_forward::<F, D, Q>(&input));
+        assert_eq!(buf, params::TESTGHAT.map(F::from_canonical_u64));
+
+        ntt_backward_inplace::<F, D, Q>(&mut buf);
+        assert_eq!(buf, input);
+    }
+
+    #[test]
+    fn test_ntt_backward() {
+        let input = params::TESTG.map(F::from_canonical_u64);
+        let evals = ntt_forward::<F, D, Q>(&input);
+        assert_eq!(ntt_backward::<F, D, Q>(&evals), input.to_vec());
+        assert_eq!(
+            ntt_backward::<F, D, Q>(&params::TESTGHAT.map(F::from_canonical_u64)),
+            input.to_vec()
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_ntt_forward_par() {
+        let input = params::TESTG.map(F::from_canonical_u64);
+        assert_eq!(
+            ntt_forward_par::<F, D, Q>(&input),
+            ntt_forward::<F, D, Q>(&input)
+        );
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(27);
+        for _ in 0..100 {
+            let input = (0..params::N)
+                .map(|_| F::from_canonical_u64(rng.gen_range(0, Q)))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                ntt_forward_par::<F, D, Q>(&input),
+                ntt_forward::<F, D, Q>(&input)
+            );
+        }
+    }
+
+    // The root tables of `params` are for `params::N` only, so the rounds at `N = 1024` run with
+    // a table built from `primitive_2n_root`.
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_ntt_forward_par_1024() {
+        const N: usize = 1024;
+        const Q: u64 = 12289;
+        let psi = primitive_2n_root(N, Q).unwrap();
+        let mut roots = std::iter::successors(Some(1), |root| Some(root * psi % Q))
+            .take(N)
+            .collect::<Vec<u64>>();
+        bit_reverse_permute(&mut roots);
+        assert!(verify_roots(&roots, N, Q));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(27);
+        for _ in 0..10 {
+            let input = (0..N)
+                .map(|_| F::from_canonical_u64(rng.gen_range(0, Q)))
+                .collect::<Vec<_>>();
+            let mut sequential = input.clone();
+            for m in ntt_rounds(N) {
+                ntt_fw_update::<F, D, Q>(&mut sequential, m, &roots);
+            }
+            let mut parallel = input;
+            ntt_forward_par_with_roots::<F, D, Q>(&mut parallel, &roots);
+            assert_eq!(parallel, sequential);
+        }
+    }
+}
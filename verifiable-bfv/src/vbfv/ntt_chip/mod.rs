@@ -1,7 +1,6 @@
 use std::marker::PhantomData;
 
 use crate::ntt_params::params;
-use anyhow::Error;
 /// Copied from https://github.com/zama-ai/verifiable-fhe-paper/blob/main/src/ntt/mod.rs
 use plonky2::field::extension::Extendable;
 use plonky2::hash::hash_types::RichField;
@@ -10,20 +9,38 @@ use plonky2::plonk::circuit_builder::CircuitBuilder;
 
 use super::arithmetic_chip::ArithmeticChip;
 use super::assigned::AssignedValue;
+use super::error::VbfvError;
 
-pub(crate) struct NTTChip<F: RichField + Extendable<D>, const D: usize, const Q: u64> {
-    arithmetic_chip: ArithmeticChip<F, D, Q>,
+pub(crate) struct NTTChip<'a, F: RichField + Extendable<D>, const D: usize, const Q: u64> {
+    arithmetic_chip: &'a mut ArithmeticChip<F, D, Q>,
+    n_inv: u64,
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> NTTChip<F, D, Q> {
-    pub fn new(arithmetic_chip: ArithmeticChip<F, D, Q>) -> Self {
-        Self { arithmetic_chip }
+impl<'a, F: RichField + Extendable<D>, const D: usize, const Q: u64> NTTChip<'a, F, D, Q> {
+    pub fn new(arithmetic_chip: &'a mut ArithmeticChip<F, D, Q>) -> Self {
+        Self {
+            arithmetic_chip,
+            n_inv: params::NINV,
+        }
+    }
+
+    // Replaces `NINV` in `ntt_backward`, so that tests can check a corrupted table is caught.
+    #[cfg(test)]
+    fn with_n_inv(mut self, n_inv: u64) -> Self {
+        self.n_inv = n_inv;
+        self
     }
 
     pub fn ntt_forward(
         &mut self,
         input: &Vec<AssignedValue<F, D, Q>>,
-    ) -> Result<Vec<AssignedValue<F, D, Q>>, Error> {
+    ) -> Result<Vec<AssignedValue<F, D, Q>>, VbfvError> {
+        if input.len() != params::N {
+            return Err(VbfvError::DimensionMismatch {
+                expected: params::N,
+                actual: input.len(),
+            });
+        }
         let mut current = input.clone();
         for m in (0..params::LOGN).map(|i| 2usize.pow(i)) {
             current = self.ntt_fw_update(&current, m)?;
@@ -36,7 +53,7 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> NTTChip<F, D, Q
         &mut self,
         input: &Vec<AssignedValue<F, D, Q>>,
         m: usize,
-    ) -> Result<Vec<AssignedValue<F, D, Q>>, Error> {
+    ) -> Result<Vec<AssignedValue<F, D, Q>>, VbfvError> {
         let mut a = input.clone();
         let t = params::N / (2 * m);
         for i in 0..m {
@@ -60,24 +77,44 @@ impl<F: RichField + Extendable<D>, const D: usize, const Q: u64> NTTChip<F, D, Q
     pub fn ntt_backward(
         &mut self,
         input: &Vec<AssignedValue<F, D, Q>>,
-    ) -> Result<Vec<AssignedValue<F, D, Q>>, Error> {
+    ) -> Result<Vec<AssignedValue<F, D, Q>>, VbfvError> {
+        if input.len() != params::N {
+            return Err(VbfvError::DimensionMismatch {
+                expected: params::N,
+                actual: input.len(),
+            });
+        }
         let mut current = input.clone();
         for m in (0..params::LOGN).rev().map(|i| 2usize.pow(i)) {
             current = self.ntt_bw_update(&current, m)?;
         }
         // let n_inv = cb.constant(F::from_canonical_u64(params::NINV));
-        let n_inv = F::from_canonical_u64(params::NINV);
+        let n_inv = F::from_canonical_u64(self.n_inv);
         current
             .into_iter()
             .map(|g| self.arithmetic_chip.mul_with_constant(g, n_inv))
-            .collect::<Result<Vec<_>, Error>>()
+            .collect::<Result<Vec<_>, VbfvError>>()
+    }
+
+    /// Constrains `ntt_backward(ntt_forward(input)) == input` element-wise, so that a wrong
+    /// `ROOTS`, `INVROOTS` or `NINV` table makes proving fail.
+    pub fn assert_roundtrip(
+        &mut self,
+        input: &Vec<AssignedValue<F, D, Q>>,
+    ) -> Result<(), VbfvError> {
+        let evals = self.ntt_forward(input)?;
+        let coeffs = self.ntt_backward(&evals)?;
+        for (x, y) in input.iter().zip(coeffs) {
+            self.arithmetic_chip.cb.connect(x.value, y.value);
+        }
+        Ok(())
     }
 
     fn ntt_bw_update(
         &mut self,
         input: &Vec<AssignedValue<F, D, Q>>,
         m: usize,
-    ) -> Result<Vec<AssignedValue<F, D, Q>>, Error> {
+    ) -> Result<Vec<AssignedValue<F, D, Q>>, VbfvError> {
         let mut a = input.clone();
         let t = params::N / (2 * m);
         let mut j1 = 0usize;
@@ -111,6 +148,26 @@ mod tests {
     use plonky2::plonk::circuit_data::CircuitConfig;
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 
+    #[test]
+    fn test_ntt_dimension_mismatch() {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::new(builder);
+        let x = (0..params::N - 1)
+            .map(|_| AssignedValue::<F, D, Q>::new(&mut arithmetic_chip.cb))
+            .collect_vec();
+        let mut ntt_chip = NTTChip::<F, D, Q>::new(&mut arithmetic_chip);
+        assert!(matches!(
+            ntt_chip.ntt_forward(&x),
+            Err(VbfvError::DimensionMismatch { expected, actual })
+                if expected == params::N && actual == params::N - 1
+        ));
+    }
+
     #[test]
     fn test_ntt_forward() {
         const D: usize = 2;
@@ -121,13 +178,14 @@ mod tests {
 
         let config = CircuitConfig::standard_recursion_config();
         let builder = CircuitBuilder::<F, D>::new(config);
-        let arithmetic_chip = ArithmeticChip::new(builder);
-        let mut ntt_chip = NTTChip::<F, D, Q>::new(arithmetic_chip);
+        let mut arithmetic_chip = ArithmeticChip::new(builder);
+        let mut ntt_chip = NTTChip::<F, D, Q>::new(&mut arithmetic_chip);
         let x = (0..N)
-            .map(|_| AssignedValue::<F, D, Q>::new(&mut builder))
+            .map(|_| AssignedValue::<F, D, Q>::new(&mut ntt_chip.arithmetic_chip.cb))
             .collect_vec();
 
-        let z = ntt_chip.ntt_forward(&mut builder, &x).unwrap();
+        let z = ntt_chip.ntt_forward(&x).unwrap();
+        let mut builder = arithmetic_chip.cb;
         x.iter()
             .for_each(|x| x.register_as_public_input(&mut builder));
         z.iter()
@@ -136,7 +194,7 @@ mod tests {
         x.iter()
             .zip(&params::TESTG)
             .map(|(x, g)| x.assign(&mut pw, F::from_canonical_u64(*g)))
-            .collect::<Result<Vec<_>, Error>>()
+            .collect::<Result<Vec<_>, VbfvError>>()
             .unwrap();
 
         let data = builder.build::<C>();
@@ -160,13 +218,14 @@ mod tests {
 
         let config = CircuitConfig::standard_recursion_config();
         let builder = CircuitBuilder::<F, D>::new(config);
-        let arithmetic_chip = ArithmeticChip::new(builder);
-        let mut ntt_chip = NTTChip::<F, D, Q>::new(arithmetic_chip);
+        let mut arithmetic_chip = ArithmeticChip::new(builder);
+        let mut ntt_chip = NTTChip::<F, D, Q>::new(&mut arithmetic_chip);
         let x = (0..N)
-            .map(|_| AssignedValue::<F, D, Q>::new(&mut builder))
+            .map(|_| AssignedValue::<F, D, Q>::new(&mut ntt_chip.arithmetic_chip.cb))
             .collect_vec();
 
-        let z = ntt_chip.ntt_backward(&mut builder, &x).unwrap();
+        let z = ntt_chip.ntt_backward(&x).unwrap();
+        let mut builder = arithmetic_chip.cb;
         // Public inputs are the initial value (provided below) and the result (which is generated).
         x.iter()
             .for_each(|x| x.register_as_public_input(&mut builder));
@@ -176,7 +235,7 @@ mod tests {
         x.iter()
             .zip(&params::TESTGHAT)
             .map(|(x, g)| x.assign(&mut pw, F::from_canonical_u64(*g)))
-            .collect::<Result<Vec<_>, Error>>()
+            .collect::<Result<Vec<_>, VbfvError>>()
             .unwrap();
 
         let data = builder.build::<C>();
@@ -189,4 +248,72 @@ mod tests {
 
         let _ = data.verify(proof).unwrap();
     }
+
+    fn roundtrip_helper(n_inv: u64) -> bool {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::new(builder);
+        let mut ntt_chip = NTTChip::<F, D, Q>::new(&mut arithmetic_chip).with_n_inv(n_inv);
+        let x = (0..params::N)
+            .map(|_| AssignedValue::<F, D, Q>::new(&mut ntt_chip.arithmetic_chip.cb))
+            .collect_vec();
+        ntt_chip.assert_roundtrip(&x).unwrap();
+
+        let mut pw = PartialWitness::new();
+        x.iter()
+            .zip(&params::TESTG)
+            .map(|(x, g)| x.assign(&mut pw, F::from_canonical_u64(*g)))
+            .collect::<Result<Vec<_>, VbfvError>>()
+            .unwrap();
+
+        let data = arithmetic_chip.cb.build::<C>();
+        data.prove(pw).and_then(|proof| data.verify(proof)).is_ok()
+    }
+
+    #[test]
+    fn test_ntt_roundtrip() {
+        assert!(roundtrip_helper(params::NINV));
+        // a corrupted `NINV` scales the round trip away from the identity
+        assert!(!roundtrip_helper(params::NINV + 1));
+    }
+
+    #[test]
+    fn test_ntt_gate_count() {
+        const D: usize = 2;
+        const Q: u64 = 3329;
+        const ROUNDTRIPS: usize = 4;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let builder = CircuitBuilder::<F, D>::new(config);
+        let mut arithmetic_chip = ArithmeticChip::new(builder);
+        let mut ntt_chip = NTTChip::<F, D, Q>::new(&mut arithmetic_chip);
+        let mut current = AssignedValue::new_batch(&mut ntt_chip.arithmetic_chip.cb, params::N);
+        let before = ntt_chip.arithmetic_chip.cb.num_gates();
+        for _ in 0..ROUNDTRIPS {
+            current = ntt_chip.ntt_forward(&current).unwrap();
+            current = ntt_chip.ntt_backward(&current).unwrap();
+        }
+        let gates = arithmetic_chip.cb.num_gates() - before;
+
+        // Each butterfly is a `mul_with_constant`, an `add` and a `sub`, and the backward
+        // transform also scales every coefficient by `NINV`. Range checking both the quotient and
+        // the result of every op as an `AssignedValue` costs two `BaseSumGate`s each, since `Q`
+        // is not a power of two (see `AssignedValue::range_check_modulus`), i.e. at least
+        // `4 * ops` gates. The quotients of `add` and `sub` are boolean carries instead, which
+        // skip the range check.
+        let butterflies = params::N / 2 * params::LOGN as usize;
+        let ops = ROUNDTRIPS * (2 * 3 * butterflies + params::N);
+        let carries = ROUNDTRIPS * 2 * 2 * butterflies;
+        assert!(
+            gates < 2 * (2 * ops - carries) + carries / 2,
+            "{gates} gates for {ops} ops and {carries} carries"
+        );
+    }
 }
@@ -0,0 +1,40 @@
+//! Fixed ciphertexts for the circuit tests, so that their expected public inputs do not depend on
+//! the RNG. They are fresh encryptions for `N = 8`, `Q = 3329` and `t = 16`, stored as
+//! coefficients in `[0, Q)`, together with the NTT evaluations of their sum (see `TESTG` and
+//! `TESTGHAT` in the params file).
+
+use crate::bfv::{poly::Poly, Ciphertext};
+
+pub const N: usize = 8;
+pub const Q: u64 = 3329;
+pub const T: i64 = 16;
+
+/// Encryption of `[0, 1, 2, 3, 4, 5, 6, 7]`
+pub const TESTCT1: [[u64; N]; 2] = [
+    [1653, 680, 1454, 3002, 1985, 2041, 1905, 2408],
+    [1870, 2791, 268, 728, 2986, 575, 115, 237],
+];
+
+/// Encryption of `[7, 6, 5, 4, 3, 2, 1, 0]` under the same key
+pub const TESTCT2: [[u64; N]; 2] = [
+    [2027, 2808, 1190, 23, 172, 2237, 2434, 361],
+    [573, 1345, 3028, 3126, 3200, 47, 2448, 2696],
+];
+
+/// NTT evaluations of `TESTCT1 + TESTCT2`
+pub const TESTSUMHAT: [[u64; N]; 2] = [
+    [508, 2246, 941, 1601, 471, 1692, 2165, 3171],
+    [893, 2170, 2271, 2506, 743, 177, 2660, 1466],
+];
+
+/// Builds the `Ciphertext` of a test vector.
+pub fn ciphertext(ct: [[u64; N]; 2]) -> Ciphertext {
+    let [c_0, c_1] = ct.map(|c| Poly::new(c.iter().map(|coeff| *coeff as i64).collect()));
+    Ciphertext {
+        c_0,
+        c_1,
+        q: Q as i64,
+        t: T,
+        level: 0,
+    }
+}